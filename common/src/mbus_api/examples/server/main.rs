@@ -86,7 +86,7 @@ async fn main() {
     }
 }
 
-async fn receive_v1(sub: &mut nats::asynk::Subscription, count: u64) {
+async fn receive_v1(sub: &mut BusSubscription, count: u64) {
     let message = &sub.next().await.unwrap();
     let message: ReceivedRawMessage = message.into();
     // notice that there is no type validation until we
@@ -100,7 +100,7 @@ async fn receive_v1(sub: &mut nats::asynk::Subscription, count: u64) {
         .unwrap();
 }
 
-async fn receive_v2(sub: &mut nats::asynk::Subscription, count: u64) {
+async fn receive_v2(sub: &mut BusSubscription, count: u64) {
     let message = &sub.next().await.unwrap();
     // notice that try_into can fail if the received type does not
     // match the received message
@@ -113,7 +113,7 @@ async fn receive_v2(sub: &mut nats::asynk::Subscription, count: u64) {
         .unwrap();
 }
 
-async fn receive_v3(sub: &mut nats::asynk::Subscription, count: u64) {
+async fn receive_v3(sub: &mut BusSubscription, count: u64) {
     let message = &sub.next().await.unwrap();
     let message: ReceivedMessageExt<DummyRequest, DummyReply> = message.try_into().unwrap();
     message
@@ -0,0 +1,161 @@
+use super::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc;
+
+/// In-process implementation of the Bus, which delivers the messages to the
+/// subscribers of the same process over tokio channels
+/// Useful to run the control plane services and their clients within the
+/// same binary, eg: for testing, without a message bus server
+/// Like with nats, every subscriber of a channel receives all of its messages
+/// and the first reply to a request is the one which is returned
+#[derive(Clone, Default)]
+pub struct LocalMessageBus {
+    timeout_options: TimeoutOptions,
+    subscribers: Arc<Mutex<HashMap<String, Vec<mpsc::UnboundedSender<BusMessage>>>>>,
+}
+
+impl LocalMessageBus {
+    /// New `LocalMessageBus` with no subscribers
+    pub fn new(timeout_options: TimeoutOptions) -> Self {
+        Self {
+            timeout_options,
+            subscribers: Default::default(),
+        }
+    }
+
+    /// Deliver a new message created by `message` to every subscriber of the
+    /// `channel`, returning how many subscribers it was delivered to
+    fn deliver(&self, channel: &str, message: impl Fn() -> BusMessage) -> usize {
+        let mut subscribers = self.subscribers.lock().expect("not poisoned");
+        match subscribers.get_mut(channel) {
+            Some(senders) => {
+                // subscriptions which have been dropped are closed
+                senders.retain(|sender| sender.send(message()).is_ok());
+                senders.len()
+            }
+            None => 0,
+        }
+    }
+}
+
+/// Replies through the channel the requester is waiting on
+struct LocalResponder(mpsc::Sender<Vec<u8>>);
+
+#[async_trait]
+impl BusResponder for LocalResponder {
+    async fn respond(&self, payload: &[u8]) -> io::Result<()> {
+        // only the first reply is received by the requester
+        self.0
+            .try_send(payload.to_vec())
+            .map_err(|error| io::Error::new(io::ErrorKind::BrokenPipe, error.to_string()))
+    }
+}
+
+/// Subscription to a channel of the `LocalMessageBus`
+struct LocalSubscription(mpsc::UnboundedReceiver<BusMessage>);
+
+#[async_trait]
+impl BusSubscriptionSource for LocalSubscription {
+    async fn next(&mut self) -> Option<BusMessage> {
+        self.0.recv().await
+    }
+}
+
+#[async_trait]
+impl Bus for LocalMessageBus {
+    async fn publish(&self, channel: Channel, message: &[u8]) -> BusResult<()> {
+        let channel = channel.to_string();
+        self.deliver(&channel, || BusMessage::new(&channel, message.to_vec()));
+        Ok(())
+    }
+
+    async fn send(&self, channel: Channel, message: &[u8]) -> BusResult<()> {
+        let channel = channel.to_string();
+        match self.deliver(&channel, || BusMessage::new(&channel, message.to_vec())) {
+            0 => Err(Error::Publish {
+                channel,
+                payload: String::from_utf8(Vec::from(message)),
+                source: io::Error::new(io::ErrorKind::NotConnected, "No subscribers"),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    async fn request(
+        &self,
+        channel: Channel,
+        message: &[u8],
+        req_options: Option<TimeoutOptions>,
+    ) -> BusResult<BusMessage> {
+        let channel = channel.to_string();
+        let reply = format!("{}.reply", channel);
+
+        let options = req_options
+            .clone()
+            .unwrap_or_else(|| self.timeout_options.clone());
+        let mut timeout = options.timeout;
+        let mut retries = 0;
+
+        loop {
+            let (sender, mut receiver) = mpsc::channel(1);
+            self.deliver(&channel, || {
+                BusMessage::new(&channel, message.to_vec())
+                    .with_responder(&reply, LocalResponder(sender.clone()))
+            });
+            // if there are no subscribers the sender is dropped here and we
+            // wait for the timeout all the same, in case the subscriber is
+            // still starting up
+            let pending = tokio::time::sleep(timeout);
+            tokio::pin!(pending);
+            drop(sender);
+            tokio::select! {
+                Some(data) = receiver.recv() => {
+                    return Ok(BusMessage::new(&reply, data));
+                }
+                _ = &mut pending => {}
+            }
+
+            if Some(retries) == options.max_retries {
+                let error = Error::RequestTimeout {
+                    channel,
+                    payload: String::from_utf8(Vec::from(message)),
+                    options,
+                };
+                tracing::error!("{}", error);
+                return Err(error);
+            }
+
+            tracing::debug!(
+                "Timeout after {:?} on {} - {} retries left",
+                timeout,
+                channel,
+                if let Some(max) = options.max_retries {
+                    (max - retries).to_string()
+                } else {
+                    "unlimited".to_string()
+                }
+            );
+
+            retries += 1;
+            timeout = std::cmp::min(options.timeout_step * retries, Duration::from_secs(10));
+        }
+    }
+
+    async fn flush(&self) -> BusResult<()> {
+        Ok(())
+    }
+
+    async fn subscribe(&self, channel: Channel) -> BusResult<BusSubscription> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.subscribers
+            .lock()
+            .expect("not poisoned")
+            .entry(channel.to_string())
+            .or_default()
+            .push(sender);
+        Ok(BusSubscription::new(LocalSubscription(receiver)))
+    }
+}
@@ -1,47 +1,39 @@
 use super::*;
 use nats::asynk::Connection;
-use once_cell::sync::OnceCell;
 use tracing::{info, warn};
 
-static NATS_MSG_BUS: OnceCell<NatsMessageBus> = OnceCell::new();
 /// Initialise the Nats Message Bus with the current tokio runtime
 /// (the runtime MUST be setup already or we will panic)
 pub fn message_bus_init_tokio(server: String) {
-    NATS_MSG_BUS.get_or_init(|| {
+    if !message_bus_initialised() {
         // Waits for the message bus to become ready
-        tokio::runtime::Handle::current().block_on(async {
+        let nc = tokio::runtime::Handle::current().block_on(async {
             NatsMessageBus::new(&server, BusOptions::new(), TimeoutOptions::new()).await
-        })
-    });
+        });
+        message_bus_init_bus(Box::new(nc));
+    }
 }
 /// Initialise the Nats Message Bus
 pub async fn message_bus_init(server: String) {
     let nc = NatsMessageBus::new(&server, BusOptions::new(), TimeoutOptions::new()).await;
-    NATS_MSG_BUS
-        .set(nc)
-        .ok()
-        .expect("Expect to be initialised only once");
+    if !message_bus_init_bus(Box::new(nc)) {
+        panic!("Expect to be initialised only once");
+    }
 }
 
 /// Initialise the Nats Message Bus with Options
-/// IGNORES all but the first initialisation of NATS_MSG_BUS
-pub async fn message_bus_init_options(server: String, timeouts: TimeoutOptions) {
-    if NATS_MSG_BUS.get().is_none() {
-        let nc = NatsMessageBus::new(&server, BusOptions::new(), timeouts).await;
-        NATS_MSG_BUS.set(nc).ok();
+/// IGNORES all but the first initialisation of the shared message bus
+pub async fn message_bus_init_options(
+    server: String,
+    bus_options: BusOptions,
+    timeouts: TimeoutOptions,
+) {
+    if !message_bus_initialised() {
+        let nc = NatsMessageBus::new(&server, bus_options, timeouts).await;
+        message_bus_init_bus(Box::new(nc));
     }
 }
 
-/// Get the static `NatsMessageBus` as a boxed `MessageBus`
-pub fn bus() -> DynBus {
-    Box::new(
-        NATS_MSG_BUS
-            .get()
-            .expect("Shared message bus should be initialised before use.")
-            .clone(),
-    )
-}
-
 // Would we want to have both sync and async clients?
 /// Nats implementation of the Bus
 #[derive(Clone)]
@@ -50,16 +42,22 @@ pub struct NatsMessageBus {
     connection: Connection,
 }
 impl NatsMessageBus {
-    /// Connect to the provided server
+    /// Connect to the provided server using the given connection options
+    /// The server may be a comma separated list of urls, in which case the
+    /// nats library fails over between them
     /// Logs the first error and quietly continues retrying forever
-    pub async fn connect(server: &str) -> Connection {
-        info!("Connecting to the nats server {}...", server);
+    pub async fn connect(server: &str, bus_options: &BusOptions) -> Connection {
+        info!(
+            "Connecting to the nats server {} with {:?}...",
+            server, bus_options
+        );
         // We retry in a loop until successful. Once connected the nats
         // library will handle reconnections for us.
         let interval = std::time::Duration::from_millis(500);
         let mut log_error = true;
         loop {
-            match BusOptions::new()
+            match bus_options
+                .nats_options()
                 .max_reconnects(None)
                 .connect_async(server)
                 .await
@@ -83,12 +81,12 @@ impl NatsMessageBus {
     /// Connects to the server and returns a new `NatsMessageBus`
     pub async fn new(
         server: &str,
-        _bus_options: BusOptions,
+        bus_options: BusOptions,
         timeout_options: TimeoutOptions,
     ) -> Self {
         Self {
             timeout_options,
-            connection: Self::connect(server).await,
+            connection: Self::connect(server, &bus_options).await,
         }
     }
 }
@@ -128,7 +126,7 @@ impl Bus for NatsMessageBus {
 
             let result = tokio::time::timeout(timeout, request).await;
             if let Ok(r) = result {
-                return r.context(Publish {
+                return r.map(BusMessage::from).context(Publish {
                     channel: channel.to_string(),
                     payload: String::from_utf8(Vec::from(message)),
                 });
@@ -167,8 +165,38 @@ impl Bus for NatsMessageBus {
         self.connection
             .subscribe(&channel.to_string())
             .await
+            .map(BusSubscription::new)
             .context(Subscribe {
                 channel: channel.to_string(),
             })
     }
 }
+
+/// Replies through the nats message itself
+struct NatsResponder(nats::asynk::Message);
+
+#[async_trait]
+impl BusResponder for NatsResponder {
+    async fn respond(&self, payload: &[u8]) -> io::Result<()> {
+        self.0.respond(payload).await
+    }
+}
+
+impl From<nats::asynk::Message> for BusMessage {
+    fn from(message: nats::asynk::Message) -> Self {
+        let bus_message = BusMessage::new(message.subject.clone(), message.data.clone());
+        match message.reply.clone() {
+            Some(reply) => bus_message.with_responder(reply, NatsResponder(message)),
+            None => bus_message,
+        }
+    }
+}
+
+#[async_trait]
+impl BusSubscriptionSource for nats::asynk::Subscription {
+    async fn next(&mut self) -> Option<BusMessage> {
+        nats::asynk::Subscription::next(self)
+            .await
+            .map(BusMessage::from)
+    }
+}
@@ -5,11 +5,13 @@ pub use crate::mbus_api::{v0::*, Message};
 use crate::{
     mbus_api::{ReplyError, ReplyErrorKind, ResourceKind},
     types::v0::message_bus::{
-        AddNexusChild, AddVolumeNexus, Child, CreateNexus, CreatePool, CreateReplica, CreateVolume,
-        DestroyNexus, DestroyPool, DestroyReplica, DestroyVolume, Filter, GetBlockDevices,
-        GetNexuses, GetNodes, GetPools, GetReplicas, GetSpecs, GetStates, GetVolumes,
-        JsonGrpcRequest, Nexus, Node, NodeId, Pool, RemoveNexusChild, RemoveVolumeNexus, Replica,
-        ShareNexus, ShareReplica, Specs, States, UnshareNexus, UnshareReplica, Volume,
+        AddNexusChild, AddVolumeNexus, Child, ConfigRevision, CreateNexus, CreatePool,
+        CreateReplica, CreateVolume, DestroyNexus, DestroyPool, DestroyReplica, DestroyVolume,
+        Filter, GetBlockDevices, GetConfigRevision, GetConfigRevisions, GetNexuses, GetNodeHistory,
+        GetNodes, GetPools, GetReplicas, GetSpecs, GetStates, GetVolumes, JsonGrpcRequest, Nexus,
+        Node, NodeId, NodeStateTransition, Pool, RemoveNexusChild, RemoveVolumeNexus, Replica,
+        RollbackConfig, SetNodeLabels, SetNodesConfig, ShareNexus, ShareReplica, Specs, States,
+        UnshareNexus, UnshareReplica, Volume,
     },
 };
 use async_trait::async_trait;
@@ -66,6 +68,13 @@ pub trait MessageBusTrait: Sized {
         only_one!(nodes, ResourceKind::Node)
     }
 
+    /// Get the state transitions of the node with `id`
+    #[tracing::instrument(level = "debug", err)]
+    async fn get_node_history(id: &NodeId) -> BusResult<Vec<NodeStateTransition>> {
+        let request = GetNodeHistory { node: id.clone() };
+        Ok(request.request().await?.into_inner())
+    }
+
     /// Get pool with filter
     #[tracing::instrument(level = "debug", err)]
     async fn get_pool(filter: Filter) -> BusResult<Pool> {
@@ -251,6 +260,37 @@ pub trait MessageBusTrait: Sized {
     async fn get_states(request: GetStates) -> BusResult<States> {
         Ok(request.request().await?)
     }
+
+    /// Get the revisions of a config of a node, oldest first
+    #[tracing::instrument(level = "debug", err)]
+    async fn get_config_revisions(request: GetConfigRevisions) -> BusResult<ConfigRevisions> {
+        Ok(request.request().await?)
+    }
+
+    /// Get a revision of a config of a node
+    #[tracing::instrument(level = "debug", err)]
+    async fn get_config_revision(request: GetConfigRevision) -> BusResult<ConfigRevision> {
+        Ok(request.request().await?)
+    }
+
+    /// Roll back a config of a node to an earlier revision
+    #[tracing::instrument(level = "debug", err)]
+    async fn rollback_config(request: RollbackConfig) -> BusResult<ConfigRevision> {
+        Ok(request.request().await?)
+    }
+
+    /// Set a config of the nodes selected by their labels, reporting which
+    /// nodes acknowledged the new revision
+    #[tracing::instrument(level = "debug", err)]
+    async fn set_nodes_config(request: SetNodesConfig) -> BusResult<NodeConfigAcks> {
+        Ok(request.request().await?)
+    }
+
+    /// Set the labels of a node, by which its configs may be set
+    #[tracing::instrument(level = "debug", err)]
+    async fn set_node_labels(request: SetNodeLabels) -> BusResult<()> {
+        Ok(request.request().await?)
+    }
 }
 
 /// Implementation of the bus interface trait
@@ -337,6 +377,7 @@ mod tests {
                 id: mayastor.clone(),
                 grpc_endpoint: "0.0.0.0:10124".to_string(),
                 state: NodeState::Online,
+                ..Default::default()
             }
         );
         let node = MessageBus::get_node(mayastor).await?;
@@ -346,6 +387,7 @@ mod tests {
                 id: mayastor.clone(),
                 grpc_endpoint: "0.0.0.0:10124".to_string(),
                 state: NodeState::Online,
+                ..Default::default()
             }
         );
 
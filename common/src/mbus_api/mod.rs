@@ -3,6 +3,7 @@
 //! plane services and mayastor
 //! We could split these out further into categories when they start to grow
 
+mod mbus_local;
 mod mbus_nats;
 /// Message bus client interface
 pub mod message_bus;
@@ -19,15 +20,21 @@ use crate::types::{
 };
 use async_trait::async_trait;
 use dyn_clonable::clonable;
+pub use mbus_local::LocalMessageBus;
 pub use mbus_nats::{
-    bus, message_bus_init, message_bus_init_options, message_bus_init_tokio, NatsMessageBus,
+    message_bus_init, message_bus_init_options, message_bus_init_tokio, NatsMessageBus,
 };
+use once_cell::sync::OnceCell;
 pub use receive::*;
 pub use send::*;
 use serde::{de::StdError, Deserialize, Serialize};
 use smol::io;
 use snafu::{ResultExt, Snafu};
-use std::{fmt::Debug, marker::PhantomData, str::FromStr, time::Duration};
+use std::{
+    collections::HashMap, fmt::Debug, marker::PhantomData, path::PathBuf, str::FromStr,
+    time::Duration,
+};
+use structopt::StructOpt;
 use strum_macros::{AsRefStr, ToString};
 
 /// Result wrapper for send/receive
@@ -233,17 +240,47 @@ pub trait Message {
 #[derive(Serialize, Deserialize, Debug)]
 struct Preamble {
     pub(crate) id: MessageId,
+    #[serde(default)]
+    pub(crate) trace_context: TraceContext,
 }
 
 /// Unsolicited (send) messages carry the message identifier, the sender
-/// identifier and finally the message payload itself
+/// identifier, the trace context of the sender and finally the message
+/// payload itself
 #[derive(Serialize, Deserialize)]
 struct SendPayload<T> {
     pub(crate) id: MessageId,
     pub(crate) sender: SenderId,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub(crate) trace_context: TraceContext,
     pub(crate) data: T,
 }
 
+/// W3C trace context headers (`traceparent` and `tracestate`) of the span
+/// which sent a message, so that the spans of the receiver can be linked to
+/// the trace of the sender
+pub type TraceContext = HashMap<String, String>;
+
+/// Trace context of the current span, injected by the global text map
+/// propagator (empty unless a propagator has been set)
+pub fn current_trace_context() -> TraceContext {
+    use opentelemetry::{propagation::TextMapPropagator, trace::TraceContextExt};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    let context = tracing::Span::current().context();
+    // spans which are not traced through the tracing crate (eg: the actix
+    // request spans) are only found in the OpenTelemetry context
+    let context = if context.has_active_span() {
+        context
+    } else {
+        opentelemetry::Context::current()
+    };
+    let mut trace_context = TraceContext::new();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut trace_context)
+    });
+    trace_context
+}
+
 /// All the different variants of Resources
 #[derive(Serialize, Deserialize, Debug, Clone, AsRefStr, ToString)]
 pub enum ResourceKind {
@@ -271,6 +308,8 @@ pub enum ResourceKind {
     Block,
     /// Watch
     Watch,
+    /// Node configuration
+    Config,
 }
 
 /// Error type which is returned over the bus
@@ -368,16 +407,126 @@ impl From<Error> for ReplyError {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ReplyPayload<T>(pub Result<T, ReplyError>);
 
-// todo: implement thin wrappers on these
-/// MessageBus raw Message
-pub type BusMessage = nats::asynk::Message;
-/// MessageBus subscription
-pub type BusSubscription = nats::asynk::Subscription;
-/// MessageBus configuration options
-pub type BusOptions = nats::Options;
+/// Replies back to the sender of a `BusMessage` through the transport which
+/// delivered the message
+#[async_trait]
+pub trait BusResponder: Send + Sync {
+    /// Send the reply `payload` back to the sender
+    async fn respond(&self, payload: &[u8]) -> io::Result<()>;
+}
+
+/// MessageBus raw Message, independent of the transport which delivered it
+pub struct BusMessage {
+    /// channel where the message was sent to
+    pub subject: String,
+    /// channel where the reply is sent to, if the sender requested one
+    pub reply: Option<String>,
+    /// raw message payload
+    pub data: Vec<u8>,
+    responder: Option<Box<dyn BusResponder>>,
+}
+
+impl BusMessage {
+    /// New message with no means of replying back to the sender
+    pub fn new(subject: impl Into<String>, data: Vec<u8>) -> Self {
+        Self {
+            subject: subject.into(),
+            reply: None,
+            data,
+            responder: None,
+        }
+    }
+    /// Message which replies back to the sender through the `responder`,
+    /// via the `reply` channel
+    pub fn with_responder(
+        mut self,
+        reply: impl Into<String>,
+        responder: impl BusResponder + 'static,
+    ) -> Self {
+        self.reply = Some(reply.into());
+        self.responder = Some(Box::new(responder));
+        self
+    }
+    /// Respond back to the sender with the raw `payload`
+    /// Fails if the sender did not request a reply
+    pub async fn respond(&self, payload: &[u8]) -> io::Result<()> {
+        match &self.responder {
+            Some(responder) => responder.respond(payload).await,
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No reply channel for the message",
+            )),
+        }
+    }
+}
+
+/// Source of the messages of a `BusSubscription`, specific to each transport
+#[async_trait]
+pub trait BusSubscriptionSource: Send {
+    /// Get the next message, or None if the subscription is closed
+    async fn next(&mut self) -> Option<BusMessage>;
+}
+
+/// MessageBus subscription, independent of the transport
+pub struct BusSubscription {
+    source: Box<dyn BusSubscriptionSource>,
+}
+
+impl BusSubscription {
+    /// New subscription which receives its messages from `source`
+    pub fn new(source: impl BusSubscriptionSource + 'static) -> Self {
+        Self {
+            source: Box::new(source),
+        }
+    }
+    /// Get the next message, or None if the subscription is closed
+    pub async fn next(&mut self) -> Option<BusMessage> {
+        self.source.next().await
+    }
+}
+
 /// Save on typing
 pub type DynBus = Box<dyn Bus>;
 
+static MSG_BUS: OnceCell<DynBus> = OnceCell::new();
+
+tokio::task_local! {
+    /// Message bus which is used in place of the shared one within a scope
+    static SCOPED_BUS: DynBus;
+}
+
+/// Initialise the shared message bus with any `Bus` implementation, eg: the
+/// `LocalMessageBus` which needs no message bus server
+/// IGNORES all but the first initialisation, returning whether it was set
+pub fn message_bus_init_bus(bus: DynBus) -> bool {
+    MSG_BUS.set(bus).is_ok()
+}
+
+/// Whether the shared message bus has been initialised, or is overridden
+/// within the current scope (see `with_bus`)
+pub fn message_bus_initialised() -> bool {
+    SCOPED_BUS.try_with(|_| ()).is_ok() || MSG_BUS.get().is_some()
+}
+
+/// Get the shared message bus as a boxed `Bus`, or the bus of the current
+/// scope, if any (see `with_bus`)
+pub fn bus() -> DynBus {
+    match SCOPED_BUS.try_with(|bus| bus.clone()) {
+        Ok(bus) => bus,
+        Err(_) => MSG_BUS
+            .get()
+            .expect("Shared message bus should be initialised before use.")
+            .clone(),
+    }
+}
+
+/// Run the `future` with the `bus` in place of the shared message bus, so that
+/// services within the same process may each use their own bus, eg: a
+/// `LocalMessageBus` when testing
+pub async fn with_bus<F: std::future::Future>(bus: DynBus, future: F) -> F::Output {
+    SCOPED_BUS.scope(bus, future).await
+}
+
 /// Timeout for receiving a reply to a request message
 /// Max number of retries until it gives up
 #[derive(Clone, Debug)]
@@ -439,6 +588,148 @@ impl TimeoutOptions {
     }
 }
 
+/// Message Bus connection options, eg: TLS and client credentials
+/// Can be flattened into the command line arguments of any binary which
+/// connects to the message bus
+#[derive(Clone, Default, StructOpt)]
+pub struct BusOptions {
+    /// Path to the CA certificate (PEM) used to verify the Nats server
+    /// Implies a TLS connection to the Nats server
+    #[structopt(long)]
+    nats_tls_ca: Option<PathBuf>,
+    /// Path to the client certificate (PEM) used for Nats TLS client
+    /// authentication
+    #[structopt(long, requires = "nats-tls-key")]
+    nats_tls_cert: Option<PathBuf>,
+    /// Path to the client private key (PEM) used for Nats TLS client
+    /// authentication
+    #[structopt(long, requires = "nats-tls-cert")]
+    nats_tls_key: Option<PathBuf>,
+    /// Require a TLS connection to the Nats server
+    #[structopt(long)]
+    nats_tls_required: bool,
+    /// User name used to authenticate with the Nats server
+    #[structopt(long, requires = "nats-password", conflicts_with_all = &["nats-token", "nats-creds"])]
+    nats_user: Option<String>,
+    /// Password used to authenticate with the Nats server
+    #[structopt(
+        long,
+        env = "NATS_PASSWORD",
+        hide_env_values = true,
+        requires = "nats-user"
+    )]
+    nats_password: Option<String>,
+    /// Token used to authenticate with the Nats server
+    #[structopt(
+        long,
+        env = "NATS_TOKEN",
+        hide_env_values = true,
+        conflicts_with = "nats-creds"
+    )]
+    nats_token: Option<String>,
+    /// Path to the Nats credentials file (user JWT and NKey seed)
+    #[structopt(long)]
+    nats_creds: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for BusOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // never leak the secrets into the logs
+        let redacted = |secret: &Option<String>| secret.as_ref().map(|_| "<redacted>");
+        f.debug_struct("BusOptions")
+            .field("nats_tls_ca", &self.nats_tls_ca)
+            .field("nats_tls_cert", &self.nats_tls_cert)
+            .field("nats_tls_key", &self.nats_tls_key)
+            .field("nats_tls_required", &self.nats_tls_required)
+            .field("nats_user", &self.nats_user)
+            .field("nats_password", &redacted(&self.nats_password))
+            .field("nats_token", &redacted(&self.nats_token))
+            .field("nats_creds", &self.nats_creds)
+            .finish()
+    }
+}
+
+impl BusOptions {
+    /// New options with default values (plain connection, no credentials)
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Verify the server with the given CA certificate (PEM), implies TLS
+    pub fn with_tls_ca(mut self, ca: impl Into<PathBuf>) -> Self {
+        self.nats_tls_ca = Some(ca.into());
+        self
+    }
+
+    /// Authenticate with the server using the given TLS client certificate
+    /// and private key (PEM)
+    pub fn with_tls_client_cert(
+        mut self,
+        cert: impl Into<PathBuf>,
+        key: impl Into<PathBuf>,
+    ) -> Self {
+        self.nats_tls_cert = Some(cert.into());
+        self.nats_tls_key = Some(key.into());
+        self
+    }
+
+    /// Require a TLS connection to the server
+    pub fn with_tls_required(mut self, required: bool) -> Self {
+        self.nats_tls_required = required;
+        self
+    }
+
+    /// Authenticate with the server using a user name and password
+    pub fn with_user_pass(mut self, user: &str, password: &str) -> Self {
+        self.nats_user = Some(user.to_string());
+        self.nats_password = Some(password.to_string());
+        self
+    }
+
+    /// Authenticate with the server using a token
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.nats_token = Some(token.to_string());
+        self
+    }
+
+    /// Authenticate with the server using a credentials file which contains
+    /// the user JWT and the NKey seed
+    pub fn with_credentials(mut self, creds: impl Into<PathBuf>) -> Self {
+        self.nats_creds = Some(creds.into());
+        self
+    }
+
+    /// Whether the connection to the server must use TLS
+    pub fn tls_required(&self) -> bool {
+        self.nats_tls_required || self.nats_tls_ca.is_some() || self.nats_tls_cert.is_some()
+    }
+
+    /// Nats connection options built from `self`
+    /// Credentials are picked in order: credentials file, user/password, token
+    pub(crate) fn nats_options(&self) -> nats::Options {
+        let options = match (
+            &self.nats_creds,
+            &self.nats_user,
+            &self.nats_password,
+            &self.nats_token,
+        ) {
+            (Some(creds), _, _, _) => nats::Options::with_credentials(creds),
+            (None, Some(user), Some(password), _) => nats::Options::with_user_pass(user, password),
+            (None, _, _, Some(token)) => nats::Options::with_token(token),
+            _ => nats::Options::new(),
+        };
+        let options = match &self.nats_tls_ca {
+            Some(ca) => options.add_root_certificate(ca),
+            None => options,
+        };
+        let options = match (&self.nats_tls_cert, &self.nats_tls_key) {
+            (Some(cert), Some(key)) => options.client_cert(cert, key),
+            _ => options,
+        };
+        options.tls_required(self.tls_required())
+    }
+}
+
 /// Messaging Bus trait with "generic" publish and request/reply semantics
 #[async_trait]
 #[clonable]
@@ -128,12 +128,29 @@ impl<'a> ReceivedRawMessage<'a> {
     /// Get the identifier of this message.
     /// May fail if the raw data cannot be deserialized into the preamble.
     pub fn id(&self) -> BusResult<MessageId> {
-        let preamble: Preamble =
-            serde_json::from_slice(&self.bus_msg.data).context(DeserializeSend {
-                receiver: std::any::type_name::<Preamble>(),
-                payload: String::from_utf8(self.bus_msg.data.clone()),
-            })?;
-        Ok(preamble.id)
+        Ok(self.preamble()?.id)
+    }
+
+    /// Get the trace context of the sender, as an OpenTelemetry context which
+    /// can be used as the parent of the spans handling this message.
+    /// Messages without a trace context yield an empty context, ie: the
+    /// handling spans start a new trace.
+    pub fn trace_context(&self) -> opentelemetry::Context {
+        use opentelemetry::propagation::TextMapPropagator;
+        let trace_context = self
+            .preamble()
+            .map(|preamble| preamble.trace_context)
+            .unwrap_or_default();
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(&trace_context)
+        })
+    }
+
+    fn preamble(&self) -> BusResult<Preamble> {
+        serde_json::from_slice(&self.bus_msg.data).context(DeserializeSend {
+            receiver: std::any::type_name::<Preamble>(),
+            payload: String::from_utf8(self.bus_msg.data.clone()),
+        })
     }
 
     /// Channel where this message traversed
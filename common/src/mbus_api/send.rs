@@ -280,6 +280,7 @@ where
                 id: payload.id(),
                 data: payload,
                 sender: Self::name(),
+                trace_context: current_trace_context(),
             },
             reply_type: Default::default(),
             bus,
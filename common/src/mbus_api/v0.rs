@@ -25,6 +25,28 @@ macro_rules! impl_channel_id {
 
 bus_impl_message_all!(Liveness, Liveness, (), Default);
 
+bus_impl_message_all!(ConfigUpdate, ConfigUpdate, (), Kiiss);
+
+bus_impl_message_all!(ConfigGetCurrent, ConfigGetCurrent, ReplyConfig, Kiiss);
+
+bus_impl_vector_request!(ConfigRevisions, ConfigRevision);
+bus_impl_message_all!(
+    GetConfigRevisions,
+    GetConfigRevisions,
+    ConfigRevisions,
+    Kiiss
+);
+
+bus_impl_message_all!(GetConfigRevision, GetConfigRevision, ConfigRevision, Kiiss);
+
+bus_impl_message_all!(RollbackConfig, RollbackConfig, ConfigRevision, Kiiss);
+
+bus_impl_vector_request!(NodeConfigAcks, NodeConfigAck);
+bus_impl_message_all!(SetNodesConfig, SetNodesConfig, NodeConfigAcks, Kiiss);
+bus_impl_message_all!(SetNodeLabels, SetNodeLabels, (), Kiiss);
+
+bus_impl_message_all!(ConfigChanged, ConfigChanged, (), Kiiss);
+
 bus_impl_message_all!(Register, Register, (), Registry);
 
 bus_impl_message_all!(Deregister, Deregister, (), Registry);
@@ -32,6 +54,9 @@ bus_impl_message_all!(Deregister, Deregister, (), Registry);
 bus_impl_vector_request!(Nodes, Node);
 bus_impl_message_all!(GetNodes, GetNodes, Nodes, Node);
 
+bus_impl_vector_request!(NodeHistory, NodeStateTransition);
+bus_impl_message_all!(GetNodeHistory, GetNodeHistory, NodeHistory, Node);
+
 bus_impl_message_all!(CreatePool, CreatePool, Pool, Pool);
 
 bus_impl_message_all!(DestroyPool, DestroyPool, (), Pool);
@@ -96,6 +121,17 @@ bus_impl_message_all!(GetWatchers, GetWatches, Watches, Watcher);
 
 bus_impl_message_all!(DeleteWatch, DeleteWatch, (), Watcher);
 
+bus_impl_message_all!(ResourceEvent, ResourceEvent, (), Events);
+
+bus_impl_vector_request!(ResourceEvents, ResourceEvent);
+
+bus_impl_message_all!(
+    GetResourceEvents,
+    GetResourceEvents,
+    ResourceEvents,
+    Watcher
+);
+
 bus_impl_message_all!(GetSpecs, GetSpecs, Specs, Registry);
 
 bus_impl_message_all!(GetStates, GetStates, States, Registry);
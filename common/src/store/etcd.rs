@@ -1,10 +1,10 @@
 use crate::types::v0::store::definitions::{
-    Connect, Delete, DeserialiseValue, Get, GetPrefix, KeyString, ObjectKey, Put, SerialiseValue,
-    StorableObject, Store, StoreError, StoreError::MissingEntry, StoreKey, StoreValue, ValueString,
-    Watch, WatchEvent,
+    Connect, Delete, DeserialiseValue, Get, GetPrefix, KeyString, ObjectKey, PrefixWatchEvent, Put,
+    SerialiseValue, StorableObject, Store, StoreError, StoreError::MissingEntry, StoreKey,
+    StorePrefixWatchReceiver, StoreValue, ValueString, Watch, WatchEvent,
 };
 use async_trait::async_trait;
-use etcd_client::{Client, EventType, GetOptions, KeyValue, WatchStream, Watcher};
+use etcd_client::{Client, EventType, GetOptions, KeyValue, WatchOptions, WatchStream, Watcher};
 use serde_json::Value;
 use snafu::ResultExt;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
@@ -152,6 +152,27 @@ impl Store for Etcd {
         Ok(receiver)
     }
 
+    /// 'Watch' all the etcd entries with the given key prefix, optionally from
+    /// an earlier `revision`, so that no change is missed across reconnections.
+    async fn watch_prefix(
+        &mut self,
+        key_prefix: &str,
+        revision: Option<i64>,
+    ) -> Result<StorePrefixWatchReceiver, StoreError> {
+        let (sender, receiver) = channel(100);
+        let mut options = WatchOptions::new().with_prefix();
+        if let Some(revision) = revision {
+            options = options.with_start_revision(revision);
+        }
+        let (watcher, stream) = self
+            .0
+            .watch(key_prefix, Some(options))
+            .await
+            .context(Watch { key: key_prefix })?;
+        watch_prefix(watcher, stream, sender);
+        Ok(receiver)
+    }
+
     async fn online(&mut self) -> bool {
         self.0.status().await.is_ok()
     }
@@ -191,7 +212,9 @@ fn watch(
                     EventType::Put => {
                         if let Some(kv) = event.kv() {
                             let result = match deserialise_kv(kv) {
-                                Ok((key, value)) => Ok(WatchEvent::Put(key, value)),
+                                Ok((key, value)) => {
+                                    Ok(WatchEvent::Put(key, value, kv.mod_revision()))
+                                }
                                 Err(e) => Err(e),
                             };
                             if sender.send(result).await.is_err() {
@@ -205,7 +228,8 @@ fn watch(
                         // Send only fails if the receiver is closed. We are
                         // returning here anyway, so the error doesn't need to
                         // be handled.
-                        let _ = sender.send(Ok(WatchEvent::Delete)).await;
+                        let revision = event.kv().map(KeyValue::mod_revision).unwrap_or_default();
+                        let _ = sender.send(Ok(WatchEvent::Delete(revision))).await;
                         return;
                     }
                 }
@@ -214,6 +238,59 @@ fn watch(
     });
 }
 
+/// Watch for events on all the entries of a key prefix in the key-value store.
+/// Unlike `watch`, the deletion of an entry does not stop the watch, which
+/// lasts until either the stream or the receiver is closed.
+fn watch_prefix(
+    watcher: Watcher,
+    mut stream: WatchStream,
+    sender: Sender<Result<PrefixWatchEvent, StoreError>>,
+) {
+    tokio::spawn(async move {
+        // keep the watcher alive for as long as we're watching
+        let _watcher = watcher;
+        loop {
+            let response = match stream.message().await {
+                Ok(Some(response)) => response,
+                // stream cancelled
+                Ok(None) => return,
+                Err(e) => {
+                    tracing::error!("Failed to get message with error {}", e);
+                    return;
+                }
+            };
+
+            for event in response.events() {
+                let kv = match event.kv() {
+                    Some(kv) => kv,
+                    None => continue,
+                };
+                let result = match event.event_type() {
+                    EventType::Put => deserialise_kv(kv).map(|(key, value)| PrefixWatchEvent {
+                        key,
+                        revision: kv.mod_revision(),
+                        value: Some(value),
+                    }),
+                    EventType::Delete => {
+                        kv.key_str()
+                            .context(KeyString {})
+                            .map(|key| PrefixWatchEvent {
+                                key: key.to_string(),
+                                revision: kv.mod_revision(),
+                                value: None,
+                            })
+                    }
+                };
+                if sender.send(result).await.is_err() {
+                    // Send only fails if the receiver is closed, so just stop
+                    // watching.
+                    return;
+                }
+            }
+        }
+    });
+}
+
 /// Deserialise a key-value pair into serde_json::Value representations.
 fn deserialise_kv(kv: &KeyValue) -> Result<(String, Value), StoreError> {
     let key_str = kv.key_str().context(KeyString {})?.to_string();
@@ -0,0 +1,205 @@
+use crate::types::v0::store::definitions::{
+    ObjectKey, PrefixWatchEvent, SerialiseValue, StorableObject, Store, StoreError,
+    StoreError::MissingEntry, StoreKey, StorePrefixWatchReceiver, StoreValue, StoreWatchReceiver,
+    WatchEvent,
+};
+use async_trait::async_trait;
+use serde_json::Value;
+use snafu::ResultExt;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::mpsc::{channel, Sender};
+
+/// Size of the channel of each watch
+const WATCH_CHANNEL_SIZE: usize = 100;
+
+/// In-memory key-value store which is not persisted anywhere
+/// Clones share the same entries, which are kept in the same order as in etcd
+/// so that prefix reads and watches behave alike, eg: to run the control plane
+/// and its clients within a single process when testing
+#[derive(Clone, Debug, Default)]
+pub struct Memory(Arc<Mutex<MemoryInner>>);
+
+#[derive(Debug, Default)]
+struct MemoryInner {
+    /// store revision of the last change
+    revision: i64,
+    /// the entries by their key, along with the revision of their last change
+    entries: BTreeMap<String, (Value, i64)>,
+    /// every change so far, so a prefix watch can start from an earlier
+    /// revision
+    history: Vec<PrefixWatchEvent>,
+    /// watches of a single key
+    key_watches: Vec<(String, Sender<Result<WatchEvent, StoreError>>)>,
+    /// watches of a key prefix
+    prefix_watches: Vec<(String, Sender<Result<PrefixWatchEvent, StoreError>>)>,
+}
+
+impl Memory {
+    /// Create a new empty in-memory store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn put(&self, key: String, value: Value) {
+        let mut inner = self.0.lock().expect("not poisoned");
+        inner.revision += 1;
+        let revision = inner.revision;
+        inner.entries.insert(key.clone(), (value.clone(), revision));
+        inner.notify(key, Some(value), revision);
+    }
+
+    fn get(&self, key: String) -> Result<Value, StoreError> {
+        let inner = self.0.lock().expect("not poisoned");
+        match inner.entries.get(&key) {
+            Some((value, _)) => Ok(value.clone()),
+            None => Err(MissingEntry { key }),
+        }
+    }
+
+    fn watch(&self, key: String) -> StoreWatchReceiver {
+        let (sender, receiver) = channel(WATCH_CHANNEL_SIZE);
+        let mut inner = self.0.lock().expect("not poisoned");
+        inner.key_watches.push((key, sender));
+        receiver
+    }
+}
+
+impl MemoryInner {
+    /// Signal the change of the entry with the `key` at the `revision` to
+    /// the watches of the key, forgetting those which are closed
+    /// A watch of a single key stops once the entry is deleted, as with etcd,
+    /// and a watch which falls behind is closed so it may be reissued from
+    /// its last seen revision
+    fn notify(&mut self, key: String, value: Option<Value>, revision: i64) {
+        self.key_watches.retain(|(watched, sender)| {
+            if watched != &key {
+                return true;
+            }
+            match &value {
+                Some(value) => sender
+                    .try_send(Ok(WatchEvent::Put(key.clone(), value.clone(), revision)))
+                    .is_ok(),
+                None => {
+                    let _ = sender.try_send(Ok(WatchEvent::Delete(revision)));
+                    false
+                }
+            }
+        });
+        self.prefix_watches.retain(|(prefix, sender)| {
+            if !key.starts_with(prefix.as_str()) {
+                return true;
+            }
+            sender
+                .try_send(Ok(PrefixWatchEvent {
+                    key: key.clone(),
+                    revision,
+                    value: value.clone(),
+                }))
+                .is_ok()
+        });
+        self.history.push(PrefixWatchEvent {
+            key,
+            revision,
+            value,
+        });
+    }
+}
+
+#[async_trait]
+impl Store for Memory {
+    async fn put_kv<K: StoreKey, V: StoreValue>(
+        &mut self,
+        key: &K,
+        value: &V,
+    ) -> Result<(), StoreError> {
+        let value = serde_json::to_value(value).context(SerialiseValue)?;
+        self.put(key.to_string(), value);
+        Ok(())
+    }
+
+    async fn get_kv<K: StoreKey>(&mut self, key: &K) -> Result<Value, StoreError> {
+        self.get(key.to_string())
+    }
+
+    async fn delete_kv<K: StoreKey>(&mut self, key: &K) -> Result<(), StoreError> {
+        let key = key.to_string();
+        let mut inner = self.0.lock().expect("not poisoned");
+        if inner.entries.remove(&key).is_some() {
+            inner.revision += 1;
+            let revision = inner.revision;
+            inner.notify(key, None, revision);
+        }
+        Ok(())
+    }
+
+    async fn watch_kv<K: StoreKey>(&mut self, key: &K) -> Result<StoreWatchReceiver, StoreError> {
+        Ok(self.watch(key.to_string()))
+    }
+
+    async fn put_obj<O: StorableObject>(&mut self, object: &O) -> Result<(), StoreError> {
+        let value = serde_json::to_value(object).context(SerialiseValue)?;
+        self.put(object.key().key(), value);
+        Ok(())
+    }
+
+    async fn get_obj<O: StorableObject>(&mut self, key: &O::Key) -> Result<O, StoreError> {
+        let value = self.get(key.key())?;
+        serde_json::from_value(value.clone()).map_err(|source| StoreError::DeserialiseValue {
+            value: value.to_string(),
+            source,
+        })
+    }
+
+    async fn get_values_prefix(
+        &mut self,
+        key_prefix: &str,
+    ) -> Result<Vec<(String, Value)>, StoreError> {
+        let inner = self.0.lock().expect("not poisoned");
+        Ok(inner
+            .entries
+            .range(key_prefix.to_string() ..)
+            .take_while(|(key, _)| key.starts_with(key_prefix))
+            .map(|(key, (value, _))| (key.clone(), value.clone()))
+            .collect())
+    }
+
+    async fn watch_obj<K: ObjectKey>(&mut self, key: &K) -> Result<StoreWatchReceiver, StoreError> {
+        Ok(self.watch(key.key()))
+    }
+
+    /// Unlike etcd, the whole history is kept so a watch may start from any
+    /// earlier `revision`
+    async fn watch_prefix(
+        &mut self,
+        key_prefix: &str,
+        revision: Option<i64>,
+    ) -> Result<StorePrefixWatchReceiver, StoreError> {
+        let mut inner = self.0.lock().expect("not poisoned");
+        let replay: Vec<PrefixWatchEvent> = match revision {
+            Some(revision) => inner
+                .history
+                .iter()
+                .filter(|event| event.revision >= revision && event.key.starts_with(key_prefix))
+                .map(|event| PrefixWatchEvent {
+                    key: event.key.clone(),
+                    revision: event.revision,
+                    value: event.value.clone(),
+                })
+                .collect(),
+            None => vec![],
+        };
+        let (sender, receiver) = channel(WATCH_CHANNEL_SIZE.max(replay.len()));
+        for event in replay {
+            let _ = sender.try_send(Ok(event));
+        }
+        inner.prefix_watches.push((key_prefix.to_string(), sender));
+        Ok(receiver)
+    }
+
+    async fn online(&mut self) -> bool {
+        true
+    }
+}
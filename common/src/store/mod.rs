@@ -1 +1,95 @@
 pub mod etcd;
+pub mod memory;
+
+use crate::types::v0::store::definitions::{
+    ObjectKey, StorableObject, Store, StoreError, StoreKey, StorePrefixWatchReceiver, StoreValue,
+    StoreWatchReceiver,
+};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Persistent store of the control plane, which is etcd unless running
+/// within a single process, eg: when testing
+#[derive(Clone, Debug)]
+pub enum StoreEngine {
+    Etcd(etcd::Etcd),
+    Memory(memory::Memory),
+}
+
+impl From<etcd::Etcd> for StoreEngine {
+    fn from(store: etcd::Etcd) -> Self {
+        Self::Etcd(store)
+    }
+}
+impl From<memory::Memory> for StoreEngine {
+    fn from(store: memory::Memory) -> Self {
+        Self::Memory(store)
+    }
+}
+
+/// Forward the store operation to the engine in use
+macro_rules! engine {
+    ($self:ident.$method:ident($($arg:expr),*)) => {
+        match $self {
+            Self::Etcd(store) => store.$method($($arg),*).await,
+            Self::Memory(store) => store.$method($($arg),*).await,
+        }
+    };
+}
+
+#[async_trait]
+impl Store for StoreEngine {
+    async fn put_kv<K: StoreKey, V: StoreValue>(
+        &mut self,
+        key: &K,
+        value: &V,
+    ) -> Result<(), StoreError> {
+        engine!(self.put_kv(key, value))
+    }
+
+    async fn get_kv<K: StoreKey>(&mut self, key: &K) -> Result<Value, StoreError> {
+        engine!(self.get_kv(key))
+    }
+
+    async fn delete_kv<K: StoreKey>(&mut self, key: &K) -> Result<(), StoreError> {
+        engine!(self.delete_kv(key))
+    }
+
+    async fn watch_kv<K: StoreKey>(&mut self, key: &K) -> Result<StoreWatchReceiver, StoreError> {
+        engine!(self.watch_kv(key))
+    }
+
+    async fn put_obj<O: StorableObject>(&mut self, object: &O) -> Result<(), StoreError> {
+        engine!(self.put_obj(object))
+    }
+
+    async fn get_obj<O: StorableObject>(&mut self, key: &O::Key) -> Result<O, StoreError> {
+        match self {
+            Self::Etcd(store) => store.get_obj::<O>(key).await,
+            Self::Memory(store) => store.get_obj::<O>(key).await,
+        }
+    }
+
+    async fn get_values_prefix(
+        &mut self,
+        key_prefix: &str,
+    ) -> Result<Vec<(String, Value)>, StoreError> {
+        engine!(self.get_values_prefix(key_prefix))
+    }
+
+    async fn watch_obj<K: ObjectKey>(&mut self, key: &K) -> Result<StoreWatchReceiver, StoreError> {
+        engine!(self.watch_obj(key))
+    }
+
+    async fn watch_prefix(
+        &mut self,
+        key_prefix: &str,
+        revision: Option<i64>,
+    ) -> Result<StorePrefixWatchReceiver, StoreError> {
+        engine!(self.watch_prefix(key_prefix, revision))
+    }
+
+    async fn online(&mut self) -> bool {
+        engine!(self.online())
+    }
+}
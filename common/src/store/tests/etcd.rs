@@ -81,7 +81,9 @@ async fn etcd() {
         .recv_timeout(Duration::from_secs(1))
         .expect("Timed out waiting for message");
     let result: TestStruct = match msg {
-        WatchEvent::Put(_k, v) => serde_json::from_value(v).expect("Failed to deserialise value"),
+        WatchEvent::Put(_k, v, _revision) => {
+            serde_json::from_value(v).expect("Failed to deserialise value")
+        }
         _ => panic!("Expected a 'put' event"),
     };
     assert_eq!(result, data);
@@ -96,7 +98,7 @@ async fn etcd() {
         .recv_timeout(Duration::from_secs(1))
         .expect("Timed out waiting for message");
     match msg {
-        WatchEvent::Delete => {
+        WatchEvent::Delete(_revision) => {
             // The entry is deleted. Let's check that a subsequent 'get' fails.
             store
                 .get_kv(&key)
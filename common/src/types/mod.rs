@@ -19,6 +19,9 @@ pub mod v0;
 pub enum Channel {
     /// Version 0 of the Channels
     v0(ChannelVs),
+    /// Subject outside of the versioned channels, eg: on which the watch
+    /// notifications are published for the watchers which sit on the bus
+    Subject(String),
 }
 
 impl FromStr for Channel {
@@ -39,6 +42,7 @@ impl ToString for Channel {
     fn to_string(&self) -> String {
         match self {
             Self::v0(channel) => format!("v0/{}", channel.to_string()),
+            Self::Subject(subject) => subject.clone(),
         }
     }
 }
@@ -0,0 +1,189 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use strum_macros::{EnumString, ToString};
+
+/// Mayastor configurations
+/// Currently, we have the global mayastor config and the child states config
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash, EnumString, ToString)]
+#[strum(serialize_all = "camelCase")]
+#[serde(rename_all = "camelCase")]
+pub enum Config {
+    /// Mayastor global config
+    MayastorConfig,
+    /// Mayastor child states config
+    ChildStatesConfig,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Config::MayastorConfig
+    }
+}
+
+/// Config Messages
+///
+/// Update mayastor configuration
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigUpdate {
+    /// type of config being updated
+    pub kind: Config,
+    /// actual config data
+    pub data: Vec<u8>,
+}
+
+/// Request message configuration used by mayastor to request configuration
+/// from a control plane service
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigGetCurrent {
+    /// type of config requested
+    pub kind: Config,
+}
+
+/// Reply message configuration returned by a control plane service to mayastor
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplyConfig {
+    /// config data
+    pub config: Vec<u8>,
+}
+
+/// Numbered revision of a config of a node
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRevision {
+    /// revision number, incremented with every update of the config
+    pub revision: u64,
+    /// when the revision was made
+    pub timestamp: std::time::SystemTime,
+    /// the earlier revision which this one restored, if it's a rollback
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollback_of: Option<u64>,
+    /// config data
+    pub data: Vec<u8>,
+}
+
+impl ConfigRevision {
+    /// New revision `revision` of the config `data`, made now
+    pub fn new(revision: u64, data: Vec<u8>, rollback_of: Option<u64>) -> Self {
+        Self {
+            revision,
+            timestamp: std::time::SystemTime::now(),
+            rollback_of,
+            data,
+        }
+    }
+}
+
+impl From<&ConfigRevision> for models::NodeConfigRevision {
+    fn from(src: &ConfigRevision) -> Self {
+        Self {
+            data: None,
+            revision: src.revision as i64,
+            rollback_of: src.rollback_of.map(|revision| revision as i64),
+            size: src.data.len() as i64,
+            timestamp: humantime::format_rfc3339(src.timestamp).to_string(),
+        }
+    }
+}
+
+/// Get the revisions of a config of a node, oldest first
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetConfigRevisions {
+    /// id of the mayastor instance
+    pub node: NodeId,
+    /// type of config
+    pub kind: Config,
+}
+
+/// Get a revision of a config of a node
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetConfigRevision {
+    /// id of the mayastor instance
+    pub node: NodeId,
+    /// type of config
+    pub kind: Config,
+    /// revision number
+    pub revision: u64,
+}
+
+/// Roll back a config of a node to an earlier revision, which is restored as
+/// a new revision
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RollbackConfig {
+    /// id of the mayastor instance
+    pub node: NodeId,
+    /// type of config
+    pub kind: Config,
+    /// revision number to roll back to
+    pub revision: u64,
+}
+
+/// New revision of a config, pushed by the kiiss agent to the node whose
+/// config changed, on the subject of the node given by `ConfigChanged::channel`.
+/// The node acknowledges the revision by replying to it.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChanged {
+    /// type of config which changed
+    pub kind: Config,
+    /// number of the new revision
+    pub revision: u64,
+    /// config data of the new revision
+    pub data: Vec<u8>,
+}
+
+impl ConfigChanged {
+    /// Subject of the node `node` on which its config changes are pushed,
+    /// eg: `v0/kiiss/node-1`
+    pub fn channel(node: &str) -> Channel {
+        Channel::Subject(format!(
+            "{}/{}",
+            Channel::from(ChannelVs::Kiiss).to_string(),
+            node
+        ))
+    }
+}
+
+/// Set a config of all the nodes which have all of the `labels`, as a new
+/// revision of the config of each node
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SetNodesConfig {
+    /// labels which the nodes must have, eg: `zone=eu-1`
+    pub labels: Vec<String>,
+    /// type of config being set
+    pub kind: Config,
+    /// actual config data
+    pub data: Vec<u8>,
+}
+
+/// Set the labels of a node, by which `SetNodesConfig` selects it
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SetNodeLabels {
+    /// id of the mayastor instance
+    pub node: NodeId,
+    /// labels of the node, eg: `zone=eu-1`
+    pub labels: Vec<String>,
+}
+
+/// Outcome of the push of a new config revision to a node
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeConfigAck {
+    /// id of the mayastor instance
+    pub node: NodeId,
+    /// number of the new revision
+    pub revision: u64,
+    /// whether the node acknowledged the new revision
+    pub acknowledged: bool,
+    /// why the node did not acknowledge the new revision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -19,4 +19,11 @@ pub struct JsonGrpcRequest {
     pub method: JsonGrpcMethod,
     /// parameters to be passed to the above method
     pub params: JsonGrpcParams,
+    /// caller on whose behalf the method is called, for auditing
+    #[serde(default)]
+    pub caller: Option<String>,
+    /// roles of the caller, if it was authenticated, which must be granted
+    /// access to the method by the allowlist of the jsongrpc agent
+    #[serde(default)]
+    pub roles: Option<Vec<String>>,
 }
@@ -41,6 +41,10 @@ pub enum Filter {
     NodeVolume(NodeId, VolumeId),
     /// Filter by Volume
     Volume(VolumeId),
+    /// Selection filters
+    ///
+    /// Filter by labels and state, one page at a time
+    Selector(Selector),
 }
 impl Default for Filter {
     fn default() -> Self {
@@ -48,6 +52,109 @@ impl Default for Filter {
     }
 }
 
+/// Selects the objects which have all of the `labels` and are in the `state`.
+/// The selected objects are ordered by their id and returned one page of at
+/// most `max_entries` objects at a time, where the next page starts after the
+/// `continuation_token`, ie: the id of the last object of the previous page.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Selector {
+    /// labels which the objects must have, eg: `zone=eu-1`
+    pub labels: Vec<String>,
+    /// state which the objects must be in (case insensitive), eg: `online`
+    pub state: Option<String>,
+    /// maximum number of objects to return
+    pub max_entries: Option<u64>,
+    /// return the objects after this token
+    pub continuation_token: Option<String>,
+}
+
+impl Selector {
+    /// Parse the comma separated list of labels of a label selector
+    pub fn parse_labels(selector: &str) -> Vec<String> {
+        selector
+            .split(',')
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty())
+            .collect()
+    }
+
+    /// Whether an object in the `state` and with the `labels` is selected
+    pub fn matches(&self, state: &str, labels: &[String]) -> bool {
+        let state_matches = match &self.state {
+            Some(selected) => selected.eq_ignore_ascii_case(state),
+            None => true,
+        };
+        state_matches && self.labels.iter().all(|label| labels.contains(label))
+    }
+
+    /// Whether the serialized object `value` is selected, by the value of its
+    /// `state` field (or the variant name of an enum state, eg: `Created`) and
+    /// by its `labels` field, either a list of labels or a map of `key=value`
+    pub fn matches_value(&self, value: &serde_json::Value) -> bool {
+        let state = match value.get("state") {
+            Some(serde_json::Value::String(state)) => state.clone(),
+            Some(serde_json::Value::Object(variant)) => {
+                variant.keys().next().cloned().unwrap_or_default()
+            }
+            _ => String::new(),
+        };
+        let labels = match value.get("labels") {
+            Some(serde_json::Value::Array(labels)) => labels
+                .iter()
+                .filter_map(|label| label.as_str().map(ToString::to_string))
+                .collect(),
+            Some(serde_json::Value::Object(labels)) => labels
+                .iter()
+                .map(|(key, value)| match value.as_str() {
+                    Some(value) => format!("{}={}", key, value),
+                    None => format!("{}={}", key, value),
+                })
+                .collect(),
+            _ => vec![],
+        };
+        self.matches(&state, &labels)
+    }
+
+    /// Return the page of `objects` which follows the continuation token,
+    /// ordered by their `id`. Only the objects of the page are sorted.
+    pub fn paginate<T, I, F>(&self, objects: I, id: F) -> Vec<T>
+    where
+        I: IntoIterator<Item = T>,
+        F: Fn(&T) -> String,
+    {
+        let mut page = objects
+            .into_iter()
+            .map(|object| (id(&object), object))
+            .filter(|(key, _)| match &self.continuation_token {
+                Some(token) => key > token,
+                None => true,
+            })
+            .collect::<Vec<_>>();
+        if let Some(max_entries) = self.max_entries {
+            let max_entries = max_entries as usize;
+            if page.len() > max_entries {
+                page.select_nth_unstable_by(max_entries, |(a, _), (b, _)| a.cmp(b));
+                page.truncate(max_entries);
+            }
+        }
+        page.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        page.into_iter().map(|(_, object)| object).collect()
+    }
+
+    /// The continuation token of the next page, if the `page` is full and so
+    /// there may be more objects after it
+    pub fn next_token<T, F>(&self, page: &[T], id: F) -> Option<String>
+    where
+        F: Fn(&T) -> String,
+    {
+        match self.max_entries {
+            Some(max_entries) if page.len() as u64 == max_entries => page.last().map(id),
+            _ => None,
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! bus_impl_string_id_inner {
     ($Name:ident, $Doc:literal) => {
@@ -1,5 +1,6 @@
 pub mod blockdevice;
 pub mod child;
+pub mod config;
 pub mod jsongrpc;
 pub mod misc;
 pub mod nexus;
@@ -13,6 +14,7 @@ pub mod watch;
 
 pub use blockdevice::*;
 pub use child::*;
+pub use config::*;
 pub use jsongrpc::*;
 pub use misc::*;
 pub use nexus::*;
@@ -61,6 +63,8 @@ pub enum ChannelVs {
     Core,
     /// Watcher Agent
     Watcher,
+    /// Resource change events published by the Core Agent
+    Events,
 }
 impl Default for ChannelVs {
     fn default() -> Self {
@@ -86,6 +90,18 @@ pub enum MessageIdVs {
     ConfigUpdate,
     /// Request current Config
     ConfigGetCurrent,
+    /// Get the revisions of a Config
+    GetConfigRevisions,
+    /// Get a revision of a Config
+    GetConfigRevision,
+    /// Roll back a Config to an earlier revision
+    RollbackConfig,
+    /// Set a Config of the nodes selected by their labels
+    SetNodesConfig,
+    /// Set the labels of a node, by which its Configs may be set
+    SetNodeLabels,
+    /// A Config changed, pushed to the node
+    ConfigChanged,
     /// Register mayastor
     Register,
     /// Deregister mayastor
@@ -93,6 +109,8 @@ pub enum MessageIdVs {
     /// Node Service
     /// Get all node information
     GetNodes,
+    /// Get the state transitions of a node
+    GetNodeHistory,
     /// Pool Service
     ///
     /// Get pools with filter
@@ -155,6 +173,10 @@ pub enum MessageIdVs {
     GetWatches,
     /// Delete Resource Watch
     DeleteWatch,
+    /// Resource change event
+    ResourceEvent,
+    /// Get the latest resource change events
+    GetResourceEvents,
     /// Get Specs
     GetSpecs,
     /// Get States
@@ -8,13 +8,41 @@ use strum_macros::{EnumString, ToString};
 /// Registration
 ///
 /// Register message payload
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Register {
     /// id of the mayastor instance
     pub id: NodeId,
     /// grpc_endpoint of the mayastor instance
     pub grpc_endpoint: String,
+    /// token which proves the identity of the mayastor instance, signed with
+    /// the node registration secret of the control plane
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// time at which the token was signed, in milliseconds since the UNIX
+    /// epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// software version of the mayastor instance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// capabilities supported by the mayastor instance
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<NodeCapability>,
+}
+
+impl Debug for Register {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the token is a credential so never leak it into the logs
+        f.debug_struct("Register")
+            .field("id", &self.id)
+            .field("grpc_endpoint", &self.grpc_endpoint)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("timestamp", &self.timestamp)
+            .field("version", &self.version)
+            .field("capabilities", &self.capabilities)
+            .finish()
+    }
 }
 
 /// Deregister message payload
@@ -39,8 +67,14 @@ pub enum NodeState {
     /// registration keep alive deadline
     Online,
     /// Node is deemed offline if has missed the
-    /// registration keep alive deadline
+    /// registration keep alive deadline and the grace period which follows it
     Offline,
+    /// Node is deemed suspect if it has missed the registration keep alive
+    /// deadline but is still within the grace period
+    Suspect,
+    /// Node is deemed flapping if it has gone offline too many times within
+    /// the flap window, until it has been stable for the hold period
+    Flapping,
 }
 
 impl Default for NodeState {
@@ -49,6 +83,118 @@ impl Default for NodeState {
     }
 }
 
+/// Get the state transitions of a node
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct GetNodeHistory {
+    /// id of the mayastor instance
+    pub node: NodeId,
+}
+
+/// Transition of a node from one state to another
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStateTransition {
+    /// previous state of the node
+    pub from: NodeState,
+    /// new state of the node
+    pub to: NodeState,
+    /// when the node changed state
+    pub timestamp: std::time::SystemTime,
+}
+
+impl NodeStateTransition {
+    /// New transition from state `from` to state `to`, happening now
+    pub fn new(from: NodeState, to: NodeState) -> Self {
+        Self {
+            from,
+            to,
+            timestamp: std::time::SystemTime::now(),
+        }
+    }
+}
+
+impl From<NodeStateTransition> for models::NodeStateTransition {
+    fn from(src: NodeStateTransition) -> Self {
+        Self::new(
+            src.from,
+            humantime::format_rfc3339(src.timestamp).to_string(),
+            src.to,
+        )
+    }
+}
+impl From<&NodeStateTransition> for models::NodeStateTransition {
+    fn from(src: &NodeStateTransition) -> Self {
+        Self::from(src.clone())
+    }
+}
+
+/// Capability of a mayastor instance, reported on registration
+/// Unknown capabilities (eg: from newer mayastor versions) are preserved as
+/// `Other` so that mixed version clusters can still register
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum NodeCapability {
+    /// can share bdevs as NVMe-oF TCP
+    Nvmf,
+    /// can share bdevs as iSCSI
+    Iscsi,
+    /// can share bdevs as NBD
+    Nbd,
+    /// can create thin provisioned replicas
+    ThinProvisioning,
+    /// can take replica snapshots
+    Snapshots,
+    /// capability not known by this version of the control plane
+    Other(String),
+}
+
+impl NodeCapability {
+    /// Capabilities of the mayastor instances which predate the capability
+    /// reporting on registration
+    pub fn baseline() -> Vec<Self> {
+        vec![Self::Nvmf, Self::Iscsi, Self::Nbd]
+    }
+    /// Capabilities required to create a replica shared with `share` and
+    /// optionally `thin` provisioned
+    pub fn replica(share: &Protocol, thin: bool) -> Vec<Self> {
+        let mut capabilities = match share {
+            Protocol::None => vec![],
+            Protocol::Nvmf => vec![Self::Nvmf],
+            Protocol::Iscsi => vec![Self::Iscsi],
+            Protocol::Nbd => vec![Self::Nbd],
+        };
+        if thin {
+            capabilities.push(Self::ThinProvisioning);
+        }
+        capabilities
+    }
+}
+
+impl From<String> for NodeCapability {
+    fn from(src: String) -> Self {
+        match src.as_str() {
+            "nvmf" => Self::Nvmf,
+            "iscsi" => Self::Iscsi,
+            "nbd" => Self::Nbd,
+            "thinProvisioning" => Self::ThinProvisioning,
+            "snapshots" => Self::Snapshots,
+            _ => Self::Other(src),
+        }
+    }
+}
+impl From<NodeCapability> for String {
+    fn from(src: NodeCapability) -> Self {
+        match src {
+            NodeCapability::Nvmf => "nvmf".to_string(),
+            NodeCapability::Iscsi => "iscsi".to_string(),
+            NodeCapability::Nbd => "nbd".to_string(),
+            NodeCapability::ThinProvisioning => "thinProvisioning".to_string(),
+            NodeCapability::Snapshots => "snapshots".to_string(),
+            NodeCapability::Other(other) => other,
+        }
+    }
+}
+
 /// Node information
 #[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -59,6 +205,27 @@ pub struct Node {
     pub grpc_endpoint: String,
     /// deemed state of the node
     pub state: NodeState,
+    /// software version of the mayastor instance
+    #[serde(default)]
+    pub version: Option<String>,
+    /// capabilities supported by the mayastor instance
+    /// empty if the instance predates the capability reporting
+    #[serde(default)]
+    pub capabilities: Vec<NodeCapability>,
+}
+
+impl Node {
+    /// Whether the node supports all the given `capabilities`
+    /// Nodes which do not report their capabilities are assumed to support
+    /// only the `NodeCapability::baseline`
+    pub fn supports(&self, capabilities: &[NodeCapability]) -> bool {
+        if self.capabilities.is_empty() {
+            let baseline = NodeCapability::baseline();
+            capabilities.iter().all(|c| baseline.contains(c))
+        } else {
+            capabilities.iter().all(|c| self.capabilities.contains(c))
+        }
+    }
 }
 
 impl From<models::Node> for Node {
@@ -67,6 +234,13 @@ impl From<models::Node> for Node {
             id: src.id.into(),
             grpc_endpoint: src.grpc_endpoint,
             state: src.state.into(),
+            version: src.version,
+            capabilities: src
+                .capabilities
+                .unwrap_or_default()
+                .into_iter()
+                .map(From::from)
+                .collect(),
         }
     }
 }
@@ -75,13 +249,23 @@ bus_impl_string_id!(NodeId, "ID of a mayastor node");
 
 impl From<Node> for models::Node {
     fn from(src: Node) -> Self {
-        Self::new(src.grpc_endpoint, src.id, src.state)
+        let capabilities = src
+            .capabilities
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        Self::new_all(
+            capabilities,
+            src.grpc_endpoint,
+            src.id,
+            src.state,
+            src.version,
+        )
     }
 }
 impl From<&Node> for models::Node {
     fn from(src: &Node) -> Self {
-        let src = src.clone();
-        Self::new(src.grpc_endpoint, src.id, src.state)
+        Self::from(src.clone())
     }
 }
 
@@ -91,6 +275,8 @@ impl From<NodeState> for models::NodeState {
             NodeState::Unknown => Self::Unknown,
             NodeState::Online => Self::Online,
             NodeState::Offline => Self::Offline,
+            NodeState::Suspect => Self::Suspect,
+            NodeState::Flapping => Self::Flapping,
         }
     }
 }
@@ -100,6 +286,39 @@ impl From<models::NodeState> for NodeState {
             models::NodeState::Unknown => Self::Unknown,
             models::NodeState::Online => Self::Online,
             models::NodeState::Offline => Self::Offline,
+            models::NodeState::Suspect => Self::Suspect,
+            models::NodeState::Flapping => Self::Flapping,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_supports() {
+        let thick = NodeCapability::replica(&Protocol::Nvmf, false);
+        let thin = NodeCapability::replica(&Protocol::Nvmf, true);
+
+        // nodes which do not report their capabilities only have the baseline
+        let node = Node::default();
+        assert!(node.supports(&thick));
+        assert!(!node.supports(&thin));
+        assert!(node.supports(&NodeCapability::replica(&Protocol::None, false)));
+
+        let node = Node {
+            capabilities: vec![NodeCapability::Nvmf, NodeCapability::ThinProvisioning],
+            ..Default::default()
+        };
+        assert!(node.supports(&thick));
+        assert!(node.supports(&thin));
+        assert!(!node.supports(&NodeCapability::replica(&Protocol::Iscsi, false)));
+
+        let node = Node {
+            capabilities: vec![NodeCapability::Iscsi],
+            ..Default::default()
+        };
+        assert!(!node.supports(&thick));
+    }
+}
@@ -108,6 +108,10 @@ impl ReplicaOwners {
     pub fn is_owned(&self) -> bool {
         self.volume.is_some() || !self.nexuses.is_empty()
     }
+    /// The volume which owns this replica, if any
+    pub fn volume(&self) -> Option<&VolumeId> {
+        self.volume.as_ref()
+    }
     /// Check if this replica is owned by this volume
     pub fn owned_by(&self, id: &VolumeId) -> bool {
         self.volume.as_ref() == Some(id)
@@ -4,10 +4,14 @@ use std::fmt::Debug;
 use super::*;
 use crate::types::v0::store::{nexus, pool, replica, volume};
 
-/// Retrieve all specs from core agent
+/// Retrieve the specs from core agent
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct GetSpecs {}
+pub struct GetSpecs {
+    /// Filter request, either none or a selector
+    #[serde(default)]
+    pub filter: Filter,
+}
 
 /// Specs detailing the requested configuration of the objects.
 #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
@@ -21,10 +25,19 @@ pub struct Specs {
     pub pools: Vec<pool::PoolSpec>,
     /// replica specs
     pub replicas: Vec<replica::ReplicaSpec>,
+    /// token to get the next page of specs with, if there are more specs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
 }
 
 impl From<Specs> for models::Specs {
     fn from(src: Specs) -> Self {
-        Self::new(src.nexuses, src.pools, src.replicas, src.volumes)
+        Self::new_all(
+            src.continuation_token,
+            src.nexuses,
+            src.pools,
+            src.replicas,
+            src.volumes,
+        )
     }
 }
@@ -284,6 +284,9 @@ pub struct CreateVolume {
     pub policy: VolumeHealPolicy,
     /// initial replica placement topology
     pub topology: Topology,
+    /// thin provisioning of the replicas
+    #[serde(default)]
+    pub thin: bool,
 }
 
 impl CreateVolume {
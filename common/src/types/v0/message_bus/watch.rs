@@ -1,7 +1,8 @@
 use super::*;
 
+use crate::types::v0::store::definitions::{ObjectKey, StorableObjectType};
 use serde::{Deserialize, Serialize};
-use std::{convert::TryFrom, fmt::Debug};
+use std::{convert::TryFrom, fmt::Debug, str::FromStr};
 
 ///
 /// Watcher Agent
@@ -11,7 +12,7 @@ use std::{convert::TryFrom, fmt::Debug};
 pub type CreateWatch = Watch;
 
 /// Watch Resource in the store
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Watch {
     /// id of the resource to watch on
@@ -20,18 +21,110 @@ pub struct Watch {
     pub callback: WatchCallback,
     /// type of watch
     pub watch_type: WatchType,
+    /// secret shared with the watcher, used to sign the notifications
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// filter of the notified resources, either none or a selector of their
+    /// labels and state
+    #[serde(default)]
+    pub filter: Filter,
+    /// delivery status of the notifications, only reported when listing the
+    /// watches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<WatchDelivery>,
+}
+
+impl Debug for Watch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // never leak the secret into the logs
+        f.debug_struct("Watch")
+            .field("id", &self.id)
+            .field("callback", &self.callback)
+            .field("watch_type", &self.watch_type)
+            .field("secret", &self.secret.as_ref().map(|_| "<redacted>"))
+            .field("filter", &self.filter)
+            .field("delivery", &self.delivery)
+            .finish()
+    }
 }
 
 impl TryFrom<&Watch> for models::RestWatch {
     type Error = ();
     fn try_from(value: &Watch) -> Result<Self, Self::Error> {
-        match &value.callback {
-            WatchCallback::Uri(uri) => Ok(Self {
-                resource: value.id.to_string(),
-                callback: uri.to_string(),
-            }),
-            /* other types are not implemented yet and should map to an error
-             * _ => Err(()), */
+        Ok(Self {
+            resource: value.id.to_string(),
+            callback: value.callback.to_string(),
+            delivery: value.delivery.clone().map(From::from),
+        })
+    }
+}
+
+/// Delivery status of the notifications of a watch
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchDelivery {
+    /// overall status of the delivery
+    pub status: WatchDeliveryStatus,
+    /// number of notifications queued for delivery
+    pub pending: u64,
+    /// number of notifications which were given up, either because they were
+    /// older than the maximum age or because the queue was full
+    pub dead_letters: u64,
+    /// error of the last failed delivery attempt, if the delivery is retrying
+    pub last_error: Option<String>,
+}
+impl From<WatchDelivery> for models::WatchDelivery {
+    fn from(src: WatchDelivery) -> Self {
+        Self::new_all(
+            src.dead_letters as i64,
+            src.last_error,
+            src.pending as i64,
+            src.status,
+        )
+    }
+}
+impl From<models::WatchDelivery> for WatchDelivery {
+    fn from(src: models::WatchDelivery) -> Self {
+        Self {
+            status: src.status.into(),
+            pending: src.pending as u64,
+            dead_letters: src.dead_letters as u64,
+            last_error: src.last_error,
+        }
+    }
+}
+
+/// Status of the delivery of the notifications of a watch
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum WatchDeliveryStatus {
+    /// all the notifications were delivered so far
+    Ok,
+    /// the delivery of the oldest pending notification is being retried
+    Retrying,
+    /// some notifications were given up
+    DeadLettered,
+}
+impl Default for WatchDeliveryStatus {
+    fn default() -> Self {
+        Self::Ok
+    }
+}
+impl From<WatchDeliveryStatus> for models::WatchDeliveryStatus {
+    fn from(src: WatchDeliveryStatus) -> Self {
+        match src {
+            WatchDeliveryStatus::Ok => Self::Ok,
+            WatchDeliveryStatus::Retrying => Self::Retrying,
+            WatchDeliveryStatus::DeadLettered => Self::DeadLettered,
+        }
+    }
+}
+impl From<models::WatchDeliveryStatus> for WatchDeliveryStatus {
+    fn from(src: models::WatchDeliveryStatus) -> Self {
+        match src {
+            models::WatchDeliveryStatus::Ok => Self::Ok,
+            models::WatchDeliveryStatus::Retrying => Self::Retrying,
+            models::WatchDeliveryStatus::DeadLettered => Self::DeadLettered,
         }
     }
 }
@@ -54,8 +147,12 @@ pub type Resource = WatchResourceId;
 pub enum WatchResourceId {
     /// nodes
     Node(NodeId),
+    /// node spec
+    NodeSpec(NodeId),
     /// pools
     Pool(PoolId),
+    /// pool spec
+    PoolSpec(PoolId),
     /// replicas
     Replica(ReplicaId),
     /// replica state
@@ -64,8 +161,24 @@ pub enum WatchResourceId {
     ReplicaSpec(ReplicaId),
     /// nexuses
     Nexus(NexusId),
+    /// nexus state
+    NexusState(NexusId),
+    /// nexus spec
+    NexusSpec(NexusId),
     /// volumes
     Volume(VolumeId),
+    /// volume state
+    VolumeState(VolumeId),
+    /// volume spec
+    VolumeSpec(VolumeId),
+    /// nexus child state, identified by its replica
+    ChildState(ReplicaId),
+    /// nexus child spec, identified by its replica
+    ChildSpec(ReplicaId),
+    /// watch configuration of a resource
+    WatchConfig(Box<WatchResourceId>),
+    /// every resource of a kind
+    Kind(StorableObjectType),
 }
 impl Default for WatchResourceId {
     fn default() -> Self {
@@ -76,7 +189,9 @@ impl ToString for WatchResourceId {
     fn to_string(&self) -> String {
         match self {
             WatchResourceId::Node(id) => format!("nodes/{}", id.to_string()),
+            WatchResourceId::NodeSpec(id) => format!("nodes_spec/{}", id.to_string()),
             WatchResourceId::Pool(id) => format!("pools/{}", id.to_string()),
+            WatchResourceId::PoolSpec(id) => format!("pools_spec/{}", id.to_string()),
             WatchResourceId::Replica(id) => {
                 format!("replicas/{}", id.to_string())
             }
@@ -87,7 +202,173 @@ impl ToString for WatchResourceId {
                 format!("replicas_spec/{}", id.to_string())
             }
             WatchResourceId::Nexus(id) => format!("nexuses/{}", id.to_string()),
+            WatchResourceId::NexusState(id) => {
+                format!("nexuses_state/{}", id.to_string())
+            }
+            WatchResourceId::NexusSpec(id) => format!("nexuses_spec/{}", id.to_string()),
             WatchResourceId::Volume(id) => format!("volumes/{}", id.to_string()),
+            WatchResourceId::VolumeState(id) => {
+                format!("volumes_state/{}", id.to_string())
+            }
+            WatchResourceId::VolumeSpec(id) => format!("volumes_spec/{}", id.to_string()),
+            WatchResourceId::ChildState(id) => {
+                format!("children_state/{}", id.to_string())
+            }
+            WatchResourceId::ChildSpec(id) => format!("children_spec/{}", id.to_string()),
+            WatchResourceId::WatchConfig(id) => format!("watches/{}", id.to_string()),
+            WatchResourceId::Kind(kind) => Self::kind_name(*kind).to_string(),
+        }
+    }
+}
+impl FromStr for WatchResourceId {
+    type Err = String;
+
+    /// Parse the resource from its string form, eg: `volumes/{volume_id}`, or
+    /// a resource kind, eg: `volumes`
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parts = source.splitn(2, '/');
+        let name = parts.next().unwrap_or_default();
+        let kind = Self::KINDS
+            .iter()
+            .find(|kind| Self::kind_name(**kind) == name)
+            .ok_or_else(|| format!("unknown resource kind '{}'", name))?;
+        match parts.next() {
+            None => Ok(Self::Kind(*kind)),
+            Some(id) => Self::from_kind(*kind, id),
+        }
+    }
+}
+impl WatchResourceId {
+    /// All the kinds of resources
+    const KINDS: [StorableObjectType; 16] = [
+        StorableObjectType::WatchConfig,
+        StorableObjectType::Volume,
+        StorableObjectType::Nexus,
+        StorableObjectType::NexusSpec,
+        StorableObjectType::NexusState,
+        StorableObjectType::Node,
+        StorableObjectType::NodeSpec,
+        StorableObjectType::Pool,
+        StorableObjectType::PoolSpec,
+        StorableObjectType::Replica,
+        StorableObjectType::ReplicaState,
+        StorableObjectType::ReplicaSpec,
+        StorableObjectType::VolumeSpec,
+        StorableObjectType::VolumeState,
+        StorableObjectType::ChildSpec,
+        StorableObjectType::ChildState,
+    ];
+
+    /// Name of a resource kind, as the prefix of its resources, eg: `volumes`
+    fn kind_name(kind: StorableObjectType) -> &'static str {
+        match kind {
+            StorableObjectType::WatchConfig => "watches",
+            StorableObjectType::Volume => "volumes",
+            StorableObjectType::Nexus => "nexuses",
+            StorableObjectType::NexusSpec => "nexuses_spec",
+            StorableObjectType::NexusState => "nexuses_state",
+            StorableObjectType::Node => "nodes",
+            StorableObjectType::NodeSpec => "nodes_spec",
+            StorableObjectType::Pool => "pools",
+            StorableObjectType::PoolSpec => "pools_spec",
+            StorableObjectType::Replica => "replicas",
+            StorableObjectType::ReplicaState => "replicas_state",
+            StorableObjectType::ReplicaSpec => "replicas_spec",
+            StorableObjectType::VolumeSpec => "volumes_spec",
+            StorableObjectType::VolumeState => "volumes_state",
+            StorableObjectType::ChildSpec => "children_spec",
+            StorableObjectType::ChildState => "children_state",
+            StorableObjectType::WatchDelivery => "watch_deliveries",
+            StorableObjectType::NodeConfig => "node_configs",
+            StorableObjectType::EventRevision => "event_revisions",
+        }
+    }
+
+    /// The resource of the `kind` with the `id`
+    pub fn from_kind(kind: StorableObjectType, id: &str) -> Result<Self, String> {
+        Ok(match kind {
+            StorableObjectType::WatchConfig => Self::WatchConfig(Box::new(id.parse()?)),
+            StorableObjectType::Volume => Self::Volume(id.into()),
+            StorableObjectType::Nexus => Self::Nexus(id.into()),
+            StorableObjectType::NexusSpec => Self::NexusSpec(id.into()),
+            StorableObjectType::NexusState => Self::NexusState(id.into()),
+            StorableObjectType::Node => Self::Node(id.into()),
+            StorableObjectType::NodeSpec => Self::NodeSpec(id.into()),
+            StorableObjectType::Pool => Self::Pool(id.into()),
+            StorableObjectType::PoolSpec => Self::PoolSpec(id.into()),
+            StorableObjectType::Replica => Self::Replica(id.into()),
+            StorableObjectType::ReplicaState => Self::ReplicaState(id.into()),
+            StorableObjectType::ReplicaSpec => Self::ReplicaSpec(id.into()),
+            StorableObjectType::VolumeSpec => Self::VolumeSpec(id.into()),
+            StorableObjectType::VolumeState => Self::VolumeState(id.into()),
+            StorableObjectType::ChildSpec => Self::ChildSpec(id.into()),
+            StorableObjectType::ChildState => Self::ChildState(id.into()),
+            StorableObjectType::WatchDelivery => {
+                return Err("the watch deliveries may not be watched".to_string())
+            }
+            StorableObjectType::NodeConfig => {
+                return Err("the node configs may not be watched".to_string())
+            }
+            StorableObjectType::EventRevision => {
+                return Err("the event revisions may not be watched".to_string())
+            }
+        })
+    }
+
+    /// Whether this resource is, or for a kind includes, the `resource`
+    pub fn contains(&self, resource: &WatchResourceId) -> bool {
+        match (self, resource) {
+            (Self::Kind(_), Self::Kind(_)) => false,
+            (Self::Kind(kind), resource) => &resource.key_type() == kind,
+            (watched, resource) => watched == resource,
+        }
+    }
+
+    /// The resource holding the desired state of this resource, which is kept
+    /// in the store as its spec, if it has one.
+    /// The watch configs may not be watched as they hold the watch secrets.
+    pub fn desired(&self) -> Option<Self> {
+        match self {
+            Self::Node(id) | Self::NodeSpec(id) => Some(Self::NodeSpec(id.clone())),
+            Self::Pool(id) | Self::PoolSpec(id) => Some(Self::PoolSpec(id.clone())),
+            Self::Replica(id) | Self::ReplicaSpec(id) => Some(Self::ReplicaSpec(id.clone())),
+            Self::Nexus(id) | Self::NexusSpec(id) => Some(Self::NexusSpec(id.clone())),
+            Self::Volume(id) | Self::VolumeSpec(id) => Some(Self::VolumeSpec(id.clone())),
+            Self::ChildSpec(_) => Some(self.clone()),
+            Self::ReplicaState(_)
+            | Self::NexusState(_)
+            | Self::VolumeState(_)
+            | Self::ChildState(_)
+            | Self::WatchConfig(_)
+            | Self::Kind(StorableObjectType::WatchConfig) => None,
+            Self::Kind(kind) => Self::from_kind(*kind, "")
+                .ok()?
+                .desired()
+                .map(|desired| Self::Kind(desired.key_type())),
+        }
+    }
+    /// The resource whose actual state is tracked by the registry for this
+    /// resource, if it has one
+    pub fn actual(&self) -> Option<Self> {
+        match self {
+            Self::Node(id) => Some(Self::Node(id.clone())),
+            Self::Pool(id) => Some(Self::Pool(id.clone())),
+            Self::Replica(id) | Self::ReplicaState(id) => Some(Self::Replica(id.clone())),
+            Self::Nexus(id) | Self::NexusState(id) => Some(Self::Nexus(id.clone())),
+            Self::Volume(id) | Self::VolumeState(id) => Some(Self::Volume(id.clone())),
+            Self::NodeSpec(_)
+            | Self::PoolSpec(_)
+            | Self::ReplicaSpec(_)
+            | Self::NexusSpec(_)
+            | Self::VolumeSpec(_)
+            | Self::ChildState(_)
+            | Self::ChildSpec(_)
+            | Self::WatchConfig(_)
+            | Self::Kind(StorableObjectType::WatchConfig) => None,
+            Self::Kind(kind) => Self::from_kind(*kind, "")
+                .ok()?
+                .actual()
+                .map(|actual| Self::Kind(actual.key_type())),
         }
     }
 }
@@ -108,6 +389,24 @@ impl Default for WatchType {
         Self::All
     }
 }
+impl From<models::WatchType> for WatchType {
+    fn from(src: models::WatchType) -> Self {
+        match src {
+            models::WatchType::Desired => Self::Desired,
+            models::WatchType::Actual => Self::Actual,
+            models::WatchType::All => Self::All,
+        }
+    }
+}
+impl From<&WatchType> for models::WatchType {
+    fn from(src: &WatchType) -> Self {
+        match src {
+            WatchType::Desired => Self::Desired,
+            WatchType::Actual => Self::Actual,
+            WatchType::All => Self::All,
+        }
+    }
+}
 
 /// Delete Watch which was previously created by CreateWatcher
 /// Fields should match the ones used for the creation
@@ -128,6 +427,8 @@ pub struct DeleteWatch {
 pub enum WatchCallback {
     /// HTTP URI callback
     Uri(String),
+    /// Message bus subject on which the notifications are published
+    Subject(String),
 }
 impl Default for WatchCallback {
     fn default() -> Self {
@@ -139,6 +440,86 @@ impl From<models::WatchCallback> for WatchCallback {
     fn from(src: models::WatchCallback) -> Self {
         match src {
             models::WatchCallback::uri(uri) => Self::Uri(uri),
+            models::WatchCallback::subject(subject) => Self::Subject(subject),
         }
     }
 }
+
+/// Scheme of the callback urls which name a message bus subject rather than an
+/// HTTP uri, eg: `nats:watches.volumes`
+pub const SUBJECT_CALLBACK_SCHEME: &str = "nats";
+
+impl From<&url::Url> for WatchCallback {
+    fn from(url: &url::Url) -> Self {
+        match url.scheme() {
+            SUBJECT_CALLBACK_SCHEME => Self::Subject(url.path().to_string()),
+            _ => Self::Uri(url.to_string()),
+        }
+    }
+}
+impl ToString for WatchCallback {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Uri(uri) => uri.clone(),
+            Self::Subject(subject) => format!("{}:{}", SUBJECT_CALLBACK_SCHEME, subject),
+        }
+    }
+}
+
+/// Change event of a resource, published by the core agent with a revision
+/// which increases with every event
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceEvent {
+    /// revision of the event
+    pub revision: u64,
+    /// id of the resource which changed
+    pub resource: WatchResourceId,
+    /// type of the change
+    pub event_type: ResourceEventType,
+    /// whether the desired or the actual state of the resource changed
+    pub watch_type: WatchType,
+    /// new value of the resource, unless it was deleted
+    pub value: Option<serde_json::Value>,
+}
+
+/// The different types of resource changes
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ResourceEventType {
+    /// The resource was created or updated
+    Put,
+    /// The resource was deleted
+    Delete,
+}
+impl Default for ResourceEventType {
+    fn default() -> Self {
+        Self::Put
+    }
+}
+impl From<&ResourceEventType> for models::WatchEventType {
+    fn from(src: &ResourceEventType) -> Self {
+        match src {
+            ResourceEventType::Put => Self::Put,
+            ResourceEventType::Delete => Self::Delete,
+        }
+    }
+}
+impl ToString for ResourceEventType {
+    fn to_string(&self) -> String {
+        match self {
+            ResourceEventType::Put => "put",
+            ResourceEventType::Delete => "delete",
+        }
+        .to_string()
+    }
+}
+
+/// Get the resource change events which followed the given revision, which
+/// must still be retained by the core agent
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GetResourceEvents {
+    /// revision after which the events are returned
+    pub revision: u64,
+}
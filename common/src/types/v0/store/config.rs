@@ -0,0 +1,126 @@
+//! Definition of the node config types that can be saved to the persistent
+//! store.
+
+use crate::{
+    mbus_api::SenderId,
+    types::v0::{
+        message_bus::{Config, ConfigRevision},
+        store::definitions::{ObjectKey, StorableObject, StorableObjectType},
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Config of a kind sent by a node, as kept by the kiiss agent
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeConfig {
+    /// The node which sent the config.
+    pub sender: SenderId,
+    /// Type of the config.
+    pub kind: Config,
+    /// The latest revisions of the config, oldest first.
+    /// The last one is the current config.
+    pub revisions: VecDeque<ConfigRevision>,
+}
+
+impl NodeConfig {
+    /// New config of the given `kind` sent by the node `sender`, without any
+    /// revision
+    pub fn new(sender: &str, kind: &Config) -> Self {
+        Self {
+            sender: sender.to_string(),
+            kind: kind.clone(),
+            revisions: VecDeque::new(),
+        }
+    }
+
+    /// The current revision of the config
+    pub fn current(&self) -> Option<&ConfigRevision> {
+        self.revisions.back()
+    }
+
+    /// The revision number `revision` of the config, if it's still kept
+    pub fn revision(&self, revision: u64) -> Option<&ConfigRevision> {
+        self.revisions.iter().find(|r| r.revision == revision)
+    }
+
+    /// Add a new revision with the config `data`, keeping only the latest
+    /// `max_revisions` revisions
+    pub fn push(
+        &mut self,
+        data: Vec<u8>,
+        rollback_of: Option<u64>,
+        max_revisions: usize,
+    ) -> ConfigRevision {
+        let number = self.current().map(|r| r.revision).unwrap_or_default() + 1;
+        let revision = ConfigRevision::new(number, data, rollback_of);
+        self.revisions.push_back(revision.clone());
+        while self.revisions.len() > max_revisions.max(1) {
+            self.revisions.pop_front();
+        }
+        revision
+    }
+}
+
+/// Key used by the store to uniquely identify a NodeConfig structure.
+pub struct NodeConfigKey {
+    sender: SenderId,
+    kind: Config,
+}
+
+impl NodeConfigKey {
+    /// Key of the config of the given `kind` sent by the node `sender`
+    pub fn new(sender: &str, kind: &Config) -> Self {
+        Self {
+            sender: sender.to_string(),
+            kind: kind.clone(),
+        }
+    }
+}
+
+impl ObjectKey for NodeConfigKey {
+    fn key_type(&self) -> StorableObjectType {
+        StorableObjectType::NodeConfig
+    }
+
+    fn key_uuid(&self) -> String {
+        format!("{}/{}", self.sender, self.kind.to_string())
+    }
+}
+
+impl StorableObject for NodeConfig {
+    type Key = NodeConfigKey;
+
+    fn key(&self) -> Self::Key {
+        NodeConfigKey::new(&self.sender, &self.kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_revisions() {
+        let mut config = NodeConfig::new("node-1", &Config::MayastorConfig);
+        assert!(config.current().is_none());
+        for data in 1u8 ..= 4 {
+            config.push(vec![data], None, 3);
+        }
+        // only the latest revisions are kept, and the numbering carries on
+        let revisions = config
+            .revisions
+            .iter()
+            .map(|r| r.revision)
+            .collect::<Vec<_>>();
+        assert_eq!(revisions, vec![2, 3, 4]);
+        assert!(config.revision(1).is_none());
+
+        let target = config.revision(2).cloned().unwrap();
+        let rollback = config.push(target.data.clone(), Some(target.revision), 3);
+        assert_eq!(rollback.revision, 5);
+        assert_eq!(rollback.rollback_of, Some(2));
+        assert_eq!(config.current().unwrap().data, vec![2]);
+    }
+}
@@ -1,9 +1,9 @@
 use async_trait::async_trait;
 use etcd_client::Error;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{Error as SerdeError, Value};
 use snafu::Snafu;
-use strum_macros::Display;
+use strum_macros::{Display, EnumString};
 use tokio::sync::mpsc::Receiver;
 
 /// Definition of errors that can be returned from the key-value store.
@@ -63,10 +63,22 @@ pub enum StoreError {
 /// Representation of a watch event.
 #[derive(Debug)]
 pub enum WatchEvent {
-    // Put operation containing the key and value
-    Put(String, Value),
-    // Delete operation
-    Delete,
+    // Put operation containing the key, value and store revision
+    Put(String, Value, i64),
+    // Delete operation containing the store revision
+    Delete(i64),
+}
+
+/// Representation of a change to an entry under a watched key prefix, along
+/// with the store revision at which it occurred.
+#[derive(Debug)]
+pub struct PrefixWatchEvent {
+    /// key of the entry which changed
+    pub key: String,
+    /// store revision of the change
+    pub revision: i64,
+    /// new value of the entry, or None if the entry was deleted
+    pub value: Option<Value>,
 }
 
 /// Store keys type trait
@@ -108,10 +120,20 @@ pub trait Store: Sync + Send + Clone {
 
     async fn watch_obj<K: ObjectKey>(&mut self, key: &K) -> Result<StoreWatchReceiver, StoreError>;
 
+    /// Watch for changes to all the entries with the given key prefix, from the
+    /// store `revision` onwards if given, or from now otherwise.
+    /// Returns a channel which will be signalled when an event occurs.
+    async fn watch_prefix(
+        &mut self,
+        key_prefix: &str,
+        revision: Option<i64>,
+    ) -> Result<StorePrefixWatchReceiver, StoreError>;
+
     async fn online(&mut self) -> bool;
 }
 
 pub type StoreWatchReceiver = Receiver<Result<WatchEvent, StoreError>>;
+pub type StorePrefixWatchReceiver = Receiver<Result<PrefixWatchEvent, StoreError>>;
 
 /// Implemented by Keys of Storable Objects, eg: VolumeId
 pub trait ObjectKey: Sync + Send {
@@ -131,7 +153,7 @@ pub trait StorableObject: Serialize + Sync + Send + DeserializeOwned {
 }
 
 /// All types of objects which are storable in our store
-#[derive(Display, Copy, Clone, Debug)]
+#[derive(Serialize, Deserialize, Display, EnumString, Copy, Clone, Debug, Eq, PartialEq)]
 pub enum StorableObjectType {
     WatchConfig,
     Volume,
@@ -149,10 +171,16 @@ pub enum StorableObjectType {
     VolumeState,
     ChildSpec,
     ChildState,
+    WatchDelivery,
+    NodeConfig,
+    EventRevision,
 }
 
+/// Prefix of the keys of all the objects in our store
+pub const STORE_PREFIX: &str = "control-plane";
+
 pub fn key_prefix(obj_type: StorableObjectType) -> String {
-    format!("control-plane/{}", obj_type.to_string())
+    format!("{}/{}", STORE_PREFIX, obj_type.to_string())
 }
 
 /// create a key based on the object's key trait
@@ -1,4 +1,5 @@
 pub mod child;
+pub mod config;
 pub mod definitions;
 pub mod nexus;
 pub mod node;
@@ -10,7 +10,7 @@ use crate::types::v0::{
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-type NodeLabels = HashMap<String, String>;
+pub type NodeLabels = HashMap<String, String>;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct Node {
@@ -33,6 +33,21 @@ pub struct NodeSpec {
     labels: NodeLabels,
 }
 
+impl NodeSpec {
+    /// Spec of the node `id` with the given `labels`
+    pub fn new(id: NodeId, labels: NodeLabels) -> Self {
+        Self { id, labels }
+    }
+    /// Node identification
+    pub fn id(&self) -> &NodeId {
+        &self.id
+    }
+    /// Node labels
+    pub fn labels(&self) -> &NodeLabels {
+        &self.labels
+    }
+}
+
 impl UuidString for NodeSpec {
     fn uuid_as_string(&self) -> String {
         self.id.clone().into()
@@ -83,6 +83,9 @@ pub struct VolumeSpec {
     pub labels: Vec<VolumeLabel>,
     /// Number of children the volume should have.
     pub num_replicas: u8,
+    /// Whether the replicas of the volume are thinly provisioned.
+    #[serde(default)]
+    pub thin: bool,
     /// Protocol that the volume should be shared over.
     pub protocol: Protocol,
     /// Number of front-end paths.
@@ -219,6 +222,7 @@ impl From<&CreateVolume> for VolumeSpec {
             size: request.size,
             labels: vec![],
             num_replicas: request.replicas as u8,
+            thin: request.thin,
             protocol: Protocol::None,
             num_paths: 1,
             state: VolumeSpecState::Creating,
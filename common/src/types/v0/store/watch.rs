@@ -7,23 +7,45 @@ impl ObjectKey for WatchResourceId {
     fn key_type(&self) -> StorableObjectType {
         match &self {
             WatchResourceId::Node(_) => StorableObjectType::Node,
+            WatchResourceId::NodeSpec(_) => StorableObjectType::NodeSpec,
             WatchResourceId::Pool(_) => StorableObjectType::Pool,
+            WatchResourceId::PoolSpec(_) => StorableObjectType::PoolSpec,
             WatchResourceId::Replica(_) => StorableObjectType::Replica,
             WatchResourceId::ReplicaState(_) => StorableObjectType::ReplicaState,
             WatchResourceId::ReplicaSpec(_) => StorableObjectType::ReplicaSpec,
             WatchResourceId::Nexus(_) => StorableObjectType::Nexus,
+            WatchResourceId::NexusState(_) => StorableObjectType::NexusState,
+            WatchResourceId::NexusSpec(_) => StorableObjectType::NexusSpec,
             WatchResourceId::Volume(_) => StorableObjectType::Volume,
+            WatchResourceId::VolumeState(_) => StorableObjectType::VolumeState,
+            WatchResourceId::VolumeSpec(_) => StorableObjectType::VolumeSpec,
+            WatchResourceId::ChildState(_) => StorableObjectType::ChildState,
+            WatchResourceId::ChildSpec(_) => StorableObjectType::ChildSpec,
+            WatchResourceId::WatchConfig(_) => StorableObjectType::WatchConfig,
+            WatchResourceId::Kind(kind) => *kind,
         }
     }
     fn key_uuid(&self) -> String {
         match &self {
             WatchResourceId::Node(i) => i.to_string(),
+            WatchResourceId::NodeSpec(i) => i.to_string(),
             WatchResourceId::Pool(i) => i.to_string(),
+            WatchResourceId::PoolSpec(i) => i.to_string(),
             WatchResourceId::Replica(i) => i.to_string(),
             WatchResourceId::ReplicaState(i) => i.to_string(),
             WatchResourceId::ReplicaSpec(i) => i.to_string(),
             WatchResourceId::Nexus(i) => i.to_string(),
+            WatchResourceId::NexusState(i) => i.to_string(),
+            WatchResourceId::NexusSpec(i) => i.to_string(),
             WatchResourceId::Volume(i) => i.to_string(),
+            WatchResourceId::VolumeState(i) => i.to_string(),
+            WatchResourceId::VolumeSpec(i) => i.to_string(),
+            WatchResourceId::ChildState(i) => i.to_string(),
+            WatchResourceId::ChildSpec(i) => i.to_string(),
+            // the watch configuration is keyed by the watched resource
+            WatchResourceId::WatchConfig(i) => i.to_string(),
+            // so that the key is the prefix of the keys of all the resources
+            WatchResourceId::Kind(_) => String::new(),
         }
     }
 }
@@ -1239,7 +1239,7 @@ impl ComposeTest {
         let (_, ip) = self.containers.get(name).unwrap();
         let url = format!("{}", ip);
         tokio::time::timeout(std::time::Duration::from_secs(2), async {
-            mbus_api::message_bus_init_options(url, bus_timeout).await
+            mbus_api::message_bus_init_options(url, mbus_api::BusOptions::new(), bus_timeout).await
         })
         .await
         .unwrap();
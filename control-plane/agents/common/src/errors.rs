@@ -22,6 +22,8 @@ pub enum SvcError {
     NodeNotOnline { node: NodeId },
     #[snafu(display("No available online nodes"))]
     NoNodes {},
+    #[snafu(display("Registration of node '{}' rejected: {}", node, reason))]
+    NodeRegistration { node: NodeId, reason: String },
     #[snafu(display(
         "Timed out after '{:?}' attempting to connect to node '{}' via gRPC endpoint '{}'",
         timeout,
@@ -110,6 +112,18 @@ pub enum SvcError {
         params: String,
         error: String,
     },
+    #[snafu(display(
+        "Failed to connect to node '{}' via JSON gRPC endpoint '{}'",
+        node,
+        endpoint
+    ))]
+    JsonRpcConnect {
+        node: String,
+        endpoint: String,
+        source: tonic::transport::Error,
+    },
+    #[snafu(display("Json RPC method '{}' is not allowed: {}", method, reason))]
+    JsonRpcNotAllowed { method: String, reason: String },
     #[snafu(display("Internal error: {}", details))]
     Internal { details: String },
     #[snafu(display("Message Bus error"))]
@@ -128,6 +142,17 @@ pub enum SvcError {
     WatchResourceNotFound { kind: ResourceKind },
     #[snafu(display("Watch Already Exists"))]
     WatchAlreadyExists {},
+    #[snafu(display(
+        "Events after revision {} are not available, the retained revisions are {} to {}",
+        revision,
+        oldest,
+        latest
+    ))]
+    EventRevisionUnavailable {
+        revision: u64,
+        oldest: u64,
+        latest: u64,
+    },
     #[snafu(display("Conflicts with existing operation - please retry"))]
     Conflict {},
     #[snafu(display("Pending deletion - please retry"))]
@@ -278,6 +303,13 @@ impl From<SvcError> for ReplyError {
                 extra: error.full_string(),
             },
 
+            SvcError::NodeRegistration { .. } => ReplyError {
+                kind: ReplyErrorKind::Unauthenticated,
+                resource: ResourceKind::Node,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+
             SvcError::GrpcConnectTimeout { .. } => ReplyError {
                 kind: ReplyErrorKind::Timeout,
                 resource: ResourceKind::Unknown,
@@ -323,6 +355,18 @@ impl From<SvcError> for ReplyError {
                 source: desc.to_string(),
                 extra: error.full_string(),
             },
+            SvcError::JsonRpcConnect { .. } => ReplyError {
+                kind: ReplyErrorKind::Unavailable,
+                resource: ResourceKind::Node,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
+            SvcError::JsonRpcNotAllowed { .. } => ReplyError {
+                kind: ReplyErrorKind::PermissionDenied,
+                resource: ResourceKind::JsonGrpc,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
             SvcError::NodeNotFound { .. } => ReplyError {
                 kind: ReplyErrorKind::NotFound,
                 resource: ResourceKind::Node,
@@ -401,6 +445,12 @@ impl From<SvcError> for ReplyError {
                 source: desc.to_string(),
                 extra: error.full_string(),
             },
+            SvcError::EventRevisionUnavailable { .. } => ReplyError {
+                kind: ReplyErrorKind::OutOfRange,
+                resource: ResourceKind::Watch,
+                source: desc.to_string(),
+                extra: error.full_string(),
+            },
             SvcError::InvalidFilter { .. } => ReplyError {
                 kind: ReplyErrorKind::Internal,
                 resource: ResourceKind::Unknown,
@@ -13,10 +13,11 @@ use std::{
 
 use async_trait::async_trait;
 use dyn_clonable::clonable;
-use futures::{future::join_all, stream::StreamExt};
-use snafu::{OptionExt, ResultExt, Snafu};
+use futures::future::join_all;
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
 use state::Container;
-use tracing::{debug, error};
+use tracing::{debug, error, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::errors::SvcError;
 use common_lib::{
@@ -29,6 +30,8 @@ use common_lib::{
 pub mod errors;
 /// Messages required by a common handler
 pub mod handler;
+/// Prometheus metrics of the agents, served over HTTP
+pub mod metrics;
 /// Version 0 of the message bus types
 pub mod v0;
 
@@ -56,6 +59,8 @@ pub enum ServiceError {
 pub struct Service {
     server: String,
     server_connected: bool,
+    bus_options: BusOptions,
+    bus: Option<DynBus>,
     channel: Channel,
     subscriptions: HashMap<String, Vec<Box<dyn ServiceSubscriber>>>,
     shared_state: std::sync::Arc<state::Container>,
@@ -66,6 +71,8 @@ impl Default for Service {
         Self {
             server: "".to_string(),
             server_connected: false,
+            bus_options: BusOptions::new(),
+            bus: None,
             channel: Default::default(),
             subscriptions: Default::default(),
             shared_state: std::sync::Arc::new(Container::new()),
@@ -129,6 +136,18 @@ impl<'a> Context<'a> {
 /// Service Request received via the message bus
 pub type Request<'a> = ReceivedRawMessage<'a>;
 
+/// Role of a subscriber for the messages which match its filter
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SubscriberRole {
+    /// Handles the message and replies back to the sender, or if it fails the
+    /// service replies with its error
+    /// Only one responder per message id is allowed on each channel
+    Responder,
+    /// Handles the message after the responder (eg: for auditing) but never
+    /// replies to the sender, and its errors are only logged
+    Observer,
+}
+
 #[async_trait]
 #[clonable]
 /// Trait which must be implemented by each subscriber with the handler
@@ -138,6 +157,10 @@ pub trait ServiceSubscriber: Clone + Send + Sync {
     async fn handler(&self, args: Arguments<'_>) -> Result<(), SvcError>;
     /// filter which identifies which messages may be routed to the handler
     fn filter(&self) -> Vec<MessageId>;
+    /// role of the subscriber, which determines whether it replies back
+    fn role(&self) -> SubscriberRole {
+        SubscriberRole::Responder
+    }
 }
 
 impl Service {
@@ -161,12 +184,39 @@ impl Service {
 
     async fn message_bus_init(&mut self) {
         if !self.server_connected {
-            // todo: parse connection options when nats has better support
-            mbus_api::message_bus_init(self.server.clone()).await;
+            match &self.bus {
+                Some(bus) => {
+                    mbus_api::message_bus_init_bus(bus.clone());
+                }
+                None => {
+                    mbus_api::message_bus_init_options(
+                        self.server.clone(),
+                        self.bus_options.clone(),
+                        TimeoutOptions::new(),
+                    )
+                    .await;
+                }
+            }
             self.server_connected = true;
         }
     }
 
+    /// Use the given message `bus` rather than connecting to the message bus
+    /// server, eg: a `LocalMessageBus` so the service and its clients can run
+    /// within the same process
+    /// Must be called before connecting to the message bus
+    pub fn with_bus(mut self, bus: impl Bus + 'static) -> Self {
+        self.bus = Some(Box::new(bus));
+        self
+    }
+
+    /// Setup the message bus connection options, eg: TLS and credentials
+    /// Must be called before connecting to the message bus
+    pub fn with_bus_options(mut self, bus_options: BusOptions) -> Self {
+        self.bus_options = bus_options;
+        self
+    }
+
     /// Setup default `channel` where `with_subscription` will listen on
     pub fn with_channel(mut self, channel: impl Into<Channel>) -> Self {
         self.channel = channel.into();
@@ -264,6 +314,10 @@ impl Service {
     }
 
     /// Add a new subscriber on the given `channel`
+    /// Any number of observers may be added for the same message id but only
+    /// one responder, so adding a second responder for a message id panics.
+    /// Observers never reply, so each message id which is observed must also
+    /// have a responder by the time the service runs (see `run`)
     pub fn with_subscription_channel(
         mut self,
         channel: Channel,
@@ -271,6 +325,21 @@ impl Service {
     ) -> Self {
         match self.subscriptions.get_mut(&channel.to_string()) {
             Some(entry) => {
+                if service_subscriber.role() == SubscriberRole::Responder {
+                    let ids = service_subscriber.filter();
+                    if let Some(id) = entry
+                        .iter()
+                        .filter(|s| s.role() == SubscriberRole::Responder)
+                        .flat_map(|s| s.filter())
+                        .find(|id| ids.contains(id))
+                    {
+                        panic!(
+                            "Message id '{}' on channel '{}' already has a responder!",
+                            id.to_string(),
+                            channel.to_string()
+                        );
+                    }
+                }
                 entry.push(Box::from(service_subscriber));
             }
             None => {
@@ -281,6 +350,29 @@ impl Service {
         self
     }
 
+    /// Message ids which only have observers on a channel, and so would never
+    /// be replied to, as `(channel, id)`
+    fn unanswered_observers(&self) -> Vec<(String, MessageId)> {
+        let mut unanswered = vec![];
+        for (channel, subscriptions) in self.subscriptions.iter() {
+            let answered = subscriptions
+                .iter()
+                .filter(|s| s.role() == SubscriberRole::Responder)
+                .flat_map(|s| s.filter())
+                .collect::<Vec<_>>();
+            for id in subscriptions
+                .iter()
+                .filter(|s| s.role() == SubscriberRole::Observer)
+                .flat_map(|s| s.filter())
+            {
+                if !answered.contains(&id) {
+                    unanswered.push((channel.clone(), id));
+                }
+            }
+        }
+        unanswered
+    }
+
     async fn run_channel(
         bus: DynBus,
         channel: Channel,
@@ -300,7 +392,23 @@ impl Service {
             let args = Arguments::new(&context, &message);
             debug!("Processing message: {{ {} }}", args.request);
 
-            if let Err(error) = Self::process_message(args, subscriptions).await {
+            // every message is handled within its own span, which continues
+            // the trace of the sender, if any
+            let span = tracing::info_span!(
+                "handle_message",
+                channel = %channel.to_string(),
+                id = %args
+                    .request
+                    .id()
+                    .map(|id| id.to_string())
+                    .unwrap_or_default()
+            );
+            span.set_parent(args.request.trace_context());
+
+            if let Err(error) = Self::process_message(args, subscriptions)
+                .instrument(span)
+                .await
+            {
                 error!("Error processing message: {}", error.full_string());
             }
         }
@@ -315,22 +423,39 @@ impl Service {
             channel: channel.clone(),
         })?;
 
-        let subscription = subscriptions
+        let matching = subscriptions
             .iter()
-            .find(|&subscriber| subscriber.filter().iter().any(|find_id| find_id == id))
-            .context(FindSubscription {
+            .filter(|&subscriber| subscriber.filter().iter().any(|find_id| find_id == id))
+            .collect::<Vec<_>>();
+        ensure!(
+            !matching.is_empty(),
+            FindSubscription {
                 channel: channel.clone(),
                 id: id.clone(),
-            })?;
+            }
+        );
 
-        match subscription.handler(arguments.clone()).await {
-            Ok(_) => Ok(()),
-            Err(error) => {
-                let result = ServiceError::HandleMessage {
-                    channel,
+        // the responder goes first so that its reply is the one the sender
+        // gets, and the observers see the message after it's been handled
+        let mut result = Ok(());
+        if let Some(responder) = matching
+            .iter()
+            .find(|s| s.role() == SubscriberRole::Responder)
+        {
+            let started = std::time::Instant::now();
+            let handled = responder.handler(arguments.clone()).await;
+            metrics::observe_bus_handler(
+                &channel.to_string(),
+                &id.to_string(),
+                started.elapsed(),
+                handled.is_ok(),
+            );
+            if let Err(error) = handled {
+                result = Err(ServiceError::HandleMessage {
+                    channel: channel.clone(),
                     id: id.clone(),
                     details: error.full_string(),
-                };
+                });
                 // respond back to the sender with an error, ignore the outcome
                 arguments
                     .request
@@ -338,9 +463,22 @@ impl Service {
                     .await
                     // ignore the outcome, since we're already in error
                     .ok();
-                Err(result)
             }
         }
+        for observer in matching
+            .iter()
+            .filter(|s| s.role() == SubscriberRole::Observer)
+        {
+            if let Err(error) = observer.handler(arguments.clone()).await {
+                error!(
+                    "Observer failed to handle message id '{}' on Channel '{}': {}",
+                    id.to_string(),
+                    channel.to_string(),
+                    error.full_string()
+                );
+            }
+        }
+        result
     }
 
     /// Runs the server which services all subscribers asynchronously until all
@@ -348,13 +486,25 @@ impl Service {
     ///
     /// subscribers are sorted according to the channel they subscribe on
     /// each channel benefits from a tokio thread which routes messages
-    /// accordingly todo: only one subscriber per message id supported at
-    /// the moment
+    /// accordingly: to the responder of the message id and then to all of
+    /// its observers, in the order they were added
+    ///
+    /// Panics if a message id has observers but no responder, since its
+    /// senders would never get a reply
     pub async fn run(&mut self) {
+        if let Some((channel, id)) = self.unanswered_observers().first() {
+            panic!(
+                "Message id '{}' on channel '{}' has observers but no responder!",
+                id.to_string(),
+                channel
+            );
+        }
         let mut threads = vec![];
 
         self.message_bus_init().await;
-        let bus = mbus_api::bus();
+        // the given bus is used even if the shared bus was initialised before,
+        // eg: by a client within the same process
+        let bus = self.bus.clone().unwrap_or_else(mbus_api::bus);
 
         for subscriptions in self.subscriptions.iter() {
             let bus = bus.clone();
@@ -362,9 +512,10 @@ impl Service {
             let subscriptions = subscriptions.1.clone();
             let state = self.shared_state.clone();
 
-            let handle = tokio::spawn(async move {
+            // the handlers reach the same bus through `mbus_api::bus`
+            let handle = tokio::spawn(mbus_api::with_bus(bus.clone(), async move {
                 Self::run_channel(bus, channel.parse().unwrap(), &subscriptions, state).await
-            });
+            }));
 
             threads.push(handle);
         }
@@ -381,3 +532,112 @@ impl Service {
             });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_lib::{
+        mbus_api::message_bus::v0::{MessageBus, MessageBusTrait},
+        types::v0::message_bus::{ChannelVs, GetNodes, Node, NodeId, Nodes},
+    };
+    use std::time::Duration;
+
+    #[derive(Clone, Default)]
+    struct ServiceHandler<T> {
+        data: std::marker::PhantomData<T>,
+    }
+
+    #[async_trait]
+    impl ServiceSubscriber for ServiceHandler<GetNodes> {
+        async fn handler(&self, args: Arguments<'_>) -> Result<(), SvcError> {
+            let request: ReceivedMessage<GetNodes> = args.request.try_into()?;
+            let node = Node {
+                id: "node-1".into(),
+                ..Default::default()
+            };
+            Ok(request.reply(Nodes(vec![node])).await?)
+        }
+        fn filter(&self) -> Vec<MessageId> {
+            vec![GetNodes::default().id()]
+        }
+    }
+
+    /// Observer which forwards the nodes requests it sees
+    #[derive(Clone)]
+    struct Observer {
+        seen: tokio::sync::mpsc::UnboundedSender<GetNodes>,
+    }
+
+    #[async_trait]
+    impl ServiceSubscriber for Observer {
+        async fn handler(&self, args: Arguments<'_>) -> Result<(), SvcError> {
+            let request: ReceivedMessage<GetNodes> = args.request.try_into()?;
+            self.seen.send(request.inner()).ok();
+            Ok(())
+        }
+        fn filter(&self) -> Vec<MessageId> {
+            vec![GetNodes::default().id()]
+        }
+        fn role(&self) -> SubscriberRole {
+            SubscriberRole::Observer
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "already has a responder")]
+    fn duplicate_responder() {
+        let _ = Service::builder("".into(), ChannelVs::Node)
+            .with_subscription(ServiceHandler::<GetNodes>::default())
+            .with_subscription(ServiceHandler::<GetNodes>::default());
+    }
+
+    #[test]
+    fn unanswered_observers() {
+        let (seen, _) = tokio::sync::mpsc::unbounded_channel();
+        let service = Service::builder("".into(), ChannelVs::Node)
+            .with_subscription(Observer { seen: seen.clone() });
+        assert_eq!(
+            service.unanswered_observers(),
+            vec![(
+                Channel::from(ChannelVs::Node).to_string(),
+                GetNodes::default().id()
+            )]
+        );
+        // the responder may also be added after the observers
+        let service = service
+            .with_subscription(Observer { seen })
+            .with_subscription(ServiceHandler::<GetNodes>::default());
+        assert!(service.unanswered_observers().is_empty());
+    }
+
+    #[tokio::test]
+    async fn local_message_bus() {
+        let timeouts = TimeoutOptions::new()
+            .with_timeout(Duration::from_millis(250))
+            .with_timeout_backoff(Duration::from_millis(250));
+        let (seen, mut observed) = tokio::sync::mpsc::unbounded_channel();
+        let mut service = Service::builder("".into(), ChannelVs::Node)
+            .with_bus(LocalMessageBus::new(timeouts))
+            .connect_message_bus()
+            .await
+            .with_default_liveness()
+            .with_subscription(Observer { seen: seen.clone() })
+            .with_subscription(ServiceHandler::<GetNodes>::default())
+            .with_subscription(Observer { seen });
+        tokio::spawn(async move { service.run().await });
+
+        // no message bus server, the service and the client share the bus
+        Liveness {}.request_on(ChannelVs::Node).await.unwrap();
+        let nodes = MessageBus::get_nodes().await.unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].id, NodeId::from("node-1"));
+
+        // both observers see the request once it's been replied to
+        for _ in 0 .. 2 {
+            tokio::time::timeout(Duration::from_secs(1), observed.recv())
+                .await
+                .expect("the observers should see the request")
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, Encoder, HistogramVec, IntCounterVec,
+    TextEncoder,
+};
+use std::{convert::Infallible, net::SocketAddr, time::Duration};
+
+lazy_static! {
+    static ref BUS_HANDLER_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "bus_handler_requests_total",
+        "Number of message bus requests handled, per channel, message id and outcome",
+        &["channel", "message_id", "outcome"]
+    )
+    .expect("metric can be registered");
+    static ref BUS_HANDLER_DURATION: HistogramVec = register_histogram_vec!(
+        "bus_handler_duration_seconds",
+        "Time taken to handle a message bus request, per channel and message id",
+        &["channel", "message_id"]
+    )
+    .expect("metric can be registered");
+}
+
+/// Record a message bus request with the message id `message_id`, received on
+/// the `channel`, which was handled in `elapsed` time
+pub(crate) fn observe_bus_handler(
+    channel: &str,
+    message_id: &str,
+    elapsed: Duration,
+    success: bool,
+) {
+    let outcome = if success { "success" } else { "failure" };
+    BUS_HANDLER_REQUESTS
+        .with_label_values(&[channel, message_id, outcome])
+        .inc();
+    BUS_HANDLER_DURATION
+        .with_label_values(&[channel, message_id])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// Encode all the metrics of the default registry in the text format
+pub fn encode() -> Result<Vec<u8>, prometheus::Error> {
+    let mut buffer = vec![];
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+    Ok(buffer)
+}
+
+async fn metrics_handler(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if request.uri().path() != "/metrics" {
+        return Ok(Response::builder()
+            .status(hyper::StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .expect("valid response"));
+    }
+    Ok(match encode() {
+        Ok(metrics) => Response::builder()
+            .header(
+                hyper::header::CONTENT_TYPE,
+                TextEncoder::new().format_type(),
+            )
+            .body(Body::from(metrics)),
+        Err(error) => Response::builder()
+            .status(hyper::StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::from(error.to_string())),
+    }
+    .expect("valid response"))
+}
+
+/// Serve the metrics on `GET /metrics` of the HTTP endpoint bound to `address`
+/// in a new tokio task, returning the address it's bound to, eg: to find the
+/// port picked by the system when binding to port 0
+pub fn serve(address: SocketAddr) -> Result<SocketAddr, hyper::Error> {
+    let service = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(metrics_handler)) });
+    let server = Server::try_bind(&address)?.serve(service);
+    let address = server.local_addr();
+    tokio::spawn(async move {
+        tracing::info!("Serving metrics on http://{}/metrics", address);
+        if let Err(error) = server.await {
+            tracing::error!("Metrics endpoint failed: {}", error);
+        }
+    });
+    Ok(address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn metrics_endpoint() {
+        observe_bus_handler("v0/node", "v0/getNodes", Duration::from_millis(5), true);
+        let address = serve("127.0.0.1:0".parse().unwrap()).unwrap();
+
+        let url = format!("http://{}", address);
+        let response = reqwest::get(format!("{}/metrics", url)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let metrics = response.text().await.unwrap();
+        assert!(metrics.contains(
+            r#"bus_handler_requests_total{channel="v0/node",message_id="v0/getNodes",outcome="success"} 1"#
+        ));
+        assert!(metrics.contains("bus_handler_duration_seconds_count"));
+
+        let response = reqwest::get(format!("{}/other", url)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+}
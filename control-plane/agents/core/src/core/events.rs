@@ -0,0 +1,385 @@
+//! Log of the resource change events which are published on the message bus,
+//! for the streaming watches of the REST service.
+//! The desired state changes come from a watch on the specs in the persistent
+//! store, and the actual state changes come from the registry poller, which
+//! compares each update of the resources with the previous one.
+//! The latest events are retained so that the streams may resume from a
+//! revision, and they're also broadcast to the watches of the core agent.
+//! The revisions are reserved in the persistent store a block at a time so
+//! they keep increasing across restarts of the core agent.
+use super::registry::Registry;
+use common::errors::SvcError;
+use common_lib::{
+    mbus_api::{message_bus_initialised, Message},
+    types::v0::{
+        message_bus::{Node, ResourceEvent, ResourceEventType, WatchResourceId, WatchType},
+        store::definitions::{
+            ObjectKey, PrefixWatchEvent, StorableObject, StorableObjectType, Store, StoreError,
+            STORE_PREFIX,
+        },
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{broadcast, Mutex, MutexGuard};
+
+/// Number of the latest events which are retained to resume the streams
+const EVENT_HISTORY: usize = 1024;
+/// Number of revisions which are reserved in the store at a time
+const REVISION_BLOCK: u64 = 1024;
+/// Delay before reissuing a lost watch on the store
+const REWATCH_DELAY: Duration = Duration::from_secs(1);
+
+/// Key of the highest event revision reserved in the store
+#[derive(Debug, Clone)]
+struct EventRevisionKey;
+
+impl ObjectKey for EventRevisionKey {
+    fn key_type(&self) -> StorableObjectType {
+        StorableObjectType::EventRevision
+    }
+    fn key_uuid(&self) -> String {
+        "core".to_string()
+    }
+}
+
+/// Highest event revision reserved in the store. The revisions are issued from
+/// it onwards after a restart so they keep increasing across restarts.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct EventRevision {
+    reserved: u64,
+}
+
+impl StorableObject for EventRevision {
+    type Key = EventRevisionKey;
+
+    fn key(&self) -> Self::Key {
+        EventRevisionKey
+    }
+}
+
+/// Resource change events with a revision which increases with every event
+#[derive(Debug, Clone)]
+pub(crate) struct EventLog {
+    inner: Arc<Mutex<EventLogInner>>,
+    /// serializes the publishing of the events so they're published in the
+    /// order of their revisions, without holding the log meanwhile
+    publisher: Arc<Mutex<()>>,
+}
+
+#[derive(Debug)]
+struct EventLogInner {
+    /// revision of the latest event
+    revision: u64,
+    /// highest revision reserved in the store
+    reserved: u64,
+    /// the latest events, oldest first
+    history: VecDeque<ResourceEvent>,
+    /// actual state of the resources as of the last registry update, unknown
+    /// until the first update
+    actual: Option<HashMap<String, (WatchResourceId, serde_json::Value)>>,
+    /// broadcasts every new event to the subscribers within the core agent
+    broadcast: broadcast::Sender<ResourceEvent>,
+}
+
+impl EventLog {
+    /// New event log, to be initialised from the store before recording events
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(EventLogInner {
+                revision: 0,
+                reserved: 0,
+                history: VecDeque::with_capacity(EVENT_HISTORY),
+                actual: None,
+                broadcast: broadcast::channel(EVENT_HISTORY).0,
+            })),
+            publisher: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Resume the revisions from the highest one reserved by the previous run
+    pub(crate) async fn init(&self, registry: &Registry) {
+        let reserved = match registry.load_obj::<EventRevision>(&EventRevisionKey).await {
+            Ok(revision) => revision.reserved,
+            Err(StoreError::MissingEntry { .. }) => 0,
+            Err(error) => panic!("Failed to load the event revision: {}", error),
+        };
+        let mut inner = self.inner.lock().await;
+        inner.revision = reserved;
+        inner.reserved = reserved;
+    }
+
+    /// Subscribe to the new events
+    pub(crate) async fn subscribe(&self) -> broadcast::Receiver<ResourceEvent> {
+        self.inner.lock().await.broadcast.subscribe()
+    }
+
+    /// Whether the registry knew of the actual `resource` as of its last update
+    pub(crate) async fn has_actual(&self, resource: &WatchResourceId) -> bool {
+        match &self.inner.lock().await.actual {
+            Some(actual) => actual.contains_key(&resource.to_string()),
+            None => false,
+        }
+    }
+
+    /// Get the retained events which followed the given `revision`
+    pub(crate) async fn since(&self, revision: u64) -> Result<Vec<ResourceEvent>, SvcError> {
+        let inner = self.inner.lock().await;
+        let oldest = inner
+            .history
+            .front()
+            .map(|event| event.revision)
+            .unwrap_or(inner.revision + 1);
+        if revision > inner.revision || revision + 1 < oldest {
+            return Err(SvcError::EventRevisionUnavailable {
+                revision,
+                oldest: oldest - 1,
+                latest: inner.revision,
+            });
+        }
+        Ok(inner
+            .history
+            .iter()
+            .filter(|event| event.revision > revision)
+            .cloned()
+            .collect())
+    }
+
+    /// Record the changes of the actual state of the resources since the last
+    /// update, where a resource which is no longer present was deleted.
+    /// The first update only sets the state which the next ones are compared
+    /// with, as the changes which happened before are unknown.
+    pub(crate) async fn update_actual(
+        &self,
+        registry: &Registry,
+        resources: Vec<(WatchResourceId, serde_json::Value)>,
+    ) {
+        let mut inner = self.inner.lock().await;
+        let previous = match &inner.actual {
+            Some(previous) => previous,
+            None => {
+                inner.actual = Some(actual_state(resources));
+                return;
+            }
+        };
+        let mut changes = vec![];
+        for (resource, value) in &resources {
+            let previous = previous.get(&resource.to_string()).map(|(_, value)| value);
+            if previous != Some(value) {
+                changes.push((
+                    resource.clone(),
+                    ResourceEventType::Put,
+                    Some(value.clone()),
+                ));
+            }
+        }
+        let actual = actual_state(resources);
+        for (key, (resource, _)) in previous {
+            if !actual.contains_key(key) {
+                changes.push((resource.clone(), ResourceEventType::Delete, None));
+            }
+        }
+
+        // when the revisions cannot be reserved the actual state is left as it
+        // was, so the changes are recorded by the next update instead
+        if inner.reserve(registry, changes.len()).await {
+            inner.actual = Some(actual);
+            let events = changes
+                .into_iter()
+                .map(|(resource, event_type, value)| {
+                    inner.record(resource, event_type, WatchType::Actual, value)
+                })
+                .collect();
+            self.publish(inner, events).await;
+        }
+    }
+
+    /// Record a change of the specs in the persistent store
+    async fn update_desired(&self, registry: &Registry, event: PrefixWatchEvent) {
+        if let Some(resource) = spec_resource(&event.key) {
+            let event_type = match event.value {
+                Some(_) => ResourceEventType::Put,
+                None => ResourceEventType::Delete,
+            };
+            loop {
+                let mut inner = self.inner.lock().await;
+                if inner.reserve(registry, 1).await {
+                    let event = inner.record(resource, event_type, WatchType::Desired, event.value);
+                    self.publish(inner, vec![event]).await;
+                    return;
+                }
+                drop(inner);
+                tokio::time::sleep(REWATCH_DELAY).await;
+            }
+        }
+    }
+
+    /// Publish the recorded `events` on the message bus, once the log is
+    /// released
+    async fn publish(&self, inner: MutexGuard<'_, EventLogInner>, events: Vec<ResourceEvent>) {
+        // the registry starts before the message bus is connected
+        if events.is_empty() || !message_bus_initialised() {
+            return;
+        }
+        let _publisher = self.publisher.lock().await;
+        drop(inner);
+        for event in events {
+            if let Err(error) = event.publish().await {
+                tracing::error!(
+                    "Failed to publish the resource event {}: {}",
+                    event.revision,
+                    error
+                );
+            }
+        }
+    }
+
+    /// Watch the specs in the persistent store, reissuing the watch from the
+    /// last seen store revision whenever it's lost so no change is missed
+    pub(crate) fn watch_specs(&self, registry: Registry) {
+        let events = self.clone();
+        tokio::spawn(async move {
+            let mut revision = None;
+            loop {
+                let watch = {
+                    let mut store = registry.store.lock().await;
+                    store.watch_prefix(STORE_PREFIX, revision).await
+                };
+                match watch {
+                    Ok(mut receiver) => {
+                        while let Some(event) = receiver.recv().await {
+                            match event {
+                                Ok(event) => {
+                                    revision = Some(event.revision + 1);
+                                    events.update_desired(&registry, event).await;
+                                }
+                                Err(error) => {
+                                    tracing::error!("Failed to watch the specs: {}", error)
+                                }
+                            }
+                        }
+                    }
+                    Err(error) => tracing::error!("Failed to watch the specs: {}", error),
+                }
+                tokio::time::sleep(REWATCH_DELAY).await;
+            }
+        });
+    }
+}
+
+/// The actual state of the `resources` by their key
+fn actual_state(
+    resources: Vec<(WatchResourceId, serde_json::Value)>,
+) -> HashMap<String, (WatchResourceId, serde_json::Value)> {
+    resources
+        .into_iter()
+        .map(|(resource, value)| (resource.to_string(), (resource, value)))
+        .collect()
+}
+
+impl EventLogInner {
+    /// Make sure that `count` more revisions are reserved in the store, so
+    /// they're never issued again after a restart
+    async fn reserve(&mut self, registry: &Registry, count: usize) -> bool {
+        let needed = self.revision + count as u64;
+        if needed <= self.reserved {
+            return true;
+        }
+        let reserved = needed + REVISION_BLOCK;
+        match registry.store_obj(&EventRevision { reserved }).await {
+            Ok(()) => {
+                self.reserved = reserved;
+                true
+            }
+            Err(error) => {
+                tracing::error!("Failed to reserve the event revisions: {}", error);
+                false
+            }
+        }
+    }
+
+    /// Record a new event with the next reserved revision
+    fn record(
+        &mut self,
+        resource: WatchResourceId,
+        event_type: ResourceEventType,
+        watch_type: WatchType,
+        value: Option<serde_json::Value>,
+    ) -> ResourceEvent {
+        self.revision += 1;
+        let event = ResourceEvent {
+            revision: self.revision,
+            resource,
+            event_type,
+            watch_type,
+            value,
+        };
+        if self.history.len() == EVENT_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(event.clone());
+        // there may be no subscribers, which is fine
+        self.broadcast.send(event.clone()).ok();
+        event
+    }
+}
+
+/// Map the key of a spec in the persistent store to the resource it specifies
+fn spec_resource(key: &str) -> Option<WatchResourceId> {
+    let mut parts = key
+        .strip_prefix(STORE_PREFIX)?
+        .strip_prefix('/')?
+        .splitn(2, '/');
+    let object_type = StorableObjectType::from_str(parts.next()?).ok()?;
+    let id = parts.next()?;
+    match object_type {
+        StorableObjectType::NodeSpec => Some(WatchResourceId::Node(id.into())),
+        StorableObjectType::PoolSpec => Some(WatchResourceId::Pool(id.into())),
+        StorableObjectType::ReplicaSpec => Some(WatchResourceId::Replica(id.into())),
+        StorableObjectType::NexusSpec => Some(WatchResourceId::Nexus(id.into())),
+        StorableObjectType::VolumeSpec => Some(WatchResourceId::Volume(id.into())),
+        _ => None,
+    }
+}
+
+impl Registry {
+    /// Get the actual state of all the resources, as known by the registry
+    pub(crate) async fn actual_resources(&self) -> Vec<(WatchResourceId, serde_json::Value)> {
+        fn actual(
+            resource: WatchResourceId,
+            value: impl Serialize,
+        ) -> Option<(WatchResourceId, serde_json::Value)> {
+            serde_json::to_value(value)
+                .ok()
+                .map(|value| (resource, value))
+        }
+
+        let mut resources = vec![];
+        for node in self.get_nodes_wrapper().await {
+            let node = Node::from(&*node.lock().await);
+            resources.extend(actual(WatchResourceId::Node(node.id.clone()), node));
+        }
+        for pool in self.get_pools_inner().await.unwrap_or_default() {
+            resources.extend(actual(WatchResourceId::Pool(pool.id.clone()), pool));
+        }
+        for replica in self.get_replicas().await.unwrap_or_default() {
+            resources.extend(actual(
+                WatchResourceId::Replica(replica.uuid.clone()),
+                replica,
+            ));
+        }
+        for nexus in self.get_nexuses().await {
+            resources.extend(actual(WatchResourceId::Nexus(nexus.uuid.clone()), nexus));
+        }
+        for volume in self.get_volumes_status().await {
+            resources.extend(actual(WatchResourceId::Volume(volume.uuid.clone()), volume));
+        }
+        resources
+    }
+}
@@ -64,6 +64,7 @@ pub(crate) struct GrpcClient {
 }
 pub(crate) type MayaClient = MayastorClient<Channel>;
 impl GrpcClient {
+    #[tracing::instrument(level = "debug", skip(context), fields(node = %context.node), err)]
     pub(crate) async fn new(context: &GrpcContext) -> Result<Self, SvcError> {
         let client = match tokio::time::timeout(
             context.comms_timeouts.connect(),
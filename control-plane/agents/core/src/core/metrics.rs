@@ -0,0 +1,126 @@
+use super::registry::Registry;
+use common_lib::types::v0::message_bus::NodeState;
+use lazy_static::lazy_static;
+use prometheus::{
+    register_gauge_vec, register_histogram, register_histogram_vec, register_int_counter_vec,
+    register_int_gauge_vec, GaugeVec, Histogram, HistogramVec, IntCounterVec, IntGaugeVec,
+};
+use std::{collections::HashMap, time::Duration};
+
+lazy_static! {
+    static ref POOL_CAPACITY: GaugeVec = register_gauge_vec!(
+        "pool_capacity_bytes",
+        "Capacity of the pool in bytes",
+        &["node", "pool"]
+    )
+    .expect("metric can be registered");
+    static ref POOL_USED: GaugeVec = register_gauge_vec!(
+        "pool_used_bytes",
+        "Used space of the pool in bytes",
+        &["node", "pool"]
+    )
+    .expect("metric can be registered");
+    static ref RESOURCES: IntGaugeVec = register_int_gauge_vec!(
+        "core_resources",
+        "Number of resources of each kind, by state",
+        &["kind", "state"]
+    )
+    .expect("metric can be registered");
+    static ref RECONCILE_DURATION: Histogram = register_histogram!(
+        "reconcile_duration_seconds",
+        "Time taken by a pass of the dirty specs reconcile loop"
+    )
+    .expect("metric can be registered");
+    static ref RECONCILE_DIRTY_SPECS: IntGaugeVec = register_int_gauge_vec!(
+        "reconcile_dirty_specs",
+        "Number of dirty specs found by the last pass of the reconcile loop",
+        &["kind"]
+    )
+    .expect("metric can be registered");
+    static ref STORE_DURATION: HistogramVec = register_histogram_vec!(
+        "store_operation_duration_seconds",
+        "Time taken by the persistent store operations",
+        &["operation"]
+    )
+    .expect("metric can be registered");
+    static ref STORE_ERRORS: IntCounterVec = register_int_counter_vec!(
+        "store_operation_errors_total",
+        "Number of failed persistent store operations",
+        &["operation"]
+    )
+    .expect("metric can be registered");
+}
+
+/// Record a persistent store `operation` which took `elapsed` time
+pub(crate) fn observe_store(operation: &str, elapsed: Duration, success: bool) {
+    STORE_DURATION
+        .with_label_values(&[operation])
+        .observe(elapsed.as_secs_f64());
+    if !success {
+        STORE_ERRORS.with_label_values(&[operation]).inc();
+    }
+}
+
+/// Record a pass of the reconcile loop which took `elapsed` time and found
+/// the given number of dirty replica and nexus specs
+pub(crate) fn observe_reconcile(elapsed: Duration, dirty_replicas: usize, dirty_nexuses: usize) {
+    RECONCILE_DURATION.observe(elapsed.as_secs_f64());
+    RECONCILE_DIRTY_SPECS
+        .with_label_values(&["replica"])
+        .set(dirty_replicas as i64);
+    RECONCILE_DIRTY_SPECS
+        .with_label_values(&["nexus"])
+        .set(dirty_nexuses as i64);
+}
+
+/// Update the pool and resource gauges from the current contents of the
+/// `registry`
+pub(crate) async fn update_registry_gauges(registry: &Registry) {
+    let mut counts = HashMap::<(&str, String), i64>::new();
+    // every node state is reported, even if there are no nodes in it
+    for state in &[
+        NodeState::Unknown,
+        NodeState::Online,
+        NodeState::Suspect,
+        NodeState::Flapping,
+        NodeState::Offline,
+    ] {
+        counts.insert(("node", state.to_string()), 0);
+    }
+
+    POOL_CAPACITY.reset();
+    POOL_USED.reset();
+    let nodes = registry.get_nodes_wrapper().await;
+    for node in nodes {
+        let node = node.lock().await;
+        *counts.entry(("node", node.state.to_string())).or_default() += 1;
+        for pool in node.pools() {
+            let labels = [pool.node.as_str(), pool.id.as_str()];
+            POOL_CAPACITY
+                .with_label_values(&labels)
+                .set(pool.capacity as f64);
+            POOL_USED.with_label_values(&labels).set(pool.used as f64);
+            *counts.entry(("pool", pool.state.to_string())).or_default() += 1;
+            for replica in pool.replicas() {
+                *counts
+                    .entry(("replica", replica.state.to_string()))
+                    .or_default() += 1;
+            }
+        }
+    }
+    for nexus in registry.get_nexuses().await {
+        *counts
+            .entry(("nexus", nexus.state.to_string()))
+            .or_default() += 1;
+    }
+    for volume in registry.get_volumes_status().await {
+        *counts
+            .entry(("volume", volume.state.to_string()))
+            .or_default() += 1;
+    }
+
+    RESOURCES.reset();
+    for ((kind, state), count) in counts {
+        RESOURCES.with_label_values(&[kind, &state]).set(count);
+    }
+}
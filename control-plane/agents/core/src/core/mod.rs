@@ -1,7 +1,11 @@
 //! Common modules used by the different core services
 
+/// resource change events of the registry
+pub(crate) mod events;
 /// gRPC helpers
 pub mod grpc;
+/// prometheus metrics of the registry and its resources
+pub(crate) mod metrics;
 /// registry with node and all its resources
 pub mod registry;
 /// generic resources
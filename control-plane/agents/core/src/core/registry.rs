@@ -13,11 +13,13 @@
 //!
 //! Each instance also contains the known nexus, pools and replicas that live in
 //! said instance.
-use super::{specs::*, states::ResourceStatesLocked, wrapper::NodeWrapper};
+use super::{
+    events::EventLog, metrics, specs::*, states::ResourceStatesLocked, wrapper::NodeWrapper,
+};
 use crate::core::wrapper::InternalOps;
 use common::errors::SvcError;
 use common_lib::{
-    store::etcd::Etcd,
+    store::{etcd::Etcd, StoreEngine},
     types::v0::{
         message_bus::NodeId,
         store::definitions::{StorableObject, Store, StoreError, StoreKey},
@@ -27,7 +29,7 @@ use std::{collections::HashMap, ops::DerefMut, sync::Arc};
 use tokio::sync::{Mutex, RwLock};
 
 /// Registry containing all mayastor instances (aka nodes)
-pub type Registry = RegistryInner<Etcd>;
+pub type Registry = RegistryInner<StoreEngine>;
 
 /// Generic Registry Inner with a Store trait
 #[derive(Clone, Debug)]
@@ -38,6 +40,8 @@ pub struct RegistryInner<S: Store> {
     pub(crate) specs: ResourceSpecsLocked,
     /// state (aka actual state) of the various resources
     pub(crate) states: ResourceStatesLocked,
+    /// change events of the desired and actual state of the resources
+    pub(crate) events: EventLog,
     /// period to refresh the cache
     cache_period: std::time::Duration,
     pub(crate) store: Arc<Mutex<S>>,
@@ -63,10 +67,30 @@ impl Registry {
         let store = Etcd::new(&store_url)
             .await
             .expect("Should connect to the persistent store");
+        Self::with_store(
+            cache_period,
+            store.into(),
+            store_timeout,
+            reconcile_period,
+            reconcile_idle_period,
+        )
+        .await
+    }
+
+    /// Create a new registry as with `new` but over the given `store`, eg: an
+    /// in-memory store to run the core agent within a test process
+    pub async fn with_store(
+        cache_period: std::time::Duration,
+        store: StoreEngine,
+        store_timeout: std::time::Duration,
+        reconcile_period: std::time::Duration,
+        reconcile_idle_period: std::time::Duration,
+    ) -> Self {
         let registry = Self {
             nodes: Default::default(),
             specs: ResourceSpecsLocked::new(),
             states: ResourceStatesLocked::new(),
+            events: EventLog::new(),
             cache_period,
             store: Arc::new(Mutex::new(store)),
             store_timeout,
@@ -79,66 +103,128 @@ impl Registry {
 
     /// Serialized write to the persistent store
     pub async fn store_obj<O: StorableObject>(&self, object: &O) -> Result<(), SvcError> {
+        let started = std::time::Instant::now();
         let mut store = self.store.lock().await;
-        match tokio::time::timeout(
-            self.store_timeout,
-            async move { store.put_obj(object).await },
-        )
-        .await
-        {
-            Ok(_) => Ok(()),
-            Err(_) => Err(StoreError::Timeout {
-                operation: "Put".to_string(),
-                timeout: self.store_timeout,
-            }
-            .into()),
-        }
+        let result =
+            match tokio::time::timeout(
+                self.store_timeout,
+                async move { store.put_obj(object).await },
+            )
+            .await
+            {
+                Ok(result) => result.map_err(SvcError::from),
+                Err(_) => Err(StoreError::Timeout {
+                    operation: "Put".to_string(),
+                    timeout: self.store_timeout,
+                }
+                .into()),
+            };
+        metrics::observe_store("put", started.elapsed(), result.is_ok());
+        result
+    }
+
+    /// Serialized read of the value of the `key` from the persistent store
+    pub async fn load_kv<K: StoreKey>(&self, key: &K) -> Result<serde_json::Value, StoreError> {
+        let started = std::time::Instant::now();
+        let mut store = self.store.lock().await;
+        let result =
+            match tokio::time::timeout(self.store_timeout, async move { store.get_kv(key).await })
+                .await
+            {
+                Ok(result) => result,
+                Err(_) => Err(StoreError::Timeout {
+                    operation: "Get".to_string(),
+                    timeout: self.store_timeout,
+                }),
+            };
+        // a missing entry is not a failure of the store
+        let success = matches!(result, Ok(_) | Err(StoreError::MissingEntry { .. }));
+        metrics::observe_store("get", started.elapsed(), success);
+        result
+    }
+
+    /// Serialized read of the object with the `key` from the persistent store
+    pub async fn load_obj<O: StorableObject>(&self, key: &O::Key) -> Result<O, StoreError> {
+        let started = std::time::Instant::now();
+        let mut store = self.store.lock().await;
+        let result =
+            match tokio::time::timeout(self.store_timeout, async move { store.get_obj(key).await })
+                .await
+            {
+                Ok(result) => result,
+                Err(_) => Err(StoreError::Timeout {
+                    operation: "Get".to_string(),
+                    timeout: self.store_timeout,
+                }),
+            };
+        let success = matches!(result, Ok(_) | Err(StoreError::MissingEntry { .. }));
+        metrics::observe_store("get", started.elapsed(), success);
+        result
     }
 
     /// Serialized delete to the persistent store
     pub async fn delete_kv<K: StoreKey>(&self, key: &K) -> Result<(), SvcError> {
+        let started = std::time::Instant::now();
         let mut store = self.store.lock().await;
-        match tokio::time::timeout(
-            self.store_timeout,
-            async move { store.delete_kv(key).await },
-        )
-        .await
-        {
-            Ok(result) => match result {
-                Ok(_) => Ok(()),
-                // already deleted, no problem
-                Err(StoreError::MissingEntry { .. }) => Ok(()),
-                Err(error) => Err(SvcError::from(error)),
-            },
-            Err(_) => Err(SvcError::from(StoreError::Timeout {
-                operation: "Delete".to_string(),
-                timeout: self.store_timeout,
-            })),
-        }
+        let result =
+            match tokio::time::timeout(
+                self.store_timeout,
+                async move { store.delete_kv(key).await },
+            )
+            .await
+            {
+                Ok(result) => match result {
+                    Ok(_) => Ok(()),
+                    // already deleted, no problem
+                    Err(StoreError::MissingEntry { .. }) => Ok(()),
+                    Err(error) => Err(SvcError::from(error)),
+                },
+                Err(_) => Err(SvcError::from(StoreError::Timeout {
+                    operation: "Delete".to_string(),
+                    timeout: self.store_timeout,
+                })),
+            };
+        metrics::observe_store("delete", started.elapsed(), result.is_ok());
+        result
     }
 
     /// Check if the persistent store is currently online
     pub async fn store_online(&self) -> bool {
+        let started = std::time::Instant::now();
         let mut store = self.store.lock().await;
-        tokio::time::timeout(self.store_timeout, async move { store.online().await })
+        let online = tokio::time::timeout(self.store_timeout, async move { store.online().await })
             .await
-            .unwrap_or(false)
+            .unwrap_or(false);
+        metrics::observe_store("online", started.elapsed(), online);
+        online
     }
 
     /// Start the worker thread which updates the registry
     async fn start(&self) {
         self.init().await;
+        self.events.init(self).await;
         let registry = self.clone();
         tokio::spawn(async move {
             registry.poller().await;
         });
         self.specs.start(self.clone());
+        self.events.watch_specs(self.clone());
     }
 
     /// Initialise the registry with the content of the persistent store.
     async fn init(&self) {
+        let started = std::time::Instant::now();
         let mut store = self.store.lock().await;
-        self.specs.init(store.deref_mut()).await;
+        let result = self.specs.init(store.deref_mut()).await;
+        metrics::observe_store("get_prefix", started.elapsed(), result.is_ok());
+        // the specs are only ever read in bulk on startup, failing which the
+        // core agent does not start at all
+        if let Err(e) = result {
+            panic!(
+                "Failed to initialise resource specs. Err {}.",
+                e.to_string()
+            );
+        }
     }
 
     /// Poll each node for resource updates
@@ -156,6 +242,10 @@ impl Registry {
                 }
             }
             self.trace_all().await;
+            metrics::update_registry_gauges(self).await;
+            self.events
+                .update_actual(self, self.actual_resources().await)
+                .await;
             tokio::time::sleep(self.cache_period).await;
         }
     }
@@ -26,7 +26,7 @@ use snafu::{ResultExt, Snafu};
 use std::fmt::Debug;
 
 #[derive(Debug, Snafu)]
-enum SpecError {
+pub(crate) enum SpecError {
     /// Failed to get entries from the persistent store.
     #[snafu(display("Failed to get entries from store. Error {}", source))]
     StoreGet { source: StoreError },
@@ -501,7 +501,7 @@ impl ResourceSpecsLocked {
     }
 
     /// Initialise the resource specs with the content from the persistent store.
-    pub(crate) async fn init<S: Store>(&self, store: &mut S) {
+    pub(crate) async fn init<S: Store>(&self, store: &mut S) -> Result<(), SpecError> {
         let spec_types = [
             StorableObjectType::VolumeSpec,
             StorableObjectType::NodeSpec,
@@ -510,13 +510,9 @@ impl ResourceSpecsLocked {
             StorableObjectType::ReplicaSpec,
         ];
         for spec in &spec_types {
-            if let Err(e) = self.populate_specs(store, *spec).await {
-                panic!(
-                    "Failed to initialise resource specs. Err {}.",
-                    e.to_string()
-                );
-            }
+            self.populate_specs(store, *spec).await?;
         }
+        Ok(())
     }
 
     /// Deserialise a vector of serde_json values into specific spec types.
@@ -609,11 +605,21 @@ impl ResourceSpecsLocked {
         tokio::spawn(async move { this.reconcile_dirty_specs(registry).await });
     }
 
+    /// Number of `specs` which are dirty
+    fn count_dirty<T: SpecOperations>(specs: Vec<Arc<Mutex<T>>>) -> usize {
+        specs.iter().filter(|spec| spec.lock().dirty()).count()
+    }
+
     /// Reconcile dirty specs to the persistent store
     async fn reconcile_dirty_specs(&self, registry: Registry) {
         loop {
+            let started = std::time::Instant::now();
+            let found_replicas = Self::count_dirty(self.get_replicas());
+            let found_nexuses = Self::count_dirty(self.get_nexuses());
+
             let dirty_replicas = self.reconcile_dirty_replicas(&registry).await;
             let dirty_nexuses = self.reconcile_dirty_nexuses(&registry).await;
+            super::metrics::observe_reconcile(started.elapsed(), found_replicas, found_nexuses);
 
             let period = if dirty_nexuses || dirty_replicas {
                 registry.reconcile_period
@@ -1,4 +1,10 @@
-use super::{super::node::watchdog::Watchdog, grpc::GrpcContext};
+use super::{
+    super::node::{
+        damping::{NodeStateDamping, NodeStateHistory},
+        watchdog::Watchdog,
+    },
+    grpc::GrpcContext,
+};
 use common::{
     errors::{GrpcRequestError, SvcError},
     v0::msg_translation::{MessageBusToRpc, RpcToMessageBus},
@@ -7,14 +13,14 @@ use common_lib::{
     mbus_api::ResourceKind,
     types::v0::message_bus::{
         AddNexusChild, Child, ChildUri, CreateNexus, CreatePool, CreateReplica, DestroyNexus,
-        DestroyPool, DestroyReplica, Nexus, NexusId, Node, NodeId, NodeState, Pool, PoolId,
-        PoolState, Protocol, RemoveNexusChild, Replica, ReplicaId, ShareNexus, ShareReplica,
-        UnshareNexus, UnshareReplica,
+        DestroyPool, DestroyReplica, Nexus, NexusId, Node, NodeId, NodeState, NodeStateTransition,
+        Pool, PoolId, PoolState, Protocol, Register, RemoveNexusChild, Replica, ReplicaId,
+        ShareNexus, ShareReplica, UnshareNexus, UnshareReplica,
     },
 };
 use rpc::mayastor::Null;
 use snafu::ResultExt;
-use std::{cmp::Ordering, collections::HashMap};
+use std::{cmp::Ordering, collections::HashMap, time::SystemTime};
 
 /// Wrapper over a `Node` plus a few useful methods/properties. Includes:
 /// all pools and replicas from the node
@@ -26,6 +32,10 @@ pub(crate) struct NodeWrapper {
     node: Node,
     /// watchdog to track the node state
     watchdog: Watchdog,
+    /// damping of the node state transitions
+    damping: NodeStateDamping,
+    /// history of the node state transitions
+    history: NodeStateHistory,
     /// gRPC CRUD lock
     lock: Arc<tokio::sync::Mutex<()>>,
     /// node communication timeouts
@@ -38,15 +48,19 @@ pub(crate) struct NodeWrapper {
 
 impl NodeWrapper {
     /// Create a new wrapper for a `Node` with a `deadline` for its watchdog
+    /// and the `damping` of its state transitions
     pub(crate) fn new(
         node: &Node,
         deadline: std::time::Duration,
+        damping: NodeStateDamping,
         comms_timeouts: NodeCommsTimeout,
     ) -> Self {
         tracing::debug!("Creating new node {:?}", node);
         Self {
             node: node.clone(),
-            watchdog: Watchdog::new(&node.id, deadline),
+            watchdog: Watchdog::new(&node.id, deadline, damping.grace()),
+            damping,
+            history: Default::default(),
             pools: Default::default(),
             nexuses: Default::default(),
             lock: Default::default(),
@@ -69,21 +83,58 @@ impl NodeWrapper {
         )
     }
 
-    /// Whether the watchdog deadline has expired
-    pub(crate) fn registration_expired(&self) -> bool {
-        self.watchdog.timestamp().elapsed() > self.watchdog.deadline()
-    }
-
     /// On_register callback when the node is registered with the registry
-    pub(crate) async fn on_register(&mut self) {
+    /// A gRPC endpoint change is only accepted if the registration was
+    /// `authenticated`, since its token vouches for the endpoint, otherwise the
+    /// current endpoint is kept
+    pub(crate) async fn on_register(&mut self, registration: &Register, authenticated: bool) {
+        if self.node.grpc_endpoint != registration.grpc_endpoint {
+            if authenticated {
+                tracing::warn!(
+                    "Node '{}' changing gRPC endpoint from '{}' to '{}'",
+                    self.node.id,
+                    self.node.grpc_endpoint,
+                    registration.grpc_endpoint
+                );
+                self.node.grpc_endpoint = registration.grpc_endpoint.clone();
+            } else {
+                tracing::warn!(
+                    "Node '{}' registered with gRPC endpoint '{}' but keeps '{}' since the registration is not authenticated",
+                    self.node.id,
+                    registration.grpc_endpoint,
+                    self.node.grpc_endpoint
+                );
+            }
+        }
+        if self.node.version != registration.version
+            || self.node.capabilities != registration.capabilities
+        {
+            tracing::info!(
+                "Node '{}' registered with version {:?} and capabilities {:?}",
+                self.node.id,
+                registration.version,
+                registration.capabilities
+            );
+            self.node.version = registration.version.clone();
+            self.node.capabilities = registration.capabilities.clone();
+        }
         self.watchdog.pet().await.ok();
-        self.set_state(NodeState::Online);
+        let state =
+            self.damping
+                .registered_state(&self.node.state, &self.history, SystemTime::now());
+        self.set_state(state);
     }
 
     /// Update the node state based on the watchdog
+    /// A node which misses its deadline is first deemed Suspect and only
+    /// deemed Offline when it also misses the grace period
     pub(crate) fn update(&mut self) {
-        if self.registration_expired() {
-            self.set_state(NodeState::Offline);
+        if let Some(state) = self.damping.expired_state(
+            &self.node.state,
+            self.watchdog.timestamp().elapsed(),
+            self.watchdog.deadline(),
+        ) {
+            self.set_state(state);
         }
     }
 
@@ -96,16 +147,31 @@ impl NodeWrapper {
                 self.node.state.to_string(),
                 state.to_string(),
             );
+            // the pools of a suspect node are left alone as it may well come
+            // back before the grace period elapses
+            let damped = state == NodeState::Suspect
+                || (self.node.state == NodeState::Suspect && state == NodeState::Online);
+            self.history.push(NodeStateTransition::new(
+                self.node.state.clone(),
+                state.clone(),
+            ));
             self.node.state = state;
             if self.node.state == NodeState::Unknown {
                 self.watchdog.disarm()
             }
-            for (_, pool) in self.pools.iter_mut() {
-                pool.set_unknown();
+            if !damped {
+                for (_, pool) in self.pools.iter_mut() {
+                    pool.set_unknown();
+                }
             }
         }
     }
 
+    /// Get the history of the node state transitions, oldest first
+    pub(crate) fn history(&self) -> Vec<NodeStateTransition> {
+        self.history.transitions()
+    }
+
     /// Get a mutable reference to the node's watchdog
     pub(crate) fn watchdog_mut(&mut self) -> &mut Watchdog {
         &mut self.watchdog
@@ -146,9 +212,19 @@ impl NodeWrapper {
         self.node.state == NodeState::Online
     }
 
-    /// Reload the node by fetching information from mayastor
+    /// Is the node still keeping alive or about to be deemed offline, so that
+    /// it's worth reaching out to it
+    pub(crate) fn is_reachable(&self) -> bool {
+        matches!(
+            self.node.state,
+            NodeState::Online | NodeState::Suspect | NodeState::Flapping
+        )
+    }
+
+    /// Reload the node by fetching information from mayastor, unless it's
+    /// deemed offline
     pub(crate) async fn reload(&mut self, registry: &Registry) -> Result<(), SvcError> {
-        if self.is_online() {
+        if self.is_reachable() {
             tracing::trace!("Reloading node '{}'", self.id);
 
             let replicas = self.fetch_replicas().await?;
@@ -276,6 +352,7 @@ impl NodeWrapper {
     }
 
     /// Fetch all replicas from this node via gRPC
+    #[tracing::instrument(level = "debug", skip(self), fields(node = %self.id), err)]
     pub(crate) async fn fetch_replicas(&self) -> Result<Vec<Replica>, SvcError> {
         let mut ctx = self.grpc_client().await?;
         let rpc_replicas = ctx
@@ -294,6 +371,7 @@ impl NodeWrapper {
         Ok(pools)
     }
     /// Fetch all pools from this node via gRPC
+    #[tracing::instrument(level = "debug", skip(self), fields(node = %self.id), err)]
     pub(crate) async fn fetch_pools(&self) -> Result<Vec<Pool>, SvcError> {
         let mut ctx = self.grpc_client().await?;
         let rpc_pools = ctx
@@ -312,6 +390,7 @@ impl NodeWrapper {
         Ok(pools)
     }
     /// Fetch all nexuses from the node via gRPC
+    #[tracing::instrument(level = "debug", skip(self), fields(node = %self.id), err)]
     pub(crate) async fn fetch_nexuses(&self) -> Result<Vec<Nexus>, SvcError> {
         let mut ctx = self.grpc_client().await?;
         let rpc_nexuses = ctx
@@ -444,6 +523,7 @@ impl InternalOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
 
 #[async_trait]
 impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn create_pool(&self, request: &CreatePool) -> Result<Pool, SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let rpc_pool =
@@ -460,6 +540,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
         Ok(pool)
     }
     /// Destroy a pool on the node via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn destroy_pool(&self, request: &DestroyPool) -> Result<(), SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let _ = ctx
@@ -475,6 +556,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Create a replica on the pool via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn create_replica(&self, request: &CreateReplica) -> Result<Replica, SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let rpc_replica =
@@ -492,6 +574,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Share a replica on the pool via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn share_replica(&self, request: &ShareReplica) -> Result<String, SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let share = ctx
@@ -514,6 +597,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Unshare a replica on the pool via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn unshare_replica(&self, request: &UnshareReplica) -> Result<String, SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let local_uri = ctx
@@ -533,6 +617,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Destroy a replica on the pool via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn destroy_replica(&self, request: &DestroyReplica) -> Result<(), SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let _ = ctx
@@ -550,6 +635,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Create a nexus on the node via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn create_nexus(&self, request: &CreateNexus) -> Result<Nexus, SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let rpc_nexus =
@@ -566,6 +652,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Destroy a nexus on the node via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn destroy_nexus(&self, request: &DestroyNexus) -> Result<(), SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let _ = ctx
@@ -581,6 +668,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Share a nexus on the node via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn share_nexus(&self, request: &ShareNexus) -> Result<String, SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let share = ctx
@@ -599,6 +687,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Unshare a nexus on the node via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn unshare_nexus(&self, request: &UnshareNexus) -> Result<(), SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let _ = ctx
@@ -614,6 +703,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Add a child to a nexus via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn add_child(&self, request: &AddNexusChild) -> Result<Child, SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let rpc_child =
@@ -630,6 +720,7 @@ impl ClientOps for Arc<tokio::sync::Mutex<NodeWrapper>> {
     }
 
     /// Remove a child from its parent nexus via gRPC
+    #[tracing::instrument(level = "info", skip(self), err)]
     async fn remove_child(&self, request: &RemoveNexusChild) -> Result<(), SvcError> {
         let mut ctx = self.grpc_client_locked().await?;
         let _ = ctx
@@ -33,6 +33,23 @@ impl Service {
                 let nexus = self.registry.get_nexus(&nexus_id).await?;
                 vec![nexus]
             }
+            Filter::Selector(selector) => {
+                // the nexuses inherit the labels of their volume
+                let labels = self.registry.specs.get_nexus_labels();
+                let nexuses = self
+                    .registry
+                    .get_node_opt_nexuses(None)
+                    .await?
+                    .into_iter()
+                    .filter(|nexus| {
+                        let labels = labels
+                            .get(&nexus.uuid)
+                            .map(Vec::as_slice)
+                            .unwrap_or_default();
+                        selector.matches(&nexus.state.to_string(), labels)
+                    });
+                selector.paginate(nexuses, |nexus| nexus.uuid.to_string())
+            }
             _ => return Err(SvcError::InvalidFilter { filter }),
         };
         Ok(Nexuses(nexuses))
@@ -103,7 +103,7 @@ fn bus_timeout_opts() -> TimeoutOptions {
 
 /// Get the nexus spec
 async fn nexus_spec(replica: &Nexus) -> Option<NexusSpec> {
-    let specs = GetSpecs {}.request().await.unwrap().nexuses;
+    let specs = GetSpecs::default().request().await.unwrap().nexuses;
     specs.iter().find(|r| r.uuid == replica.uuid).cloned()
 }
 
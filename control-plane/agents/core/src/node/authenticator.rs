@@ -0,0 +1,192 @@
+use common::errors::SvcError;
+use common_lib::types::v0::message_bus::{NodeId, Register};
+use parking_lot::Mutex;
+use ring::hmac;
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Verifies the identity of the mayastor instances which register with the
+/// control plane.
+/// Each node is handed out its node key: the HMAC-SHA256 of its node id, keyed
+/// with the node registration secret, so that the secret itself is not shared
+/// with the nodes. A node proves its identity with a token: the hex encoded
+/// HMAC-SHA256 of `id|grpc_endpoint|timestamp`, keyed with its node key.
+/// The token therefore only vouches for the endpoint it was signed for, and it
+/// is rejected once it's older than the maximum age or if it's not newer than
+/// the last token accepted for the node, so that it can't be replayed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NodeAuthenticator {
+    key: Option<hmac::Key>,
+    /// maximum difference between the timestamp of a token and the time at
+    /// which it's verified
+    max_age: Duration,
+    /// timestamp of the last token accepted for each node
+    last_timestamps: Arc<Mutex<HashMap<NodeId, u64>>>,
+}
+
+impl NodeAuthenticator {
+    /// New authenticator which verifies the node tokens with the `secret`,
+    /// rejecting the tokens which are older than `max_age`
+    pub(crate) fn new(secret: &[u8], max_age: Duration) -> Self {
+        Self {
+            key: Some(hmac::Key::new(hmac::HMAC_SHA256, secret)),
+            max_age,
+            last_timestamps: Default::default(),
+        }
+    }
+
+    /// New authenticator with the secret read from the file at `path`
+    /// (trailing whitespace is not part of the secret)
+    pub(crate) fn from_file(path: &Path, max_age: Duration) -> std::io::Result<Self> {
+        let secret = std::fs::read_to_string(path)?;
+        Ok(Self::new(secret.trim_end().as_bytes(), max_age))
+    }
+
+    /// Whether the node registrations are authenticated
+    pub(crate) fn enabled(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Authenticate the node `registration`
+    /// Returns whether the identity of the node was verified, which is never
+    /// the case when authentication is disabled
+    pub(crate) fn authenticate(&self, registration: &Register) -> Result<bool, SvcError> {
+        self.authenticate_at(registration, SystemTime::now())
+    }
+
+    /// Authenticate the node `registration` at the time `now`
+    fn authenticate_at(&self, registration: &Register, now: SystemTime) -> Result<bool, SvcError> {
+        let key = match &self.key {
+            Some(key) => key,
+            None => return Ok(false),
+        };
+        let reject = |reason: &str| SvcError::NodeRegistration {
+            node: registration.id.clone(),
+            reason: reason.to_string(),
+        };
+        let token = registration
+            .token
+            .as_ref()
+            .ok_or_else(|| reject("missing registration token"))?;
+        let timestamp = registration
+            .timestamp
+            .ok_or_else(|| reject("missing registration timestamp"))?;
+        let tag = hex::decode(token).map_err(|_| reject("malformed registration token"))?;
+        let node_key = Self::node_key(key, &registration.id);
+        hmac::verify(&node_key, &Self::message(registration, timestamp), &tag)
+            .map_err(|_| reject("invalid registration token"))?;
+
+        let now = now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        if now.max(timestamp) - now.min(timestamp) > self.max_age.as_millis() as u64 {
+            return Err(reject("stale registration token"));
+        }
+        let mut last_timestamps = self.last_timestamps.lock();
+        match last_timestamps.get(&registration.id) {
+            Some(last) if *last >= timestamp => Err(reject("replayed registration token")),
+            _ => {
+                last_timestamps.insert(registration.id.clone(), timestamp);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Key of the node `node_id`, derived from the registration secret `key`
+    fn node_key(key: &hmac::Key, node_id: &NodeId) -> hmac::Key {
+        let node_key = hmac::sign(key, node_id.as_str().as_bytes());
+        hmac::Key::new(hmac::HMAC_SHA256, node_key.as_ref())
+    }
+
+    /// Message signed by the registration token
+    fn message(registration: &Register, timestamp: u64) -> Vec<u8> {
+        format!(
+            "{}|{}|{}",
+            registration.id, registration.grpc_endpoint, timestamp
+        )
+        .into_bytes()
+    }
+
+    /// Registration token of the `registration` at the `timestamp`, signed
+    /// with the key of the node derived from the `secret`
+    #[cfg(test)]
+    pub(crate) fn token(secret: &[u8], registration: &Register, timestamp: u64) -> String {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+        let node_key = Self::node_key(&key, &registration.id);
+        hex::encode(hmac::sign(&node_key, &Self::message(registration, timestamp)).as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"node-registration-secret";
+
+    fn registration(id: &str, endpoint: &str) -> Register {
+        Register {
+            id: id.into(),
+            grpc_endpoint: endpoint.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn signed(mut registration: Register, timestamp: u64) -> Register {
+        registration.token = Some(NodeAuthenticator::token(SECRET, &registration, timestamp));
+        registration.timestamp = Some(timestamp);
+        registration
+    }
+
+    fn at(timestamp: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(timestamp)
+    }
+
+    #[test]
+    fn registration_token() {
+        let authenticator = NodeAuthenticator::new(SECRET, Duration::from_secs(60));
+        let node = registration("node-1", "10.1.0.5:10124");
+
+        assert!(authenticator
+            .authenticate_at(&signed(node.clone(), 1_000_000), at(1_000_000))
+            .unwrap());
+        // the token is only valid for the node and endpoint it was signed for
+        let mut other = signed(node.clone(), 1_001_000);
+        other.id = "node-2".into();
+        assert!(authenticator
+            .authenticate_at(&other, at(1_001_000))
+            .is_err());
+        let mut other = signed(node.clone(), 1_001_000);
+        other.grpc_endpoint = "10.1.0.66:10124".to_string();
+        assert!(authenticator
+            .authenticate_at(&other, at(1_001_000))
+            .is_err());
+        // stale tokens are rejected
+        assert!(authenticator
+            .authenticate_at(&signed(node.clone(), 1_002_000), at(1_063_000))
+            .is_err());
+        // replayed tokens are rejected
+        assert!(authenticator
+            .authenticate_at(&signed(node.clone(), 1_000_000), at(1_003_000))
+            .is_err());
+        assert!(authenticator
+            .authenticate_at(&signed(node.clone(), 1_004_000), at(1_004_000))
+            .unwrap());
+
+        assert!(authenticator.authenticate_at(&node, at(1_005_000)).is_err());
+        let mut malformed = signed(node.clone(), 1_005_000);
+        malformed.token = Some("not-hex".into());
+        assert!(authenticator
+            .authenticate_at(&malformed, at(1_005_000))
+            .is_err());
+
+        // without a secret nodes are not authenticated
+        let authenticator = NodeAuthenticator::default();
+        assert!(!authenticator.enabled());
+        assert!(!authenticator.authenticate(&node).unwrap());
+    }
+}
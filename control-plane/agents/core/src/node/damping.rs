@@ -0,0 +1,203 @@
+use common_lib::types::v0::message_bus::{NodeState, NodeStateTransition};
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime},
+};
+
+/// Maximum number of state transitions kept in a node's history
+const MAX_HISTORY: usize = 64;
+
+/// Damping of the node state transitions, so that a single missed keep alive
+/// or a node which keeps going up and down does not trigger a storm of
+/// healing and failover work
+#[derive(Debug, Clone)]
+pub(crate) struct NodeStateDamping {
+    /// after missing the deadline a node is deemed Suspect for this period
+    /// before being deemed Offline
+    grace: Duration,
+    /// window over which the offline transitions are counted
+    flap_window: Duration,
+    /// number of offline transitions within the window which makes a node
+    /// be deemed Flapping
+    flap_threshold: usize,
+    /// period for which a flapping node must be stable before being deemed
+    /// Online again
+    flap_hold: Duration,
+}
+
+impl NodeStateDamping {
+    /// New `NodeStateDamping` with the given grace period and flap detection
+    pub(crate) fn new(
+        grace: Duration,
+        flap_window: Duration,
+        flap_threshold: usize,
+        flap_hold: Duration,
+    ) -> Self {
+        Self {
+            grace,
+            flap_window,
+            flap_threshold,
+            flap_hold,
+        }
+    }
+
+    /// the set grace period
+    pub(crate) fn grace(&self) -> Duration {
+        self.grace
+    }
+
+    /// State of a node in the `current` state when its keep alive was last
+    /// seen `elapsed` ago, or None if the state should not change
+    pub(crate) fn expired_state(
+        &self,
+        current: &NodeState,
+        elapsed: Duration,
+        deadline: Duration,
+    ) -> Option<NodeState> {
+        match current {
+            NodeState::Online | NodeState::Suspect | NodeState::Flapping => {}
+            NodeState::Offline | NodeState::Unknown => return None,
+        }
+        if elapsed >= deadline + self.grace {
+            Some(NodeState::Offline)
+        } else if elapsed >= deadline && current == &NodeState::Online {
+            Some(NodeState::Suspect)
+        } else {
+            None
+        }
+    }
+
+    /// State of a node in the `current` state when it registers at the time
+    /// `now`, given the `history` of its state transitions
+    pub(crate) fn registered_state(
+        &self,
+        current: &NodeState,
+        history: &NodeStateHistory,
+        now: SystemTime,
+    ) -> NodeState {
+        match current {
+            // it never went down so it's not flapping
+            NodeState::Online | NodeState::Suspect => NodeState::Online,
+            NodeState::Flapping => match history.last_elapsed(now) {
+                Some(stable) if stable < self.flap_hold => NodeState::Flapping,
+                _ => NodeState::Online,
+            },
+            NodeState::Offline | NodeState::Unknown => {
+                if history.offline_within(self.flap_window, now) >= self.flap_threshold {
+                    NodeState::Flapping
+                } else {
+                    NodeState::Online
+                }
+            }
+        }
+    }
+}
+
+/// Bounded history of the state transitions of a node, oldest first
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NodeStateHistory {
+    transitions: VecDeque<NodeStateTransition>,
+}
+
+impl NodeStateHistory {
+    /// Record a new transition, dropping the oldest one if full
+    pub(crate) fn push(&mut self, transition: NodeStateTransition) {
+        if self.transitions.len() >= MAX_HISTORY {
+            self.transitions.pop_front();
+        }
+        self.transitions.push_back(transition);
+    }
+
+    /// All the recorded transitions, oldest first
+    pub(crate) fn transitions(&self) -> Vec<NodeStateTransition> {
+        self.transitions.iter().cloned().collect()
+    }
+
+    /// Time elapsed between the last transition and `now`
+    fn last_elapsed(&self, now: SystemTime) -> Option<Duration> {
+        self.transitions
+            .back()
+            .map(|t| now.duration_since(t.timestamp).unwrap_or_default())
+    }
+
+    /// Number of transitions to Offline within the `window` which ends `now`
+    fn offline_within(&self, window: Duration, now: SystemTime) -> usize {
+        self.transitions
+            .iter()
+            .filter(|t| t.to == NodeState::Offline)
+            .filter(|t| now.duration_since(t.timestamp).unwrap_or_default() <= window)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    /// Transition from `from` to `to` at `at` seconds on the test clock
+    fn transition(from: NodeState, to: NodeState, at: u64) -> NodeStateTransition {
+        NodeStateTransition {
+            from,
+            to,
+            timestamp: clock(at),
+        }
+    }
+
+    /// Time at `at` seconds on the test clock
+    fn clock(at: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + at * SECOND
+    }
+
+    #[test]
+    fn node_state_damping() {
+        let damping = NodeStateDamping::new(2 * SECOND, 60 * SECOND, 2, 60 * SECOND);
+
+        let state = damping.expired_state(&NodeState::Online, SECOND, 2 * SECOND);
+        assert_eq!(state, None);
+        let state = damping.expired_state(&NodeState::Online, 3 * SECOND, 2 * SECOND);
+        assert_eq!(state, Some(NodeState::Suspect));
+        let state = damping.expired_state(&NodeState::Suspect, 3 * SECOND, 2 * SECOND);
+        assert_eq!(state, None);
+        let state = damping.expired_state(&NodeState::Suspect, 4 * SECOND, 2 * SECOND);
+        assert_eq!(state, Some(NodeState::Offline));
+        let state = damping.expired_state(&NodeState::Offline, 5 * SECOND, 2 * SECOND);
+        assert_eq!(state, None);
+        // a suspect node which registers again within the grace period is
+        // back online
+        let history = NodeStateHistory::default();
+        let state = damping.registered_state(&NodeState::Suspect, &history, clock(3));
+        assert_eq!(state, NodeState::Online);
+
+        // without a grace period the node goes offline straight away
+        let undamped = NodeStateDamping::new(Duration::default(), 60 * SECOND, 2, 60 * SECOND);
+        let state = undamped.expired_state(&NodeState::Online, 2 * SECOND, 2 * SECOND);
+        assert_eq!(state, Some(NodeState::Offline));
+    }
+
+    #[test]
+    fn node_state_flapping() {
+        let damping = NodeStateDamping::new(2 * SECOND, 60 * SECOND, 2, 30 * SECOND);
+
+        let mut history = NodeStateHistory::default();
+        history.push(transition(NodeState::Suspect, NodeState::Offline, 100));
+        let state = damping.registered_state(&NodeState::Offline, &history, clock(110));
+        assert_eq!(state, NodeState::Online);
+
+        history.push(transition(NodeState::Online, NodeState::Suspect, 120));
+        history.push(transition(NodeState::Suspect, NodeState::Offline, 130));
+        let state = damping.registered_state(&NodeState::Offline, &history, clock(140));
+        assert_eq!(state, NodeState::Flapping);
+        // the first offline transition is out of the window by then
+        let state = damping.registered_state(&NodeState::Offline, &history, clock(170));
+        assert_eq!(state, NodeState::Online);
+
+        history.push(transition(NodeState::Offline, NodeState::Flapping, 140));
+        // not stable for long enough
+        let state = damping.registered_state(&NodeState::Flapping, &history, clock(169));
+        assert_eq!(state, NodeState::Flapping);
+        let state = damping.registered_state(&NodeState::Flapping, &history, clock(170));
+        assert_eq!(state, NodeState::Online);
+    }
+}
@@ -1,3 +1,7 @@
+/// node registration authentication
+pub(crate) mod authenticator;
+/// node state damping and transition history
+pub(crate) mod damping;
 pub(super) mod service;
 /// node watchdog to keep track of a node's liveness
 pub(crate) mod watchdog;
@@ -5,15 +9,16 @@ pub(crate) mod watchdog;
 use super::{
     core::registry, handler, handler_publish, impl_publish_handler, impl_request_handler, CliArgs,
 };
+use authenticator::NodeAuthenticator;
 use common::{errors::SvcError, Service};
 use common_lib::mbus_api::{v0::*, *};
+use damping::NodeStateDamping;
 
 use async_trait::async_trait;
 use common_lib::types::v0::message_bus::{
-    ChannelVs, Deregister, GetBlockDevices, GetNodes, GetSpecs, GetStates, Register,
+    ChannelVs, Deregister, GetBlockDevices, GetNodeHistory, GetNodes, GetSpecs, GetStates, Register,
 };
 use std::{convert::TryInto, marker::PhantomData};
-use structopt::StructOpt;
 
 pub(crate) fn configure(builder: Service) -> Service {
     let node_service = create_node_service(&builder);
@@ -26,22 +31,44 @@ pub(crate) fn configure(builder: Service) -> Service {
         .with_subscription(handler!(GetStates))
         .with_channel(ChannelVs::Node)
         .with_subscription(handler!(GetNodes))
+        .with_subscription(handler!(GetNodeHistory))
         .with_subscription(handler!(GetBlockDevices))
         .with_default_liveness()
 }
 
 fn create_node_service(builder: &Service) -> service::Service {
     let registry = builder.get_shared_state::<registry::Registry>().clone();
-    let deadline = CliArgs::from_args().deadline.into();
-    let request = CliArgs::from_args().request.into();
-    let connect = CliArgs::from_args().connect.into();
-    service::Service::new(registry, deadline, request, connect)
+    let args = builder.get_shared_state::<CliArgs>();
+    let deadline = args.deadline.into();
+    let damping = NodeStateDamping::new(
+        args.node_grace_period.into(),
+        args.flap_window.into(),
+        args.flap_threshold,
+        args.flap_hold.into(),
+    );
+    let request = args.request.into();
+    let connect = args.connect.into();
+    let authenticator = match &args.node_secret {
+        Some(path) => NodeAuthenticator::from_file(path, args.node_token_max_age.into())
+            .expect("Failed to read the node registration secret file"),
+        None => NodeAuthenticator::default(),
+    };
+    if !authenticator.enabled() {
+        tracing::warn!("Node registrations are not authenticated");
+    }
+    service::Service::new(registry, deadline, damping, request, connect, authenticator)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use common_lib::types::v0::message_bus::{Node, NodeState};
+    use actix_web::test;
+    use common_lib::{
+        store::memory::Memory,
+        types::v0::message_bus::{Node, NodeState},
+    };
+    use std::time::Duration;
+    use structopt::StructOpt;
     use testlib::ClusterBuilder;
 
     #[actix_rt::test]
@@ -50,6 +77,7 @@ mod tests {
             .with_rest(false)
             .with_agents(vec!["core"])
             .with_node_deadline("2s")
+            .with_node_grace_period("2s")
             .build()
             .await
             .unwrap();
@@ -66,6 +94,20 @@ mod tests {
                 id: maya_name.clone(),
                 grpc_endpoint: grpc.clone(),
                 state: NodeState::Online,
+                ..Default::default()
+            }
+        );
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        let nodes = GetNodes {}.request().await.unwrap();
+        tracing::info!("Nodes: {:?}", nodes);
+        assert_eq!(nodes.0.len(), 1);
+        assert_eq!(
+            nodes.0.first().unwrap(),
+            &Node {
+                id: maya_name.clone(),
+                grpc_endpoint: grpc.clone(),
+                state: NodeState::Suspect,
+                ..Default::default()
             }
         );
         tokio::time::sleep(std::time::Duration::from_secs(2)).await;
@@ -78,7 +120,83 @@ mod tests {
                 id: maya_name.clone(),
                 grpc_endpoint: grpc.clone(),
                 state: NodeState::Offline,
+                ..Default::default()
             }
         );
+
+        let history = GetNodeHistory { node: maya_name }.request().await.unwrap();
+        tracing::info!("History: {:?}", history);
+        let history = history
+            .into_inner()
+            .into_iter()
+            .map(|t| (t.from, t.to))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            history,
+            vec![
+                (NodeState::Unknown, NodeState::Online),
+                (NodeState::Online, NodeState::Suspect),
+                (NodeState::Suspect, NodeState::Offline),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn node_local_bus() {
+        // the core agent and the rest service are both run by the test itself,
+        // over a local message bus and with an in-memory store
+        let args = CliArgs::from_iter(&["core"]);
+        let registry = registry::Registry::with_store(
+            args.cache_period.into(),
+            Memory::new().into(),
+            args.store_timeout.into(),
+            args.reconcile_period.into(),
+            args.reconcile_idle_period.into(),
+        )
+        .await;
+        let timeouts = TimeoutOptions::new()
+            .with_timeout(Duration::from_millis(250))
+            .with_timeout_backoff(Duration::from_millis(250));
+        let bus = LocalMessageBus::new(timeouts);
+        let builder = Service::builder(String::new(), ChannelVs::Core)
+            .with_bus(bus.clone())
+            .connect_message_bus()
+            .await;
+        let mut service = crate::configure(builder, args, registry);
+        tokio::spawn(async move { service.run().await });
+
+        let rest = test::init_service(rest_client::service::app(
+            Box::new(bus.clone()),
+            Default::default(),
+        ))
+        .await;
+
+        let register = Register {
+            id: "node-1".into(),
+            grpc_endpoint: "127.0.0.1:10124".into(),
+            ..Default::default()
+        };
+        // the agent may not be subscribed yet and the registration and the
+        // request are handled on different channels, so keep registering the
+        // node until it's listed
+        let nodes = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                Register::Publish(&register, register.channel(), Box::new(bus.clone()))
+                    .await
+                    .unwrap();
+                let request = test::TestRequest::get().uri("/v0/nodes").to_request();
+                let nodes: serde_json::Value = test::read_response_json(&rest, request).await;
+                match nodes.as_array() {
+                    Some(nodes) if !nodes.is_empty() => break nodes.clone(),
+                    _ => tokio::time::sleep(Duration::from_millis(50)).await,
+                }
+            }
+        })
+        .await
+        .expect("the node should be listed through the rest service");
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0]["id"], "node-1");
+        assert_eq!(nodes[0]["grpcEndpoint"], "127.0.0.1:10124");
+        assert_eq!(nodes[0]["state"], "Online");
     }
 }
@@ -1,13 +1,25 @@
 use super::*;
-use crate::core::{registry::Registry, wrapper::NodeWrapper};
+use crate::{
+    core::{registry::Registry, wrapper::NodeWrapper},
+    node::{authenticator::NodeAuthenticator, damping::NodeStateDamping},
+};
 use common::{
     errors::{GrpcRequestError, NodeNotFound, SvcError},
     v0::msg_translation::RpcToMessageBus,
 };
-use common_lib::types::v0::message_bus::{GetSpecs, Node, NodeId, NodeState, Specs, States};
+use common_lib::types::v0::{
+    message_bus::{
+        Filter, GetNodeHistory, GetSpecs, Node, NodeHistory, NodeId, NodeState, Selector, Specs,
+        States, VolumeId,
+    },
+    openapi::models,
+    store::{
+        nexus::NexusSpec, pool::PoolSpec, replica::ReplicaSpec, volume::VolumeSpec, SpecState,
+    },
+};
 use rpc::mayastor::ListBlockDevicesRequest;
 use snafu::{OptionExt, ResultExt};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
 
 /// Node's Service
@@ -16,8 +28,12 @@ pub(crate) struct Service {
     registry: Registry,
     /// deadline for receiving keepalive/Register messages
     deadline: std::time::Duration,
+    /// damping of the node state transitions
+    damping: NodeStateDamping,
     /// node communication timeouts
     comms_timeouts: NodeCommsTimeout,
+    /// verifies the identity of the registering nodes
+    authenticator: NodeAuthenticator,
 }
 
 /// Node communication Timeouts for establishing the connection to a node and
@@ -50,13 +66,17 @@ impl Service {
     pub(super) fn new(
         registry: Registry,
         deadline: std::time::Duration,
+        damping: NodeStateDamping,
         request: std::time::Duration,
         connect: std::time::Duration,
+        authenticator: NodeAuthenticator,
     ) -> Self {
         Self {
             registry,
             deadline,
+            damping,
             comms_timeouts: NodeCommsTimeout::new(connect, request),
+            authenticator,
         }
     }
 
@@ -72,29 +92,49 @@ impl Service {
                     id,
                     service.deadline
                 );
-                node.update();
             }
+            node.update();
         }
     }
 
     /// Register a new node through the register information
     pub(super) async fn register(&self, registration: &Register) {
+        if let Err(error) = self.register_node(registration).await {
+            tracing::error!("{}", error.full_string());
+        }
+    }
+
+    /// Register a new node or refresh an existing one, provided that the
+    /// registration is authenticated (when authentication is enabled)
+    async fn register_node(&self, registration: &Register) -> Result<(), SvcError> {
+        let authenticated = self.authenticator.authenticate(registration)?;
         let node = Node {
             id: registration.id.clone(),
             grpc_endpoint: registration.grpc_endpoint.clone(),
-            state: NodeState::Online,
+            state: NodeState::Unknown,
+            ..Default::default()
         };
         let mut nodes = self.registry.nodes.write().await;
         match nodes.get_mut(&node.id) {
             None => {
-                let mut node = NodeWrapper::new(&node, self.deadline, self.comms_timeouts.clone());
+                let mut node = NodeWrapper::new(
+                    &node,
+                    self.deadline,
+                    self.damping.clone(),
+                    self.comms_timeouts.clone(),
+                );
+                node.on_register(registration, authenticated).await;
                 node.watchdog_mut().arm(self.clone());
                 nodes.insert(node.id.clone(), Arc::new(Mutex::new(node)));
             }
             Some(node) => {
-                node.lock().await.on_register().await;
+                node.lock()
+                    .await
+                    .on_register(registration, authenticated)
+                    .await;
             }
         }
+        Ok(())
     }
 
     /// Deregister a node through the deregister information
@@ -122,6 +162,22 @@ impl Service {
         Ok(Nodes(nodes_vec))
     }
 
+    /// Get the state transitions of a node
+    pub(crate) async fn get_node_history(
+        &self,
+        request: &GetNodeHistory,
+    ) -> Result<NodeHistory, SvcError> {
+        let node = self
+            .registry
+            .get_node_wrapper(&request.node)
+            .await
+            .context(NodeNotFound {
+                node_id: request.node.clone(),
+            })?;
+        let history = node.lock().await.history();
+        Ok(NodeHistory(history))
+    }
+
     /// Get block devices from a node
     pub(crate) async fn get_block_devices(
         &self,
@@ -158,15 +214,25 @@ impl Service {
         Ok(BlockDevices(bdevs))
     }
 
-    /// Get specs from the registry
-    pub(crate) async fn get_specs(&self, _request: &GetSpecs) -> Result<Specs, SvcError> {
-        let specs = self.registry.specs.write();
-        Ok(Specs {
-            volumes: specs.get_volumes(),
-            nexuses: specs.get_nexuses(),
-            replicas: specs.get_replicas(),
-            pools: specs.get_pools(),
-        })
+    /// Get specs from the registry according to the filter
+    pub(crate) async fn get_specs(&self, request: &GetSpecs) -> Result<Specs, SvcError> {
+        let specs = {
+            let specs = self.registry.specs.write();
+            Specs {
+                volumes: specs.get_volumes(),
+                nexuses: specs.get_nexuses(),
+                replicas: specs.get_replicas(),
+                pools: specs.get_pools(),
+                continuation_token: None,
+            }
+        };
+        match &request.filter {
+            Filter::None => Ok(specs),
+            Filter::Selector(selector) => Ok(select_specs(selector, specs)),
+            filter => Err(SvcError::InvalidFilter {
+                filter: filter.clone(),
+            }),
+        }
     }
 
     /// Get states from the registry
@@ -180,6 +246,70 @@ impl Service {
     }
 }
 
+/// Select the specs of all kinds as a single list ordered by kind and id, where
+/// the continuation token is the `kind/id` of the last spec of the page.
+/// The nexuses and replicas inherit the labels of their volume.
+fn select_specs(selector: &Selector, specs: Specs) -> Specs {
+    enum AnySpec {
+        Volume(VolumeSpec),
+        Nexus(NexusSpec),
+        Pool(PoolSpec),
+        Replica(ReplicaSpec),
+    }
+    fn state<T: Clone>(state: &SpecState<T>) -> String {
+        models::SpecState::from(state.clone()).to_string()
+    }
+
+    let volume_labels = specs
+        .volumes
+        .iter()
+        .map(|spec| (spec.uuid.clone(), spec.labels.clone()))
+        .collect::<HashMap<_, _>>();
+    let inherited_labels = |volume: Option<&VolumeId>| {
+        volume
+            .and_then(|volume| volume_labels.get(volume))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    };
+
+    let mut selected = vec![];
+    for spec in specs.volumes {
+        if selector.matches(&state(&spec.state), &spec.labels) {
+            selected.push((format!("volume/{}", spec.uuid), AnySpec::Volume(spec)));
+        }
+    }
+    for spec in specs.nexuses {
+        if selector.matches(&state(&spec.state), inherited_labels(spec.owner.as_ref())) {
+            selected.push((format!("nexus/{}", spec.uuid), AnySpec::Nexus(spec)));
+        }
+    }
+    for spec in specs.pools {
+        if selector.matches(&state(&spec.state), &spec.labels) {
+            selected.push((format!("pool/{}", spec.id), AnySpec::Pool(spec)));
+        }
+    }
+    for spec in specs.replicas {
+        if selector.matches(&state(&spec.state), inherited_labels(spec.owners.volume())) {
+            selected.push((format!("replica/{}", spec.uuid), AnySpec::Replica(spec)));
+        }
+    }
+
+    let page = selector.paginate(selected, |(key, _)| key.clone());
+    let mut specs = Specs {
+        continuation_token: selector.next_token(&page, |(key, _)| key.clone()),
+        ..Default::default()
+    };
+    for (_, spec) in page {
+        match spec {
+            AnySpec::Volume(spec) => specs.volumes.push(spec),
+            AnySpec::Nexus(spec) => specs.nexuses.push(spec),
+            AnySpec::Pool(spec) => specs.pools.push(spec),
+            AnySpec::Replica(spec) => specs.replicas.push(spec),
+        }
+    }
+    specs
+}
+
 impl Registry {
     /// Get all node wrappers
     pub(crate) async fn get_nodes_wrapper(&self) -> Vec<Arc<Mutex<NodeWrapper>>> {
@@ -199,3 +329,70 @@ impl Registry {
             .map(|(_, node)| node.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_lib::types::v0::message_bus::ReplicaOwners;
+
+    #[test]
+    fn select_specs_inherited_labels() {
+        let volume = VolumeSpec {
+            uuid: "v1".into(),
+            labels: vec!["zone=eu-1".into()],
+            ..Default::default()
+        };
+        let specs = Specs {
+            volumes: vec![
+                volume.clone(),
+                VolumeSpec {
+                    uuid: "v2".into(),
+                    ..Default::default()
+                },
+            ],
+            nexuses: vec![
+                NexusSpec {
+                    uuid: "n1".into(),
+                    owner: Some(volume.uuid.clone()),
+                    ..Default::default()
+                },
+                NexusSpec {
+                    uuid: "n2".into(),
+                    ..Default::default()
+                },
+            ],
+            replicas: vec![
+                ReplicaSpec {
+                    uuid: "r1".into(),
+                    owners: ReplicaOwners::new(&volume.uuid),
+                    ..Default::default()
+                },
+                ReplicaSpec {
+                    uuid: "r2".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let selector = Selector {
+            labels: vec!["zone=eu-1".into()],
+            max_entries: Some(2),
+            ..Default::default()
+        };
+
+        let page = select_specs(&selector, specs.clone());
+        assert_eq!(page.nexuses, vec![specs.nexuses[0].clone()]);
+        assert_eq!(page.replicas, vec![specs.replicas[0].clone()]);
+        assert!(page.volumes.is_empty());
+        assert_eq!(page.continuation_token.as_deref(), Some("replica/r1"));
+
+        let selector = Selector {
+            continuation_token: page.continuation_token,
+            ..selector
+        };
+        let page = select_specs(&selector, specs);
+        assert_eq!(page.volumes, vec![volume]);
+        assert!(page.nexuses.is_empty() && page.replicas.is_empty());
+        assert_eq!(page.continuation_token, None);
+    }
+}
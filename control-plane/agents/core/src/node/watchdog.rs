@@ -2,11 +2,13 @@ use crate::node::service::Service;
 use common_lib::types::v0::message_bus::NodeId;
 
 /// Watchdog which must be pet within the deadline, otherwise
-/// it triggers the `on_timeout` callback from the node `Service`
+/// it triggers the `on_timeout` callback from the node `Service`, and then
+/// again at every `grace` period until it is pet
 #[derive(Debug, Clone)]
 pub(crate) struct Watchdog {
     node_id: NodeId,
     deadline: std::time::Duration,
+    grace: std::time::Duration,
     timestamp: std::time::Instant,
     pet_chan: Option<tokio::sync::mpsc::Sender<()>>,
     service: Option<Service>,
@@ -14,9 +16,14 @@ pub(crate) struct Watchdog {
 
 impl Watchdog {
     /// new empty watchdog with a deadline timeout for node `node_id`
-    pub(crate) fn new(node_id: &NodeId, deadline: std::time::Duration) -> Self {
+    pub(crate) fn new(
+        node_id: &NodeId,
+        deadline: std::time::Duration,
+        grace: std::time::Duration,
+    ) -> Self {
         Self {
             deadline,
+            grace,
             node_id: node_id.clone(),
             timestamp: std::time::Instant::now(),
             pet_chan: None,
@@ -42,17 +49,26 @@ impl Watchdog {
         self.pet_chan = Some(s);
         self.service = Some(service.clone());
         let deadline = self.deadline;
+        let grace = if self.grace > std::time::Duration::default() {
+            self.grace
+        } else {
+            self.deadline
+        };
         let id = self.node_id.clone();
         tokio::spawn(async move {
+            let mut timeout = deadline;
             loop {
-                let result = tokio::time::timeout(deadline, r.recv()).await;
+                let result = tokio::time::timeout(timeout, r.recv()).await;
                 match result {
-                    Err(_) => Service::on_timeout(&service, &id).await,
+                    Err(_) => {
+                        Service::on_timeout(&service, &id).await;
+                        timeout = grace;
+                    }
                     Ok(None) => {
                         tracing::warn!("Stopping Watchdog for node '{}'", id);
                         break;
                     }
-                    _ => (),
+                    _ => timeout = deadline,
                 }
             }
         });
@@ -1,6 +1,8 @@
 use crate::core::{registry::Registry, wrapper::*};
 use common::errors::{NodeNotFound, PoolNotFound, ReplicaNotFound, SvcError};
-use common_lib::types::v0::message_bus::{NodeId, Pool, PoolId, Replica, ReplicaId};
+use common_lib::types::v0::message_bus::{
+    NodeCapability, NodeId, Pool, PoolId, Replica, ReplicaId,
+};
 use snafu::OptionExt;
 
 /// Pool helpers
@@ -54,12 +56,35 @@ impl Registry {
         Ok(pools)
     }
 
-    /// Get pool wrappers per node
-    pub(crate) async fn get_node_pools_wrapper(&self) -> Result<Vec<Vec<PoolWrapper>>, SvcError> {
+    /// Get pool wrappers per node, only from the online nodes which support
+    /// all the required `capabilities`
+    pub(crate) async fn get_node_pools_wrapper(
+        &self,
+        capabilities: &[NodeCapability],
+    ) -> Result<Vec<Vec<PoolWrapper>>, SvcError> {
         let nodes = self.get_nodes_wrapper().await;
         let mut pools = vec![];
         for node in nodes {
-            pools.push(node.pools().await);
+            let node = node.lock().await;
+            if !node.is_online() {
+                // suspect and flapping nodes are not reliable enough for new
+                // replicas
+                tracing::debug!(
+                    "Skipping the pools of node '{}' which is {}",
+                    node.node().id,
+                    node.node().state.to_string()
+                );
+                continue;
+            }
+            if !node.node().supports(capabilities) {
+                tracing::debug!(
+                    "Skipping the pools of node '{}' which does not support {:?}",
+                    node.node().id,
+                    capabilities
+                );
+                continue;
+            }
+            pools.push(node.pools());
         }
         Ok(pools)
     }
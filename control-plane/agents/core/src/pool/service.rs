@@ -7,6 +7,7 @@ use common_lib::{
         Pool, Replica, ShareReplica, UnshareReplica,
     },
 };
+use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub(super) struct Service {
@@ -36,6 +37,27 @@ impl Service {
                 let pool = self.registry.get_pool_wrapper(&pool_id).await?;
                 vec![pool.into()]
             }
+            Filter::Selector(selector) => {
+                // the labels are part of the pool specs
+                let labels = self
+                    .registry
+                    .specs
+                    .read()
+                    .get_pools()
+                    .into_iter()
+                    .map(|spec| (spec.id, spec.labels))
+                    .collect::<HashMap<_, _>>();
+                let pools = self
+                    .registry
+                    .get_node_opt_pools(None)
+                    .await?
+                    .into_iter()
+                    .filter(|pool| {
+                        let labels = labels.get(&pool.id).map(Vec::as_slice).unwrap_or_default();
+                        selector.matches(&pool.state.to_string(), labels)
+                    });
+                selector.paginate(pools, |pool| pool.id.to_string())
+            }
             _ => return Err(SvcError::InvalidFilter { filter }),
         };
         Ok(Pools(pools))
@@ -83,6 +105,23 @@ impl Service {
             Filter::Replica(replica_id) => {
                 vec![self.registry.get_replica(&replica_id).await?]
             }
+            Filter::Selector(selector) => {
+                // the replicas inherit the labels of their volume
+                let labels = self.registry.specs.get_replica_labels();
+                let replicas = self
+                    .registry
+                    .get_node_opt_replicas(None)
+                    .await?
+                    .into_iter()
+                    .filter(|replica| {
+                        let labels = labels
+                            .get(&replica.uuid)
+                            .map(Vec::as_slice)
+                            .unwrap_or_default();
+                        selector.matches(&replica.state.to_string(), labels)
+                    });
+                selector.paginate(replicas, |replica| replica.uuid.to_string())
+            }
             _ => return Err(SvcError::InvalidFilter { filter }),
         };
         Ok(Replicas(replicas))
@@ -5,7 +5,8 @@ use common_lib::{
     mbus_api::TimeoutOptions,
     types::v0::{
         message_bus::{
-            GetNodes, GetSpecs, Protocol, Replica, ReplicaId, ReplicaShareProtocol, ReplicaState,
+            Filter, GetNodes, GetSpecs, Protocol, Replica, ReplicaId, ReplicaShareProtocol,
+            ReplicaState, Selector,
         },
         store::replica::ReplicaSpec,
     },
@@ -109,6 +110,74 @@ async fn pool() {
     assert!(GetPools::default().request().await.unwrap().0.is_empty());
 }
 
+#[actix_rt::test]
+async fn replica_selector() {
+    let cluster = ClusterBuilder::builder()
+        .with_rest(false)
+        .with_pools(1)
+        .with_agents(vec!["core"])
+        .build()
+        .await
+        .unwrap();
+
+    let mut uuids = vec![];
+    for _ in 0 .. 3 {
+        let replica = CreateReplica {
+            node: cluster.node(0),
+            uuid: ReplicaId::new(),
+            pool: cluster.pool(0, 0),
+            size: 5 * 1024 * 1024,
+            thin: true,
+            share: Protocol::None,
+            ..Default::default()
+        }
+        .request()
+        .await
+        .unwrap();
+        uuids.push(replica.uuid);
+    }
+    uuids.sort_by_key(|uuid| uuid.to_string());
+
+    let get_page = |selector: Selector| async move {
+        GetReplicas {
+            filter: Filter::Selector(selector),
+        }
+        .request()
+        .await
+        .unwrap()
+        .into_inner()
+        .into_iter()
+        .map(|replica| replica.uuid)
+        .collect::<Vec<_>>()
+    };
+    let first_page = get_page(Selector {
+        max_entries: Some(2),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(first_page, uuids[0 .. 2].to_vec());
+    let last_page = get_page(Selector {
+        max_entries: Some(2),
+        continuation_token: first_page.last().map(ToString::to_string),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(last_page, uuids[2 ..].to_vec());
+
+    let online = get_page(Selector {
+        state: Some("Online".to_string()),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(online, uuids);
+    let faulted = get_page(Selector {
+        state: Some("faulted".to_string()),
+        ..Default::default()
+    })
+    .await;
+    assert!(faulted.is_empty());
+}
+
 /// The tests below revolve around transactions and are dependent on the core agent's command line
 /// arguments for timeouts.
 /// This is required because as of now, we don't have a good mocking strategy
@@ -122,7 +191,7 @@ fn bus_timeout_opts() -> TimeoutOptions {
 
 /// Get the replica spec
 async fn replica_spec(replica: &Replica) -> Option<ReplicaSpec> {
-    GetSpecs {}
+    GetSpecs::default()
         .request()
         .await
         .unwrap()
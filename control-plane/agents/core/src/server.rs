@@ -7,11 +7,16 @@ pub mod watcher;
 
 use crate::core::registry;
 use common::*;
-use common_lib::types::v0::message_bus::ChannelVs;
+use common_lib::{mbus_api::BusOptions, types::v0::message_bus::ChannelVs};
+use opentelemetry::{
+    global,
+    sdk::{propagation::TraceContextPropagator, trace::Tracer},
+};
 use structopt::StructOpt;
 use tracing::info;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-#[derive(Debug, StructOpt)]
+#[derive(Debug, Clone, StructOpt)]
 pub(crate) struct CliArgs {
     /// The Nats Server URL to connect to
     /// (supports the nats schema)
@@ -19,6 +24,10 @@ pub(crate) struct CliArgs {
     #[structopt(long, short, default_value = "nats://127.0.0.1:4222")]
     pub(crate) nats: String,
 
+    /// The Nats Server connection options (TLS and credentials)
+    #[structopt(flatten)]
+    pub(crate) nats_options: BusOptions,
+
     /// The period at which the registry updates its cache of all
     /// resources from all nodes
     #[structopt(long, short, default_value = "20s")]
@@ -37,6 +46,27 @@ pub(crate) struct CliArgs {
     #[structopt(long, short, default_value = "10s")]
     pub(crate) deadline: humantime::Duration,
 
+    /// Grace period which follows a missed registration deadline, during
+    /// which the node is deemed Suspect before being deemed Offline
+    /// Default: none, the node is deemed Offline as soon as it misses the
+    /// deadline
+    #[structopt(long, default_value = "0s")]
+    pub(crate) node_grace_period: humantime::Duration,
+
+    /// Window over which the times a node went offline are counted
+    #[structopt(long, default_value = "5m")]
+    pub(crate) flap_window: humantime::Duration,
+
+    /// Number of times a node may go offline within the flap window before
+    /// being deemed Flapping
+    #[structopt(long, default_value = "3")]
+    pub(crate) flap_threshold: usize,
+
+    /// Period for which a flapping node must keep registering before being
+    /// deemed Online again
+    #[structopt(long, default_value = "1m")]
+    pub(crate) flap_hold: humantime::Duration,
+
     /// The Persistent Store URLs to connect to
     /// (supports the http/https schema)
     /// Default: http://localhost:2379
@@ -54,19 +84,68 @@ pub(crate) struct CliArgs {
     /// The timeout for every node request operation (gRPC)
     #[structopt(long, short, default_value = "6s")]
     pub(crate) request: humantime::Duration,
+
+    /// Path to the secret used to verify the registration tokens of the nodes
+    /// If not provided, node registrations are not authenticated
+    #[structopt(long)]
+    pub(crate) node_secret: Option<std::path::PathBuf>,
+
+    /// The maximum age of the node registration tokens, beyond which they are
+    /// rejected as stale
+    #[structopt(long, default_value = "1m")]
+    pub(crate) node_token_max_age: humantime::Duration,
+
+    /// Path to a PEM CA certificate used to verify the HTTPS watch callbacks,
+    /// in addition to the system root certificates
+    #[structopt(long)]
+    pub(crate) watch_ca: Option<std::path::PathBuf>,
+
+    /// The maximum age of the watch notifications pending delivery, after
+    /// which they are dead-lettered
+    #[structopt(long, default_value = "1h")]
+    pub(crate) watch_max_age: humantime::Duration,
+
+    /// The bind address for the prometheus metrics endpoint (HTTP)
+    /// The resource gauges are refreshed every cache period
+    /// If not provided, the metrics are not served
+    #[structopt(long)]
+    pub(crate) metrics: Option<std::net::SocketAddr>,
+
+    /// Trace the handled requests to the Jaeger endpoint agent
+    #[structopt(long, short)]
+    pub(crate) jaeger: Option<String>,
 }
 
-fn init_tracing() {
-    if let Ok(filter) = tracing_subscriber::EnvFilter::try_from_default_env() {
-        tracing_subscriber::fmt().with_env_filter(filter).init();
+fn init_tracing() -> Option<Tracer> {
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    let subscriber = tracing_subscriber::Registry::default()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer());
+
+    if let Some(agent) = CliArgs::from_args().jaeger {
+        // the trace context carried by the bus messages is propagated
+        // following the W3C trace context specification
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = opentelemetry_jaeger::new_pipeline()
+            .with_agent_endpoint(agent.clone())
+            .with_service_name("core-agent")
+            .install_simple()
+            .expect("Jaeger pipeline install error");
+        let telemetry = tracing_opentelemetry::layer().with_tracer(tracer.clone());
+        subscriber.with(telemetry).init();
+        info!("Started jaeger trace pipeline at {}", agent);
+        Some(tracer)
     } else {
-        tracing_subscriber::fmt().with_env_filter("info").init();
+        subscriber.init();
+        None
     }
 }
 
 #[tokio::main]
 async fn main() {
-    init_tracing();
+    // need to keep the jaeger pipeline tracer alive, if enabled
+    let _tracer = init_tracing();
 
     let cli_args = CliArgs::from_args();
     info!("Using options: {:?}", &cli_args);
@@ -75,26 +154,45 @@ async fn main() {
 }
 
 async fn server(cli_args: CliArgs) {
-    let registry = registry::Registry::new(
-        CliArgs::from_args().cache_period.into(),
-        CliArgs::from_args().store,
-        CliArgs::from_args().store_timeout.into(),
-        CliArgs::from_args().reconcile_period.into(),
-        CliArgs::from_args().reconcile_idle_period.into(),
+    if let Some(address) = cli_args.metrics {
+        if let Err(error) = common::metrics::serve(address) {
+            tracing::error!("Failed to serve the metrics on {}: {}", address, error);
+        }
+    }
+    let registry = new_registry(&cli_args).await;
+    let builder = Service::builder(cli_args.nats.clone(), ChannelVs::Core)
+        .with_bus_options(cli_args.nats_options.clone())
+        .connect_message_bus()
+        .await;
+    configure(builder, cli_args, registry).run().await;
+}
+
+/// The registry of the core agent, configured with the `cli_args`
+async fn new_registry(cli_args: &CliArgs) -> registry::Registry {
+    registry::Registry::new(
+        cli_args.cache_period.into(),
+        cli_args.store.clone(),
+        cli_args.store_timeout.into(),
+        cli_args.reconcile_period.into(),
+        cli_args.reconcile_idle_period.into(),
     )
-    .await;
-    Service::builder(cli_args.nats, ChannelVs::Core)
+    .await
+}
+
+/// Configure the core agent services on the `builder`, with the `cli_args` and
+/// the `registry`
+/// The builder may use any message bus, eg: a `LocalMessageBus` to run the core
+/// agent and its clients within the same process
+fn configure(builder: Service, cli_args: CliArgs, registry: registry::Registry) -> Service {
+    builder
         .with_default_liveness()
-        .connect_message_bus()
-        .await
+        .with_shared_state(cli_args)
         .with_shared_state(registry)
         .configure(node::configure)
         .configure(pool::configure)
         .configure(nexus::configure)
         .configure(volume::configure)
         .configure(watcher::configure)
-        .run()
-        .await;
 }
 
 /// Constructs a service handler for `RequestType` which gets redirected to a
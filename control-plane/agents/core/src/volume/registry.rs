@@ -46,7 +46,7 @@ impl Registry {
     }
 
     /// Get all volume status
-    pub(super) async fn get_volumes_status(&self) -> Vec<Volume> {
+    pub(crate) async fn get_volumes_status(&self) -> Vec<Volume> {
         let mut volumes = vec![];
         let volume_specs = self.specs.get_volumes();
         for volume in volume_specs {
@@ -7,6 +7,7 @@ use common_lib::{
         UnpublishVolume, UnshareVolume, Volume,
     },
 };
+use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub(super) struct Service {
@@ -38,6 +39,24 @@ impl Service {
                 .filter(|volume_iter| &volume_iter.uuid == volume)
                 .cloned()
                 .collect(),
+            Filter::Selector(selector) => {
+                // the labels are part of the volume specs
+                let labels = self
+                    .registry
+                    .specs
+                    .get_volumes()
+                    .into_iter()
+                    .map(|spec| (spec.uuid, spec.labels))
+                    .collect::<HashMap<_, _>>();
+                let volumes = volumes.into_iter().filter(|volume| {
+                    let labels = labels
+                        .get(&volume.uuid)
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+                    selector.matches(&volume.state.to_string(), labels)
+                });
+                selector.paginate(volumes, |volume| volume.uuid.to_string())
+            }
             filter => {
                 return Err(SvcError::InvalidFilter {
                     filter: filter.clone(),
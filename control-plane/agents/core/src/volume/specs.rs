@@ -1,4 +1,4 @@
-use std::{convert::From, ops::Deref, sync::Arc};
+use std::{collections::HashMap, convert::From, ops::Deref, sync::Arc};
 
 use parking_lot::Mutex;
 
@@ -18,9 +18,9 @@ use common_lib::{
     types::v0::{
         message_bus::{
             ChildUri, CreateNexus, CreateReplica, CreateVolume, DestroyNexus, DestroyReplica,
-            DestroyVolume, Nexus, NexusId, NodeId, PoolState, Protocol, PublishVolume, ReplicaId,
-            ReplicaOwners, ShareNexus, ShareVolume, UnpublishVolume, UnshareNexus, UnshareVolume,
-            Volume, VolumeId, VolumeState,
+            DestroyVolume, Nexus, NexusId, NodeCapability, NodeId, PoolState, Protocol,
+            PublishVolume, ReplicaId, ReplicaOwners, ShareNexus, ShareVolume, UnpublishVolume,
+            UnshareNexus, UnshareVolume, Volume, VolumeId, VolumeState,
         },
         store::{
             nexus::NexusSpec,
@@ -32,11 +32,23 @@ use common_lib::{
 };
 use snafu::OptionExt;
 
+/// The replicas of a volume are shared with its nexus over nvmf
+const REPLICA_SHARE: Protocol = Protocol::Nvmf;
+
+/// Capabilities which a node requires to host a replica of the volume `spec`
+fn replica_capabilities(spec: &VolumeSpec) -> Vec<NodeCapability> {
+    NodeCapability::replica(&REPLICA_SHARE, spec.thin)
+}
+
 async fn get_node_pools(
     registry: &Registry,
+    spec: &VolumeSpec,
     request: &CreateVolume,
 ) -> Result<Vec<Vec<PoolWrapper>>, SvcError> {
-    let node_pools = registry.get_node_pools_wrapper().await?;
+    // only the nodes which can create the replicas of the volume can host them
+    // (see `get_node_replicas`)
+    let capabilities = replica_capabilities(spec);
+    let node_pools = registry.get_node_pools_wrapper(&capabilities).await?;
 
     let size = request.size;
     let replicas = request.replicas;
@@ -98,9 +110,10 @@ async fn get_node_pools(
 
 async fn get_node_replicas(
     registry: &Registry,
+    spec: &VolumeSpec,
     request: &CreateVolume,
 ) -> Result<Vec<Vec<CreateReplica>>, SvcError> {
-    let pools = get_node_pools(registry, request).await?;
+    let pools = get_node_pools(registry, spec, request).await?;
     let node_replicas = pools
         .iter()
         .map(|p| {
@@ -110,8 +123,8 @@ async fn get_node_replicas(
                     uuid: ReplicaId::new(),
                     pool: p.id.clone(),
                     size: request.size,
-                    thin: false,
-                    share: Protocol::Nvmf,
+                    thin: spec.thin,
+                    share: REPLICA_SHARE,
                     managed: true,
                     owners: ReplicaOwners::new(&request.uuid),
                 })
@@ -136,8 +149,48 @@ impl ResourceSpecs {
     pub(crate) fn get_volumes(&self) -> Vec<VolumeSpec> {
         self.volumes.values().map(|v| v.lock().clone()).collect()
     }
+    /// Gets the labels of all volumes, which their nexuses and replicas inherit
+    fn get_volume_labels(&self) -> HashMap<VolumeId, Vec<String>> {
+        self.volumes
+            .values()
+            .map(|v| {
+                let v = v.lock();
+                (v.uuid.clone(), v.labels.clone())
+            })
+            .collect()
+    }
 }
 impl ResourceSpecsLocked {
+    /// Gets the labels of all nexuses, as inherited from their owning volume
+    pub(crate) fn get_nexus_labels(&self) -> HashMap<NexusId, Vec<String>> {
+        let specs = self.read();
+        let volume_labels = specs.get_volume_labels();
+        specs
+            .nexuses
+            .values()
+            .filter_map(|n| {
+                let n = n.lock();
+                let labels = volume_labels.get(n.owner.as_ref()?)?;
+                Some((n.uuid.clone(), labels.clone()))
+            })
+            .collect()
+    }
+
+    /// Gets the labels of all replicas, as inherited from their owning volume
+    pub(crate) fn get_replica_labels(&self) -> HashMap<ReplicaId, Vec<String>> {
+        let specs = self.read();
+        let volume_labels = specs.get_volume_labels();
+        specs
+            .replicas
+            .values()
+            .filter_map(|r| {
+                let r = r.lock();
+                let labels = volume_labels.get(r.owners.volume()?)?;
+                Some((r.uuid.clone(), labels.clone()))
+            })
+            .collect()
+    }
+
     /// Get the protected VolumeSpec for the given volume `id`, if any exists
     pub(crate) fn get_volume(&self, id: &VolumeId) -> Option<Arc<Mutex<VolumeSpec>>> {
         let specs = self.read();
@@ -199,7 +252,8 @@ impl ResourceSpecsLocked {
 
         // todo: pick nodes and pools using the Node&Pool Topology
         // todo: virtually increase the pool usage to avoid a race for space with concurrent calls
-        let create_replicas = get_node_replicas(registry, request).await?;
+        let spec = volume.lock().clone();
+        let create_replicas = get_node_replicas(registry, &spec, request).await?;
 
         let mut replicas = vec![];
         for node_replica in &create_replicas {
@@ -564,16 +618,20 @@ async fn get_volume_target_node(
 
     match request.target_node.as_ref() {
         None => {
-            // auto select a node
+            // auto select a node, preferably an online one over a suspect or
+            // flapping one
             let nodes = registry.get_nodes_wrapper().await;
+            let mut fallback = None;
             for locked_node in nodes {
                 let node = locked_node.lock().await;
                 // todo: use other metrics in order to make the "best" choice
                 if node.is_online() {
                     return Ok(node.id.clone());
+                } else if node.is_reachable() && fallback.is_none() {
+                    fallback = Some(node.id.clone());
                 }
             }
-            Err(SvcError::NoNodes {})
+            fallback.ok_or(SvcError::NoNodes {})
         }
         Some(node) => {
             // make sure the requested node is available
@@ -585,7 +643,7 @@ async fn get_volume_target_node(
                     node_id: node.clone(),
                 })?;
             let node = node.lock().await;
-            if node.is_online() {
+            if node.is_reachable() {
                 Ok(node.id.clone())
             } else {
                 Err(SvcError::NodeNotOnline {
@@ -686,3 +744,18 @@ impl SpecOperations for VolumeSpec {
         self.state = state;
     }
 }
+
+#[test]
+fn replica_capabilities_test() {
+    let spec = VolumeSpec::from(&CreateVolume::default());
+    assert_eq!(replica_capabilities(&spec), vec![NodeCapability::Nvmf]);
+    let spec = VolumeSpec::from(&CreateVolume {
+        thin: true,
+        ..Default::default()
+    });
+    assert!(spec.thin);
+    assert_eq!(
+        replica_capabilities(&spec),
+        vec![NodeCapability::Nvmf, NodeCapability::ThinProvisioning]
+    );
+}
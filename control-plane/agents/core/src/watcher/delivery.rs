@@ -0,0 +1,527 @@
+//! Reliable delivery of the watch notifications.
+//! The notifications of each watch are queued in the persistent store, one entry
+//! per notification, before being delivered, in order, to its callback, and are
+//! only removed from the store once delivered. This way they survive a restart of the core agent
+//! and are delivered at least once. Failed deliveries are retried with an
+//! exponential backoff, and the notifications which exceed the maximum age or
+//! overflow the bounded queue are dead-lettered: the latest dead letters are
+//! kept in the queue for inspection and counted in the watch delivery status.
+use super::webhook::Webhook;
+use crate::core::registry::Registry;
+use common_lib::{
+    mbus_api::bus,
+    types::{
+        v0::{
+            message_bus::{
+                WatchCallback, WatchDelivery, WatchDeliveryStatus, WatchResourceId, WatchType,
+            },
+            openapi::models,
+            store::definitions::{
+                ObjectKey, StorableObject, StorableObjectType, Store, StoreError,
+            },
+        },
+        Channel,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::min,
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::{broadcast::Receiver, Mutex, Notify};
+
+/// Maximum number of notifications pending delivery for each watch
+const QUEUE_SIZE: usize = 256;
+/// Number of the latest dead letters which are kept
+const DEAD_LETTERS: usize = 16;
+/// Delay before the first retry of a failed delivery, doubled on every retry
+const BACKOFF_MIN: Duration = Duration::from_millis(100);
+/// Maximum delay between the retries of a failed delivery
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// Uniquely identify the delivery queue of a watch in the store
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DeliveryQueueId {
+    /// the watched resource
+    resource: WatchResourceId,
+    /// callback of the watch
+    callback: WatchCallback,
+    /// type of the watch
+    #[serde(rename = "type")]
+    type_: WatchType,
+}
+
+impl DeliveryQueueId {
+    pub(crate) fn new(
+        resource: &WatchResourceId,
+        callback: &WatchCallback,
+        type_: &WatchType,
+    ) -> Self {
+        Self {
+            resource: resource.clone(),
+            callback: callback.clone(),
+            type_: type_.clone(),
+        }
+    }
+}
+
+impl ObjectKey for DeliveryQueueId {
+    fn key_type(&self) -> StorableObjectType {
+        StorableObjectType::WatchDelivery
+    }
+    fn key_uuid(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            self.resource.to_string(),
+            models::WatchType::from(&self.type_).to_string(),
+            self.callback.to_string()
+        )
+    }
+}
+
+/// Uniquely identify a notification pending delivery in the store
+#[derive(Debug, Clone)]
+struct DeliveryKey {
+    /// the delivery queue of the watch
+    queue: DeliveryQueueId,
+    /// position of the notification in the queue
+    sequence: u64,
+}
+
+impl ObjectKey for DeliveryKey {
+    fn key_type(&self) -> StorableObjectType {
+        StorableObjectType::WatchDelivery
+    }
+    fn key_uuid(&self) -> String {
+        format!("{}/{:020}", self.queue.key_uuid(), self.sequence)
+    }
+}
+
+/// Notification pending delivery
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Delivery {
+    /// the delivery queue of the watch
+    queue: DeliveryQueueId,
+    /// position of the notification in the queue
+    sequence: u64,
+    notification: models::WatchNotification,
+    /// unix time (in seconds) at which the notification was queued
+    queued: u64,
+    /// number of failed delivery attempts, which is not persisted
+    #[serde(skip)]
+    attempts: u32,
+}
+
+impl StorableObject for Delivery {
+    type Key = DeliveryKey;
+
+    fn key(&self) -> Self::Key {
+        DeliveryKey {
+            queue: self.queue.clone(),
+            sequence: self.sequence,
+        }
+    }
+}
+
+impl Delivery {
+    fn new(queue: &DeliveryQueue, notification: models::WatchNotification) -> Self {
+        Self {
+            queue: queue.id.clone(),
+            sequence: queue.next_sequence(),
+            notification,
+            queued: now(),
+            attempts: 0,
+        }
+    }
+    /// Whether the notification was queued longer than `max_age` ago
+    fn expired(&self, max_age: Duration) -> bool {
+        now().saturating_sub(self.queued) > max_age.as_secs()
+    }
+}
+
+/// Delivery queue of a watch. Only its dead letters are persisted under the
+/// queue key, as each pending notification is stored on its own
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DeliveryQueue {
+    id: DeliveryQueueId,
+    /// notifications pending delivery, oldest first
+    #[serde(skip)]
+    pending: VecDeque<Delivery>,
+    /// number of notifications which were given up
+    dead_letters: u64,
+    /// the latest notifications which were given up, oldest first
+    last_dead_letters: VecDeque<Delivery>,
+    /// error of the last failed delivery attempt, if it's retrying
+    #[serde(skip)]
+    last_error: Option<String>,
+}
+
+impl StorableObject for DeliveryQueue {
+    type Key = DeliveryQueueId;
+
+    fn key(&self) -> Self::Key {
+        self.id.clone()
+    }
+}
+
+impl DeliveryQueue {
+    /// Sequence of the next queued notification
+    fn next_sequence(&self) -> u64 {
+        self.pending
+            .back()
+            .map(|delivery| delivery.sequence + 1)
+            .unwrap_or_default()
+    }
+
+    /// The oldest pending notification, provided it's still the one with the
+    /// `sequence`, as it may have been dead-lettered or delivered meanwhile
+    fn pending_front(&mut self, sequence: u64) -> Option<&mut Delivery> {
+        self.pending
+            .front_mut()
+            .filter(|delivery| delivery.sequence == sequence)
+    }
+
+    /// Give up the oldest pending notification, returning its key
+    fn dead_letter(&mut self) -> Option<DeliveryKey> {
+        let delivery = self.pending.pop_front()?;
+        tracing::warn!(
+            "Dead-lettering the notification of '{}' for watch '{}' after {} attempts",
+            delivery.notification.resource,
+            self.id.key_uuid(),
+            delivery.attempts
+        );
+        self.dead_letters += 1;
+        if self.last_dead_letters.len() == DEAD_LETTERS {
+            self.last_dead_letters.pop_front();
+        }
+        let key = delivery.key();
+        self.last_dead_letters.push_back(delivery);
+        Some(key)
+    }
+}
+
+/// Outbox of a watch, which queues its notifications and delivers them
+#[derive(Debug, Clone)]
+pub(crate) struct Outbox {
+    queue: Arc<Mutex<DeliveryQueue>>,
+    /// signalled when a notification is queued
+    queued: Arc<Notify>,
+    registry: Registry,
+}
+
+impl Outbox {
+    /// Outbox of the watch `id`, with the notifications which were still
+    /// pending delivery when the core agent last stopped, if any
+    pub(crate) async fn load(id: DeliveryQueueId, registry: &Registry) -> Self {
+        let mut queue = match registry.load_obj::<DeliveryQueue>(&id).await {
+            Ok(queue) => queue,
+            Err(StoreError::MissingEntry { .. }) => DeliveryQueue {
+                id,
+                ..Default::default()
+            },
+            Err(error) => {
+                tracing::error!(
+                    "Failed to load the dead letters of watch '{}': {}",
+                    id.key_uuid(),
+                    error
+                );
+                DeliveryQueue {
+                    id,
+                    ..Default::default()
+                }
+            }
+        };
+        match Self::stored(&queue.id, registry).await {
+            Ok(mut pending) => {
+                pending.sort_by_key(|delivery| delivery.sequence);
+                queue.pending = pending.into();
+            }
+            Err(error) => tracing::error!(
+                "Failed to load the pending notifications of watch '{}': {}",
+                queue.id.key_uuid(),
+                error
+            ),
+        }
+        Self {
+            queue: Arc::new(Mutex::new(queue)),
+            queued: Arc::new(Notify::new()),
+            registry: registry.clone(),
+        }
+    }
+
+    /// The notifications of the queue `id` which are stored
+    async fn stored(
+        id: &DeliveryQueueId,
+        registry: &Registry,
+    ) -> Result<Vec<Delivery>, StoreError> {
+        let mut store = registry.store.lock().await;
+        let entries = store.get_values_prefix(&format!("{}/", id.key())).await?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|(_, value)| serde_json::from_value::<Delivery>(value).ok())
+            // the prefix may also match the queues of other callbacks, eg: of
+            // a longer uri
+            .filter(|delivery| &delivery.queue == id)
+            .collect())
+    }
+
+    /// Queue the `notification` for delivery, dead-lettering the oldest
+    /// pending notification if the queue is full
+    pub(crate) async fn push(&self, notification: models::WatchNotification) {
+        {
+            let mut queue = self.queue.lock().await;
+            if queue.pending.len() == QUEUE_SIZE {
+                self.dead_letter(&mut queue).await;
+            }
+            let delivery = Delivery::new(&queue, notification);
+            self.persist(&delivery).await;
+            queue.pending.push_back(delivery);
+        }
+        self.queued.notify_one();
+    }
+
+    /// Delivery status of the notifications
+    pub(crate) async fn status(&self) -> WatchDelivery {
+        let queue = self.queue.lock().await;
+        let status = if queue.dead_letters > 0 {
+            WatchDeliveryStatus::DeadLettered
+        } else if queue.last_error.is_some() {
+            WatchDeliveryStatus::Retrying
+        } else {
+            WatchDeliveryStatus::Ok
+        };
+        WatchDelivery {
+            status,
+            pending: queue.pending.len() as u64,
+            dead_letters: queue.dead_letters,
+            last_error: queue.last_error.clone(),
+        }
+    }
+
+    /// Remove the queue and its pending notifications from the store, once the
+    /// watch is deleted and its delivery has stopped
+    pub(crate) async fn remove(&self) {
+        let mut queue = self.queue.lock().await;
+        let mut keys = queue
+            .pending
+            .drain(..)
+            .map(|delivery| delivery.key().key())
+            .collect::<Vec<_>>();
+        match Self::stored(&queue.id, &self.registry).await {
+            Ok(stored) => keys.extend(stored.iter().map(|delivery| delivery.key().key())),
+            Err(error) => tracing::error!(
+                "Failed to list the pending notifications of watch '{}': {}",
+                queue.id.key_uuid(),
+                error
+            ),
+        }
+        keys.sort();
+        keys.dedup();
+        keys.push(queue.id.key());
+
+        let mut store = self.registry.store.lock().await;
+        for key in keys {
+            match store.delete_kv(&key).await {
+                Ok(_) | Err(StoreError::MissingEntry { .. }) => {}
+                Err(error) => tracing::error!(
+                    "Failed to remove '{}' of the delivery queue of watch '{}': {}",
+                    key,
+                    queue.id.key_uuid(),
+                    error
+                ),
+            }
+        }
+    }
+
+    /// Deliver the queued notifications to the `callback`, in order, until the
+    /// watch is cancelled. Each notification is retried until it's delivered
+    /// or becomes older than the `max_age`.
+    pub(crate) async fn deliver(
+        self,
+        mut cancel: Receiver<()>,
+        callback: WatchCallback,
+        webhook: Webhook,
+        max_age: Duration,
+    ) {
+        loop {
+            let next = self.queue.lock().await.pending.front().cloned();
+            let delivery = match next {
+                Some(delivery) => delivery,
+                None => {
+                    tokio::select! {
+                        _cancel = cancel.recv() => return,
+                        _queued = self.queued.notified() => continue,
+                    }
+                }
+            };
+
+            if delivery.expired(max_age) {
+                let mut queue = self.queue.lock().await;
+                if queue.pending_front(delivery.sequence).is_some() {
+                    self.dead_letter(&mut queue).await;
+                }
+                continue;
+            }
+
+            match send(&callback, &webhook, &delivery.notification).await {
+                Ok(()) => {
+                    let mut queue = self.queue.lock().await;
+                    if queue.last_error.take().is_some() {
+                        tracing::info!("Completed notification for {}", callback.to_string());
+                    }
+                    if queue.pending_front(delivery.sequence).is_some() {
+                        queue.pending.pop_front();
+                        self.delete(&delivery.key()).await;
+                    }
+                }
+                Err(error) => {
+                    let attempts = {
+                        let mut queue = self.queue.lock().await;
+                        if queue.last_error.is_none() {
+                            tracing::error!(
+                                "Failed to notify {}, {}. Quietly retrying...",
+                                callback.to_string(),
+                                error
+                            );
+                        }
+                        queue.last_error = Some(error);
+                        match queue.pending_front(delivery.sequence) {
+                            Some(front) => {
+                                front.attempts += 1;
+                                front.attempts
+                            }
+                            None => 1,
+                        }
+                    };
+                    tokio::select! {
+                        _cancel = cancel.recv() => return,
+                        _backoff = tokio::time::sleep(backoff(attempts)) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Give up the oldest pending notification of the `queue`, moving it from
+    /// its own entry in the store to the dead letters of the queue
+    async fn dead_letter(&self, queue: &mut DeliveryQueue) {
+        if let Some(key) = queue.dead_letter() {
+            let mut store = self.registry.store.lock().await;
+            if let Err(error) = store.put_obj(&*queue).await {
+                tracing::error!(
+                    "Failed to persist the dead letters of watch '{}': {}",
+                    queue.id.key_uuid(),
+                    error
+                );
+            }
+            drop(store);
+            self.delete(&key).await;
+        }
+    }
+
+    /// Write the `delivery` to the store, so the pending notification survives
+    /// a restart of the core agent
+    async fn persist(&self, delivery: &Delivery) {
+        let mut store = self.registry.store.lock().await;
+        if let Err(error) = store.put_obj(delivery).await {
+            tracing::error!(
+                "Failed to persist the notification '{}' of watch '{}': {}",
+                delivery.sequence,
+                delivery.queue.key_uuid(),
+                error
+            );
+        }
+    }
+
+    /// Remove the notification with the `key` from the store, once it's
+    /// delivered or dead-lettered
+    async fn delete(&self, key: &DeliveryKey) {
+        let mut store = self.registry.store.lock().await;
+        match store.delete_kv(&key.key()).await {
+            Ok(_) | Err(StoreError::MissingEntry { .. }) => {}
+            Err(error) => tracing::error!(
+                "Failed to remove the notification '{}' of watch '{}': {}",
+                key.sequence,
+                key.queue.key_uuid(),
+                error
+            ),
+        }
+    }
+}
+
+/// Send the `notification` to the `callback`
+async fn send(
+    callback: &WatchCallback,
+    webhook: &Webhook,
+    notification: &models::WatchNotification,
+) -> Result<(), String> {
+    let body = serde_json::to_vec(notification).map_err(|error| error.to_string())?;
+    match callback {
+        WatchCallback::Uri(uri) => match webhook.send(uri, &body).await {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) => Err(format!("response status {}", response.status())),
+            Err(error) => Err(error.to_string()),
+        },
+        WatchCallback::Subject(subject) => bus()
+            .publish(Channel::Subject(subject.clone()), &body)
+            .await
+            .map_err(|error| error.to_string()),
+    }
+}
+
+/// Exponential backoff delay after the given number of failed `attempts`
+fn backoff(attempts: u32) -> Duration {
+    min(
+        BACKOFF_MIN * 2u32.pow(min(attempts.saturating_sub(1), 16)),
+        BACKOFF_MAX,
+    )
+}
+
+/// Current unix time, in seconds
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delivery_backoff() {
+        assert_eq!(backoff(1), BACKOFF_MIN);
+        assert_eq!(backoff(2), BACKOFF_MIN * 2);
+        assert_eq!(backoff(4), BACKOFF_MIN * 8);
+        assert_eq!(backoff(10), BACKOFF_MAX);
+        assert_eq!(backoff(u32::MAX), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn dead_letters() {
+        let mut queue = DeliveryQueue::default();
+        for _ in 0 .. DEAD_LETTERS + 2 {
+            let delivery = Delivery::new(&queue, models::WatchNotification::default());
+            queue.pending.push_back(delivery);
+        }
+        assert_eq!(queue.next_sequence(), DEAD_LETTERS as u64 + 2);
+        assert!(queue.pending_front(1).is_none());
+        assert!(queue.pending_front(0).is_some());
+        let mut sequence = 0;
+        while let Some(key) = queue.dead_letter() {
+            assert_eq!(key.sequence, sequence);
+            sequence += 1;
+        }
+        assert_eq!(queue.dead_letters, DEAD_LETTERS as u64 + 2);
+        assert_eq!(queue.last_dead_letters.len(), DEAD_LETTERS);
+        assert_eq!(queue.next_sequence(), 0);
+        assert!(!Delivery::new(&queue, models::WatchNotification::default())
+            .expired(Duration::from_secs(1)));
+    }
+}
@@ -1,34 +1,50 @@
+mod delivery;
 pub mod service;
 mod watch;
+mod webhook;
 
 use std::{convert::TryInto, marker::PhantomData};
 
-use super::{core::registry::Registry, handler, impl_request_handler};
+use super::{core::registry::Registry, handler, impl_request_handler, CliArgs};
 use async_trait::async_trait;
 use common::errors::SvcError;
 use common_lib::{
     mbus_api::*,
-    types::v0::message_bus::{ChannelVs, CreateWatch, DeleteWatch, GetWatchers},
+    types::v0::message_bus::{ChannelVs, CreateWatch, DeleteWatch, GetResourceEvents, GetWatchers},
 };
 
 pub(crate) fn configure(builder: common::Service) -> common::Service {
     let registry = builder.get_shared_state::<Registry>().clone();
+    let args = builder.get_shared_state::<CliArgs>();
+    let client = webhook::client(args.watch_ca.as_deref());
+    let service = service::Service::new(registry, client, args.watch_max_age.into());
+    service.restore();
     builder
         .with_channel(ChannelVs::Watcher)
         .with_default_liveness()
-        .with_shared_state(service::Service::new(registry))
+        .with_shared_state(service)
         .with_subscription(handler!(CreateWatch))
         .with_subscription(handler!(GetWatchers))
         .with_subscription(handler!(DeleteWatch))
+        .with_subscription(handler!(GetResourceEvents))
 }
 
 #[cfg(test)]
 mod tests {
     use common_lib::{
+        mbus_api::{bus, BusSubscription, Message, ReceivedRawMessage},
         store::etcd::Etcd,
-        types::v0::{
-            message_bus::{CreateVolume, Volume, VolumeId, WatchResourceId},
-            store::definitions::{ObjectKey, Store},
+        types::{
+            v0::{
+                message_bus::{
+                    ChannelVs, CreateVolume, CreateWatch, DeleteWatch, Filter, GetResourceEvents,
+                    ResourceEvent, ResourceEventType, Selector, Volume, VolumeId, WatchCallback,
+                    WatchDelivery, WatchResourceId, WatchType,
+                },
+                openapi::models,
+                store::definitions::{ObjectKey, StorableObjectType, Store},
+            },
+            Channel,
         },
     };
     use once_cell::sync::OnceCell;
@@ -36,9 +52,15 @@ mod tests {
     use testlib::*;
     use tokio::net::TcpStream;
 
-    static CALLBACK: OnceCell<tokio::sync::mpsc::Sender<()>> = OnceCell::new();
+    static CALLBACK: OnceCell<tokio::sync::mpsc::Sender<models::WatchNotification>> =
+        OnceCell::new();
 
-    async fn setup_watcher(client: &impl RestClient) -> (Volume, tokio::sync::mpsc::Receiver<()>) {
+    async fn setup_watcher(
+        client: &impl RestClient,
+    ) -> (
+        Volume,
+        tokio::sync::mpsc::Receiver<models::WatchNotification>,
+    ) {
         let volume = client
             .create_volume(CreateVolume {
                 uuid: VolumeId::new(),
@@ -52,8 +74,16 @@ mod tests {
         let (s, r) = tokio::sync::mpsc::channel(1);
         CALLBACK.set(s).unwrap();
 
-        async fn notify() -> actix_web::HttpResponse {
-            CALLBACK.get().cloned().unwrap().send(()).await.unwrap();
+        async fn notify(
+            notification: actix_web::web::Json<models::WatchNotification>,
+        ) -> actix_web::HttpResponse {
+            CALLBACK
+                .get()
+                .cloned()
+                .unwrap()
+                .send(notification.into_inner())
+                .await
+                .unwrap();
             actix_web::HttpResponse::Ok().finish()
         }
 
@@ -93,9 +123,11 @@ mod tests {
         let cluster = cluster.unwrap();
         let client = cluster.rest_v0();
 
-        let (volume, mut callback_ch) = setup_watcher(&client).await;
+        let (_volume, mut callback_ch) = setup_watcher(&client).await;
 
-        let watch_volume = WatchResourceId::Volume(volume.uuid);
+        // the spec of this volume is written to the store by the test itself
+        let watch_volume = WatchResourceId::Volume(VolumeId::new());
+        let spec_key = watch_volume.desired().unwrap().key();
         let callback = url::Url::parse("http://10.1.0.1:8082/test").unwrap();
 
         let watchers = client.get_watches(watch_volume.clone()).await.unwrap();
@@ -110,8 +142,27 @@ mod tests {
             .await
             .expect_err("volume does not exist in the store");
 
+        // the watch configs hold the secrets of the watches
+        let watch_configs = [
+            WatchResourceId::Kind(StorableObjectType::WatchConfig),
+            WatchResourceId::WatchConfig(Box::new(watch_volume.clone())),
+        ];
+        for watch_config in watch_configs.iter() {
+            CreateWatch {
+                id: watch_config.clone(),
+                callback: WatchCallback::Uri(callback.to_string()),
+                watch_type: WatchType::All,
+                secret: None,
+                filter: Filter::None,
+                delivery: None,
+            }
+            .request()
+            .await
+            .expect_err("the watch configs may not be watched");
+        }
+
         store
-            .put_kv(&watch_volume.key(), &serde_json::json!("aaa"))
+            .put_kv(&spec_key, &serde_json::json!("aaa"))
             .await
             .unwrap();
 
@@ -126,18 +177,26 @@ mod tests {
             Some(&v0::RestWatch {
                 resource: watch_volume.to_string(),
                 callback: callback.to_string(),
+                delivery: Some(WatchDelivery::default()),
             })
         );
         assert_eq!(watchers.len(), 1);
 
         store
-            .put_kv(&watch_volume.key(), &serde_json::json!("aaa"))
+            .put_kv(&spec_key, &serde_json::json!("aaa"))
             .await
             .unwrap();
 
-        tokio::time::timeout(Duration::from_millis(250), callback_ch.recv())
+        let notification = tokio::time::timeout(Duration::from_millis(250), callback_ch.recv())
             .await
+            .unwrap()
             .unwrap();
+        assert_eq!(notification.resource, watch_volume.to_string());
+        assert_eq!(notification.event_type, models::WatchEventType::Put);
+        assert_eq!(notification.watch_type, models::WatchType::Desired);
+        assert_eq!(notification.value, Some(serde_json::json!("aaa")));
+        assert_eq!(notification.version, "v0");
+        assert!(notification.revision.is_some());
 
         client
             .delete_watch(watch_volume.clone(), callback.clone())
@@ -145,7 +204,7 @@ mod tests {
             .unwrap();
 
         store
-            .put_kv(&watch_volume.key(), &serde_json::json!("bbb"))
+            .put_kv(&spec_key, &serde_json::json!("bbb"))
             .await
             .unwrap();
 
@@ -156,4 +215,216 @@ mod tests {
         let watchers = client.get_watches(watch_volume.clone()).await.unwrap();
         assert!(watchers.is_empty());
     }
+
+    #[actix_rt::test]
+    async fn resource_events() {
+        let cluster = ClusterBuilder::builder().with_pools(1).build().await;
+        let cluster = cluster.unwrap();
+        let client = cluster.rest_v0();
+
+        let mut subscription = bus().subscribe(ChannelVs::Events.into()).await.unwrap();
+        let volume = client
+            .create_volume(CreateVolume {
+                uuid: VolumeId::new(),
+                size: 10 * 1024 * 1024,
+                replicas: 1,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        // the volume spec is written to the store which emits a desired event
+        let resource = WatchResourceId::Volume(volume.uuid);
+        let event = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let message = subscription.next().await.unwrap();
+                let event = ReceivedRawMessage::from(&message)
+                    .inner::<ResourceEvent>()
+                    .unwrap();
+                if event.resource == resource && event.watch_type == WatchType::Desired {
+                    return event;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(event.event_type, ResourceEventType::Put);
+
+        // the event is retained so the streams can resume from the revision before it
+        let events = GetResourceEvents {
+            revision: event.revision - 1,
+        }
+        .request()
+        .await
+        .unwrap();
+        assert_eq!(events.0.first(), Some(&event));
+
+        GetResourceEvents { revision: u64::MAX }
+            .request()
+            .await
+            .expect_err("the revision was not issued yet");
+    }
+
+    #[actix_rt::test]
+    async fn watcher_subject() {
+        let cluster = ClusterBuilder::builder().with_pools(1).build().await;
+        let cluster = cluster.unwrap();
+        let client = cluster.rest_v0();
+
+        let watch_volume = WatchResourceId::Volume(VolumeId::new());
+        let spec_key = watch_volume.desired().unwrap().key();
+        let subject = "watches.test";
+        let callback = url::Url::parse(&format!("nats:{}", subject)).unwrap();
+
+        let mut store = Etcd::new("0.0.0.0:2379")
+            .await
+            .expect("Failed to connect to etcd.");
+        store
+            .put_kv(&spec_key, &serde_json::json!("aaa"))
+            .await
+            .unwrap();
+
+        client
+            .create_watch(
+                watch_volume.clone(),
+                url::Url::parse("nats:v0/core").unwrap(),
+            )
+            .await
+            .expect_err("the control plane channels are reserved");
+        for subject in &["_INBOX.test", "$SYS.test", "watches.", "watches.*"] {
+            client
+                .create_watch(
+                    watch_volume.clone(),
+                    url::Url::parse(&format!("nats:{}", subject)).unwrap(),
+                )
+                .await
+                .expect_err("only plain subjects within the watches namespace are allowed");
+        }
+
+        let mut subscription = bus()
+            .subscribe(Channel::Subject(subject.to_string()))
+            .await
+            .unwrap();
+        client
+            .create_watch(watch_volume.clone(), callback.clone())
+            .await
+            .unwrap();
+
+        let watchers = client.get_watches(watch_volume.clone()).await.unwrap();
+        assert_eq!(
+            watchers.first().map(|watch| watch.callback.as_str()),
+            Some(callback.as_str())
+        );
+
+        store
+            .put_kv(&spec_key, &serde_json::json!("bbb"))
+            .await
+            .unwrap();
+
+        let message = tokio::time::timeout(Duration::from_secs(1), subscription.next())
+            .await
+            .unwrap()
+            .unwrap();
+        let notification: models::WatchNotification =
+            serde_json::from_slice(&message.data).unwrap();
+        assert_eq!(notification.resource, watch_volume.to_string());
+        assert_eq!(notification.event_type, models::WatchEventType::Put);
+        assert_eq!(notification.value, Some(serde_json::json!("bbb")));
+
+        client
+            .delete_watch(watch_volume.clone(), callback)
+            .await
+            .unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn watcher_kind() {
+        let cluster = ClusterBuilder::builder().with_pools(1).build().await;
+        let cluster = cluster.unwrap();
+        let client = cluster.rest_v0();
+
+        let watch_volumes = WatchResourceId::Kind(StorableObjectType::Volume);
+        let subject = "watcher.kind";
+        let mut subscription = bus()
+            .subscribe(Channel::Subject(subject.to_string()))
+            .await
+            .unwrap();
+        CreateWatch {
+            id: watch_volumes.clone(),
+            callback: WatchCallback::Subject(subject.to_string()),
+            watch_type: WatchType::Desired,
+            secret: None,
+            filter: Filter::Selector(Selector {
+                labels: vec!["tier=ssd".to_string()],
+                ..Default::default()
+            }),
+            delivery: None,
+        }
+        .request()
+        .await
+        .unwrap();
+
+        let watchers = client.get_watches(watch_volumes.clone()).await.unwrap();
+        assert_eq!(watchers.len(), 1);
+
+        let mut store = Etcd::new("0.0.0.0:2379")
+            .await
+            .expect("Failed to connect to etcd.");
+        let selected = WatchResourceId::Volume(VolumeId::new());
+        let other = WatchResourceId::Volume(VolumeId::new());
+
+        // only the volumes with the selected labels are notified
+        store
+            .put_kv(
+                &other.desired().unwrap().key(),
+                &serde_json::json!({ "labels": ["tier=hdd"] }),
+            )
+            .await
+            .unwrap();
+        store
+            .put_kv(
+                &selected.desired().unwrap().key(),
+                &serde_json::json!({ "labels": ["tier=ssd"] }),
+            )
+            .await
+            .unwrap();
+        let notification = next_notification(&mut subscription).await.unwrap();
+        assert_eq!(notification.resource, selected.to_string());
+        assert_eq!(notification.event_type, models::WatchEventType::Put);
+        assert_eq!(notification.watch_type, models::WatchType::Desired);
+
+        // the change which deselects a volume is notified, but not the next
+        store
+            .put_kv(
+                &selected.desired().unwrap().key(),
+                &serde_json::json!({ "labels": [] }),
+            )
+            .await
+            .unwrap();
+        let notification = next_notification(&mut subscription).await.unwrap();
+        assert_eq!(notification.resource, selected.to_string());
+        store
+            .delete_kv(&selected.desired().unwrap().key())
+            .await
+            .unwrap();
+        assert!(next_notification(&mut subscription).await.is_none());
+
+        DeleteWatch {
+            id: watch_volumes,
+            callback: WatchCallback::Subject(subject.to_string()),
+            watch_type: WatchType::Desired,
+        }
+        .request()
+        .await
+        .unwrap();
+    }
+
+    async fn next_notification(
+        subscription: &mut BusSubscription,
+    ) -> Option<models::WatchNotification> {
+        let message = tokio::time::timeout(Duration::from_secs(1), subscription.next())
+            .await
+            .ok()??;
+        serde_json::from_slice(&message.data).ok()
+    }
 }
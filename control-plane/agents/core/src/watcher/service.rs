@@ -5,11 +5,11 @@ use crate::{
 pub use common::errors::SvcError;
 pub use common_lib::mbus_api::{Message, MessageId, ReceivedMessage};
 use common_lib::{
-    mbus_api::message_bus::v0::Watches,
-    types::v0::message_bus::{CreateWatch, DeleteWatch, GetWatchers},
+    mbus_api::message_bus::v0::{ResourceEvents, Watches},
+    types::v0::message_bus::{CreateWatch, DeleteWatch, GetResourceEvents, GetWatchers},
 };
 pub use std::convert::TryInto;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 
 #[derive(Clone, Debug)]
@@ -20,13 +20,23 @@ pub(super) struct Service {
 
 /// Watcher Agent's Service
 impl Service {
-    pub(super) fn new(registry: Registry) -> Self {
+    pub(super) fn new(registry: Registry, client: reqwest::Client, max_age: Duration) -> Self {
         Self {
-            watcher: Arc::new(Mutex::new(StoreWatcher::new(registry.clone()))),
+            watcher: Arc::new(Mutex::new(StoreWatcher::new(
+                registry.clone(),
+                client,
+                max_age,
+            ))),
             registry,
         }
     }
 
+    /// Restore the watches which are recorded in the store, in the background
+    pub(super) fn restore(&self) {
+        let watcher = self.watcher.clone();
+        tokio::spawn(async move { watcher.lock().await.restore().await });
+    }
+
     /// Create new resource watch
     #[tracing::instrument(level = "debug", err)]
     pub(super) async fn create_watch(&self, request: &CreateWatch) -> Result<(), SvcError> {
@@ -37,6 +47,8 @@ impl Service {
                 &WatchCfgId::from(request),
                 &request.callback,
                 &request.watch_type,
+                request.secret.clone(),
+                &request.filter,
             )
             .await?;
         Ok(())
@@ -65,4 +77,14 @@ impl Service {
             )
             .await
     }
+
+    /// Get the resource change events which followed the requested revision
+    #[tracing::instrument(level = "debug", err)]
+    pub(super) async fn get_resource_events(
+        &self,
+        request: &GetResourceEvents,
+    ) -> Result<ResourceEvents, SvcError> {
+        let events = self.registry.events.since(request.revision).await?;
+        Ok(ResourceEvents(events))
+    }
 }
@@ -1,14 +1,20 @@
+use super::{
+    delivery::{DeliveryQueueId, Outbox},
+    webhook::Webhook,
+};
 use crate::core::registry::Registry;
 use common::errors::{Store as SvcStoreError, SvcError};
 use common_lib::{
     mbus_api::{message_bus::v0::Watches, ResourceKind},
     types::v0::{
         message_bus::{
-            CreateWatch, DeleteWatch, GetWatchers, Watch, WatchCallback, WatchResourceId, WatchType,
+            CreateWatch, DeleteWatch, Filter, GetWatchers, ResourceEvent, ResourceEventType,
+            Selector, Watch, WatchCallback, WatchResourceId, WatchType, VERSION,
         },
+        openapi::models,
         store::definitions::{
-            ObjectKey, StorableObject, StorableObjectType, Store, StoreError, StoreWatchReceiver,
-            WatchEvent,
+            key_prefix, ObjectKey, StorableObject, StorableObjectType, Store, StoreError,
+            StorePrefixWatchReceiver, StoreWatchReceiver, WatchEvent,
         },
     },
 };
@@ -16,15 +22,22 @@ use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use std::{
     cmp::min,
+    collections::HashSet,
     ops::{Deref, DerefMut},
     sync::Arc,
     time::Duration,
 };
 use tokio::{
-    sync::{broadcast::error::TryRecvError, Mutex},
+    sync::{
+        broadcast::error::{RecvError, TryRecvError},
+        Mutex,
+    },
     task::JoinHandle,
 };
 
+/// Namespace of the bus subjects on which the notifications may be published
+const WATCH_SUBJECT_PREFIX: &str = "watches.";
+
 impl ObjectKey for WatchCfgId {
     fn key_type(&self) -> StorableObjectType {
         StorableObjectType::WatchConfig
@@ -60,18 +73,72 @@ struct WatchParams {
 }
 
 /// Watch parameters with handle to the watcher worker thread
-#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[derive(Serialize, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 struct WatchParamsCfg {
     /// inner configurable watch parameters
     params: WatchParams,
+    /// secret shared with the watcher, used to sign the notifications
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secret: Option<String>,
+    /// selects the resources which are notified by their labels and state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    selector: Option<Selector>,
     /// handle to the watcher
     #[serde(skip)]
     handle: Option<WatchHandle>,
+    /// queue of the notifications pending delivery
+    #[serde(skip)]
+    outbox: Option<Outbox>,
+}
+
+impl std::fmt::Debug for WatchParamsCfg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // never leak the secret into the logs
+        f.debug_struct("WatchParamsCfg")
+            .field("params", &self.params)
+            .field("secret", &self.secret.as_ref().map(|_| "<redacted>"))
+            .field("selector", &self.selector)
+            .field("handle", &self.handle)
+            .field("outbox", &self.outbox)
+            .finish()
+    }
+}
+
+/// Watch Handle to the watch threads with a cancellation channel
+type WatchHandle = Arc<(tokio::sync::broadcast::Sender<()>, Vec<JoinHandle<()>>)>;
+
+/// Selection of the resource changes which are notified to a watcher.
+/// Once a resource is selected, the change which deselects it (eg: the removal
+/// of a selected label or its deletion) is notified as well.
+#[derive(Debug, Default)]
+struct Selection {
+    selector: Option<Selector>,
+    /// the resources which are currently selected
+    selected: HashSet<String>,
 }
 
-/// Watch Handle to a watch thread with a cancellation channel
-type WatchHandle = Arc<(tokio::sync::broadcast::Sender<()>, JoinHandle<()>)>;
+impl Selection {
+    fn new(selector: Option<Selector>) -> Self {
+        Self {
+            selector,
+            selected: HashSet::new(),
+        }
+    }
+
+    /// Whether the change of the `resource` to the `value` is notified, where
+    /// no value means that the resource was deleted
+    fn select(&mut self, resource: &WatchResourceId, value: Option<&serde_json::Value>) -> bool {
+        match (&self.selector, value) {
+            (None, _) => true,
+            (Some(selector), Some(value)) if selector.matches_value(value) => {
+                self.selected.insert(resource.to_string());
+                true
+            }
+            (Some(_), _) => self.selected.remove(&resource.to_string()),
+        }
+    }
+}
 
 impl Deref for WatchParamsCfg {
     type Target = WatchParams;
@@ -114,13 +181,19 @@ pub(crate) struct StoreWatcher {
     pub(crate) registry: Registry,
     /// record of all watchers
     watches: Vec<Arc<Mutex<WatchCfg>>>,
+    /// HTTP client used to notify the watchers
+    client: reqwest::Client,
+    /// maximum age of the notifications pending delivery
+    max_age: Duration,
 }
 
 impl StoreWatcher {
-    pub fn new(registry: Registry) -> Self {
+    pub fn new(registry: Registry, client: reqwest::Client, max_age: Duration) -> Self {
         Self {
             registry,
             watches: Default::default(),
+            client,
+            max_age,
         }
     }
 }
@@ -137,90 +210,221 @@ impl WatchCfg {
     /// Add a new watch element to this watcher
     async fn add(
         &mut self,
-        watch: &WatchParams,
-        store: Arc<Mutex<impl Store + 'static>>,
+        mut watch: WatchParamsCfg,
+        client: reqwest::Client,
+        max_age: Duration,
+        registry: &Registry,
     ) -> Result<(), SvcError> {
-        if self.watchers.iter().any(|item| &item.params == watch) {
+        if self.watchers.iter().any(|item| item.params == watch.params) {
             return Err(SvcError::WatchAlreadyExists {});
         }
 
-        {
-            // make sure the target resource exists
-            let mut store = store.lock().await;
-            match store.get_kv(&self.watch_id.id.key()).await {
-                Ok(_) => Ok(()),
-                Err(StoreError::MissingEntry { .. }) => Err(SvcError::WatchResourceNotFound {
-                    kind: Self::resource_to_kind(&self.watch_id.id),
-                }),
-                Err(error) => Err(error.into()),
-            }?;
-        }
+        let id = &self.watch_id.id;
+        let (desired, actual) = Self::watched(id, &watch.type_)?;
+        let queue_id = DeliveryQueueId::new(id, &watch.callback, &watch.type_);
+        let outbox = Outbox::load(queue_id, registry).await;
+        let webhook = Webhook::new(client, watch.secret.clone());
+        let handle = self
+            .watch(&watch, desired, actual, &outbox, webhook, max_age, registry)
+            .await?;
+        watch.handle = Some(handle);
+        watch.outbox = Some(outbox);
+        self.watchers.push(watch);
 
-        let handle = self.watch(watch, store).await?;
+        // now record the watch in the store
+        // if this fails the watch will be cancelled
+        if let Err(error) = registry.store_obj(self).await {
+            self.watchers.pop();
+            return Err(error);
+        }
+        Ok(())
+    }
 
-        let watch = WatchParamsCfg {
-            params: watch.clone(),
-            handle: Some(handle),
+    /// The resources which hold the `desired` and `actual` state of the
+    /// resource `id`, as watched with the given type
+    fn watched(
+        id: &WatchResourceId,
+        type_: &WatchType,
+    ) -> Result<(Option<WatchResourceId>, Option<WatchResourceId>), SvcError> {
+        let desired = match type_ {
+            WatchType::Actual => None,
+            _ => id.desired(),
         };
-        self.watchers.push(watch);
+        let actual = match type_ {
+            WatchType::Desired => None,
+            _ => id.actual(),
+        };
+        if desired.is_none() && actual.is_none() {
+            // eg: the actual state of a spec
+            return Err(SvcError::InvalidArguments {});
+        }
+        Ok((desired, actual))
+    }
+
+    /// Make sure the resource `id` exists in any of the watched states, unless
+    /// a whole kind of resources is watched
+    async fn exists(
+        id: &WatchResourceId,
+        type_: &WatchType,
+        registry: &Registry,
+    ) -> Result<(), SvcError> {
+        if matches!(id, WatchResourceId::Kind(_)) {
+            return Ok(());
+        }
+        let (desired, actual) = Self::watched(id, type_)?;
+        let mut exists = false;
+        if let Some(desired) = &desired {
+            match registry.load_kv(&desired.key()).await {
+                Ok(_) => exists = true,
+                Err(StoreError::MissingEntry { .. }) => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+        if let Some(actual) = &actual {
+            exists = exists || registry.events.has_actual(actual).await;
+        }
+        if !exists {
+            return Err(SvcError::WatchResourceNotFound {
+                kind: Self::resource_to_kind(id),
+            });
+        }
         Ok(())
     }
 
     /// Map a watch resource to a resource kind
     fn resource_to_kind(resource: &WatchResourceId) -> ResourceKind {
         match &resource {
-            WatchResourceId::Node(_) => ResourceKind::Node,
-            WatchResourceId::Pool(_) => ResourceKind::Pool,
+            WatchResourceId::Node(_) | WatchResourceId::NodeSpec(_) => ResourceKind::Node,
+            WatchResourceId::Pool(_) | WatchResourceId::PoolSpec(_) => ResourceKind::Pool,
             WatchResourceId::Replica(_) => ResourceKind::Replica,
             WatchResourceId::ReplicaState(_) => ResourceKind::ReplicaState,
             WatchResourceId::ReplicaSpec(_) => ResourceKind::ReplicaSpec,
-            WatchResourceId::Nexus(_) => ResourceKind::Nexus,
-            WatchResourceId::Volume(_) => ResourceKind::Volume,
+            WatchResourceId::Nexus(_)
+            | WatchResourceId::NexusState(_)
+            | WatchResourceId::NexusSpec(_) => ResourceKind::Nexus,
+            WatchResourceId::Volume(_)
+            | WatchResourceId::VolumeState(_)
+            | WatchResourceId::VolumeSpec(_) => ResourceKind::Volume,
+            WatchResourceId::ChildState(_) | WatchResourceId::ChildSpec(_) => ResourceKind::Child,
+            WatchResourceId::WatchConfig(_) => ResourceKind::Watch,
+            WatchResourceId::Kind(kind) => match WatchResourceId::from_kind(*kind, "") {
+                Ok(resource) => Self::resource_to_kind(&resource),
+                Err(_) => ResourceKind::Watch,
+            },
+        }
+    }
+
+    /// Delete a watcher using its parameters, along with its pending
+    /// notifications
+    async fn del(&mut self, watch: &WatchParams, registry: &Registry) -> Result<(), SvcError> {
+        let index = match self.watchers.iter().position(|item| &item.params == watch) {
+            Some(index) => index,
+            None => return Err(SvcError::WatchNotFound {}),
+        };
+        let removed = self.watchers.remove(index);
+        registry
+            .store
+            .lock()
+            .await
+            .put_obj(self)
+            .await
+            .context(SvcStoreError {})?;
+        if let Some(handle) = removed.handle {
+            Self::cancel(handle).await;
+        }
+        // the workers are done, so none of them may queue a notification
+        if let Some(outbox) = removed.outbox {
+            outbox.remove().await;
         }
+        Ok(())
     }
 
-    /// Delete a watcher using its parameters
-    fn del(&mut self, watch: &WatchParams) -> Result<(), SvcError> {
-        if !self.watchers.iter().any(|item| &item.params == watch) {
-            Err(SvcError::WatchNotFound {})
-        } else {
-            self.watchers.retain(|item| &item.params != watch);
-            Ok(())
+    /// Cancel the watch threads and wait until they've stopped
+    async fn cancel(handle: WatchHandle) {
+        let (_cancel, threads) = &*handle;
+        threads.iter().for_each(|thread| thread.abort());
+        if let Ok((_cancel, threads)) = Arc::try_unwrap(handle) {
+            for thread in threads {
+                // the aborted threads complete with a cancelled error
+                let _ = thread.await;
+            }
         }
     }
 
-    /// Register a callback for the element using the store's watch feature
+    /// Register a callback for the element: the `desired` state is watched
+    /// using the store's watch feature and the `actual` state is watched
+    /// through the events of the registry. The notifications are queued in the
+    /// `outbox`, from which they're delivered through the `webhook`.
+    #[allow(clippy::too_many_arguments)]
     async fn watch(
         &self,
-        watch: &WatchParams,
-        store_arc: Arc<Mutex<impl Store + 'static>>,
+        watch: &WatchParamsCfg,
+        desired: Option<WatchResourceId>,
+        actual: Option<WatchResourceId>,
+        outbox: &Outbox,
+        webhook: Webhook,
+        max_age: Duration,
+        registry: &Registry,
     ) -> Result<WatchHandle, SvcError> {
-        let mut store = store_arc.lock().await;
-        let handle = {
-            // start watching before writing to the store
-            let channel = store.watch_obj(&self.watch_id.id).await?;
-            let watch = watch.clone();
+        let (cancel_sender, _) = tokio::sync::broadcast::channel(1);
+        let mut threads = vec![];
+        {
+            let cancel = cancel_sender.subscribe();
+            let outbox = outbox.clone();
+            let callback = watch.callback.clone();
+            threads.push(tokio::spawn(async move {
+                outbox.deliver(cancel, callback, webhook, max_age).await;
+            }));
+        }
+        if let Some(actual) = actual {
+            let events = registry.events.subscribe().await;
+            let cancel = cancel_sender.subscribe();
+            let outbox = outbox.clone();
+            let selection = Selection::new(watch.selector.clone());
+            let id = self.watch_id.id.clone();
+            threads.push(tokio::spawn(async move {
+                Self::actual_worker(cancel, events, outbox, selection, id, actual).await;
+            }));
+        }
+
+        if let Some(desired) = desired {
+            let store_arc = registry.store.clone();
+            let mut store = store_arc.lock().await;
+            let cancel = cancel_sender.subscribe();
+            let outbox = outbox.clone();
+            let selection = Selection::new(watch.selector.clone());
             let id = self.watch_id.id.clone();
             let store = store_arc.clone();
-            let (cancel_sender, cancel) = tokio::sync::broadcast::channel(1);
-            let thread = tokio::spawn(async move {
-                Self::watcher_worker(cancel, channel, watch, id, store).await;
-            });
-            Arc::new((cancel_sender, thread))
-        };
-        // now record the watch in the store
-        // if this fails the watch will be cancelled
-        store.put_obj(self).await.context(SvcStoreError {})?;
-        Ok(handle)
+            // start watching before writing to the store
+            let thread = if let WatchResourceId::Kind(_) = &desired {
+                let channel = store.watch_prefix(&desired.key(), None).await?;
+                tokio::spawn(async move {
+                    Self::prefix_worker(cancel, channel, outbox, selection, id, desired, store)
+                        .await;
+                })
+            } else {
+                let channel = store.watch_obj(&desired).await?;
+                tokio::spawn(async move {
+                    Self::watcher_worker(cancel, channel, outbox, selection, id, desired, store)
+                        .await;
+                })
+            };
+            threads.push(thread);
+        }
+        Ok(Arc::new((cancel_sender, threads)))
     }
 
     /// Worker thread which listens for events from the store (etcd) for a
     /// specific watcher which is created through `create_watcher`.
+    /// The `desired` resource is watched in the store and the notifications
+    /// are of the watched resource `id`.
     async fn watcher_worker(
         mut cancel: tokio::sync::broadcast::Receiver<()>,
         mut channel: StoreWatchReceiver,
-        params: WatchParams,
+        outbox: Outbox,
+        mut selection: Selection,
         id: WatchResourceId,
+        desired: WatchResourceId,
         store: Arc<Mutex<impl Store + 'static>>,
     ) {
         let mut last_seen: Option<serde_json::Value> = None;
@@ -234,12 +438,21 @@ impl WatchCfg {
                     match watch_event {
                         None => {
                             if let Some(chan) =
-                                Self::reconnect_watch(&mut cancel, &id, &store).await
+                                Self::reconnect_watch(&mut cancel, &desired, &store).await
                             {
-                                if Some(&chan.0) != last_seen.as_ref() {
+                                if Some(&chan.0) != last_seen.as_ref()
+                                    && selection.select(&id, Some(&chan.0))
+                                {
                                     // we can't know if we missed any event so just
                                     // compare the latest with last seen
-                                    Self::notify(&mut cancel, &params.callback).await;
+                                    let notification = Self::notification(
+                                        &id,
+                                        WatchType::Desired,
+                                        ResourceEventType::Put,
+                                        Some(chan.0.clone()),
+                                        None,
+                                    );
+                                    outbox.push(notification).await;
                                 }
                                 last_seen = Some(chan.0);
                                 channel = chan.1;
@@ -253,16 +466,29 @@ impl WatchCfg {
                         }
 
                         Some(Ok(result)) => {
-                            match &result {
-                                WatchEvent::Put(_, v) => {
-                                    last_seen = Some(v.clone());
+                            let (event_type, value, revision) = match result {
+                                WatchEvent::Put(_, value, revision) => {
+                                    last_seen = Some(value.clone());
+                                    (ResourceEventType::Put, Some(value), revision)
                                 }
-                                WatchEvent::Delete => {
-                                    // resource deleted so we don't need to keep on watching
-                                    return;
+                                WatchEvent::Delete(revision) => {
+                                    (ResourceEventType::Delete, None, revision)
                                 }
+                            };
+                            if selection.select(&id, value.as_ref()) {
+                                let notification = Self::notification(
+                                    &id,
+                                    WatchType::Desired,
+                                    event_type.clone(),
+                                    value,
+                                    Some(revision),
+                                );
+                                outbox.push(notification).await;
+                            }
+                            if event_type == ResourceEventType::Delete {
+                                // resource deleted so we don't need to keep on watching
+                                return;
                             }
-                            Self::notify(&mut cancel, &params.callback).await;
                         }
                     }
                 }
@@ -270,59 +496,158 @@ impl WatchCfg {
         }
     }
 
-    /// Notify the watcher using its callback
-    async fn notify(cancel: &mut tokio::sync::broadcast::Receiver<()>, callback: &WatchCallback) {
-        let mut tries = 0;
-        let mut log_failure = true;
+    /// Worker thread which listens for events from the store (etcd) for all
+    /// the resources of the `desired` kind, using a prefix watch, and notifies
+    /// the changes of the affected resources of the watched kind `id`.
+    /// The watch is reissued from the last seen revision whenever it's lost,
+    /// so no change is missed.
+    async fn prefix_worker(
+        mut cancel: tokio::sync::broadcast::Receiver<()>,
+        mut channel: StorePrefixWatchReceiver,
+        outbox: Outbox,
+        mut selection: Selection,
+        id: WatchResourceId,
+        desired: WatchResourceId,
+        store: Arc<Mutex<impl Store + 'static>>,
+    ) {
+        let prefix = desired.key();
+        let mut revision = None;
         loop {
-            match cancel.try_recv() {
-                Err(TryRecvError::Empty) => {}
-                // dropped or received the cancel signal so bail out
-                _ => return,
-            };
-
-            match &callback {
-                WatchCallback::Uri(uri) => {
-                    let request = reqwest::Client::new()
-                        .put(uri)
-                        .timeout(std::time::Duration::from_secs(1))
-                        .send();
-                    match request.await {
-                        Ok(resp) if resp.status().is_success() => {
-                            // notification complete
-                            if !log_failure {
-                                tracing::info!("Completed notification for url {}", uri);
+            tokio::select! {
+                _cancel = cancel.recv() => {
+                    // the watch has been cancelled
+                    return;
+                },
+                watch_event = channel.recv() => {
+                    match watch_event {
+                        None => {
+                            match Self::reconnect_prefix_watch(&mut cancel, &prefix, revision, &store)
+                                .await
+                            {
+                                Some(chan) => channel = chan,
+                                None => return,
                             }
-                            return;
                         }
-                        Ok(resp) => {
-                            if log_failure {
-                                tracing::error!(
-                                    "Notify response for url {} completed with error: {}. Quietly retrying...",
-                                    uri,
-                                    resp.status().to_string()
-                                );
-                                log_failure = false;
-                            }
+                        Some(Err(error)) => {
+                            tracing::error!("Error watching: {:?}", error);
                         }
-                        Err(error) => {
-                            if log_failure {
-                                tracing::error!(
-                                    "Failed to send notify for url {}, {}. Quietly retrying...",
-                                    uri,
-                                    error.to_string()
-                                );
-                                log_failure = false;
+                        Some(Ok(event)) => {
+                            revision = Some(event.revision + 1);
+                            let resource = match event.key.strip_prefix(&prefix) {
+                                Some(uuid) => Self::affected(&id, uuid),
+                                None => None,
+                            };
+                            let resource = match resource {
+                                Some(resource) => resource,
+                                None => continue,
+                            };
+                            if !selection.select(&resource, event.value.as_ref()) {
+                                continue;
                             }
+                            let event_type = match event.value {
+                                Some(_) => ResourceEventType::Put,
+                                None => ResourceEventType::Delete,
+                            };
+                            let notification = Self::notification(
+                                &resource,
+                                WatchType::Desired,
+                                event_type,
+                                event.value,
+                                Some(event.revision),
+                            );
+                            outbox.push(notification).await;
                         }
                     }
                 }
             }
+        }
+    }
 
-            backoff(&mut tries, Duration::from_secs(5)).await;
+    /// The resource of the watched `id` which is affected by a change of the
+    /// resource with the `uuid`, which for a kind is the resource of that kind
+    fn affected(id: &WatchResourceId, uuid: &str) -> Option<WatchResourceId> {
+        match id {
+            WatchResourceId::Kind(kind) => WatchResourceId::from_kind(*kind, uuid).ok(),
+            id => Some(id.clone()),
+        }
+    }
+
+    /// Worker thread which listens for the changes of the `actual` state of
+    /// the watched resource (or kind) `id`, as tracked by the registry.
+    /// Unlike the specs, resources come and go from the registry (eg: a node
+    /// which is temporarily offline) so the watch carries on after a delete.
+    async fn actual_worker(
+        mut cancel: tokio::sync::broadcast::Receiver<()>,
+        mut events: tokio::sync::broadcast::Receiver<ResourceEvent>,
+        outbox: Outbox,
+        mut selection: Selection,
+        id: WatchResourceId,
+        actual: WatchResourceId,
+    ) {
+        loop {
+            tokio::select! {
+                _cancel = cancel.recv() => {
+                    // the watch has been cancelled
+                    return;
+                },
+                event = events.recv() => {
+                    match event {
+                        Ok(event) => {
+                            if event.watch_type != WatchType::Actual
+                                || !actual.contains(&event.resource)
+                            {
+                                continue;
+                            }
+                            let resource =
+                                match Self::affected(&id, &event.resource.key_uuid()) {
+                                    Some(resource) => resource,
+                                    None => continue,
+                                };
+                            if !selection.select(&resource, event.value.as_ref()) {
+                                continue;
+                            }
+                            let notification = Self::notification(
+                                &resource,
+                                WatchType::Actual,
+                                event.event_type,
+                                event.value,
+                                None,
+                            );
+                            outbox.push(notification).await;
+                        }
+                        Err(RecvError::Lagged(missed)) => {
+                            tracing::warn!(
+                                "Watch of '{}' missed {} resource events",
+                                id.to_string(),
+                                missed
+                            );
+                        }
+                        Err(RecvError::Closed) => return,
+                    }
+                }
+            }
         }
     }
 
+    /// Notification of a change of the `watch_type` state of the watched
+    /// resource
+    fn notification(
+        id: &WatchResourceId,
+        watch_type: WatchType,
+        event_type: ResourceEventType,
+        value: Option<serde_json::Value>,
+        revision: Option<i64>,
+    ) -> models::WatchNotification {
+        models::WatchNotification::new_all(
+            &event_type,
+            id.to_string(),
+            revision,
+            value,
+            VERSION,
+            &watch_type,
+        )
+    }
+
     /// Reissue a watch for the given resource id.
     /// The actual value is returned as its useful to crudely verify if any
     /// update was missed.
@@ -372,6 +697,35 @@ impl WatchCfg {
             backoff(&mut tries, Duration::from_secs(5)).await;
         }
     }
+
+    /// Reissue the watch of the keys with the `prefix` from the `revision`
+    /// which follows the last seen change, retrying until the store is back
+    async fn reconnect_prefix_watch(
+        cancel: &mut tokio::sync::broadcast::Receiver<()>,
+        prefix: &str,
+        revision: Option<i64>,
+        store: &Arc<Mutex<impl Store + 'static>>,
+    ) -> Option<StorePrefixWatchReceiver> {
+        let mut tries = 0;
+        loop {
+            match cancel.try_recv() {
+                Err(TryRecvError::Empty) => {}
+                // dropped or received cancel signal
+                _ => return None,
+            };
+
+            {
+                let mut store = store.lock().await;
+                if store.online().await {
+                    if let Ok(channel) = store.watch_prefix(prefix, revision).await {
+                        return Some(channel);
+                    }
+                }
+            }
+
+            backoff(&mut tries, Duration::from_secs(5)).await;
+        }
+    }
 }
 
 /// Simple backoff delay which gets gradually larger up to a `max` duration.
@@ -392,14 +746,23 @@ impl StoreWatcher {
         let watches = match self.get_watch_cfg(watch_id).await {
             Some(db) => {
                 let db = db.lock().await;
-                db.watchers
-                    .iter()
-                    .map(|e| Watch {
+                let mut watches = vec![];
+                for e in &db.watchers {
+                    let delivery = match &e.outbox {
+                        Some(outbox) => Some(outbox.status().await),
+                        None => None,
+                    };
+                    watches.push(Watch {
                         id: watch_id.id.clone(),
                         callback: e.callback.clone(),
                         watch_type: e.type_.clone(),
-                    })
-                    .collect()
+                        // the secret is never disclosed
+                        secret: None,
+                        filter: e.selector.clone().map(Filter::Selector).unwrap_or_default(),
+                        delivery,
+                    });
+                }
+                watches
             }
             None => vec![],
         };
@@ -439,18 +802,104 @@ impl StoreWatcher {
         watch_id: &WatchCfgId,
         callback: &WatchCallback,
         type_: &WatchType,
+        secret: Option<String>,
+        filter: &Filter,
     ) -> Result<(), SvcError> {
+        if let WatchCallback::Subject(subject) = callback {
+            if !Self::valid_subject(subject) {
+                return Err(SvcError::InvalidArguments {});
+            }
+        }
+        let selector = match filter {
+            Filter::None => None,
+            Filter::Selector(selector) => Some(selector.clone()),
+            filter => {
+                return Err(SvcError::InvalidFilter {
+                    filter: filter.clone(),
+                })
+            }
+        };
+        WatchCfg::exists(&watch_id.id, type_, &self.registry).await?;
         let watch_cfg = self.get_or_create_watch_cfg(watch_id).await;
-        let watch = WatchParams {
-            callback: callback.clone(),
-            type_: type_.clone(),
+        let watch = WatchParamsCfg {
+            params: WatchParams {
+                callback: callback.clone(),
+                type_: type_.clone(),
+            },
+            secret,
+            selector,
+            handle: None,
+            outbox: None,
         };
 
         let mut watch_cfg = watch_cfg.lock().await;
-        watch_cfg.add(&watch, self.registry.store.clone()).await?;
+        watch_cfg
+            .add(watch, self.client.clone(), self.max_age, &self.registry)
+            .await?;
         Ok(())
     }
 
+    /// Restore the watches which are recorded in the store, along with their
+    /// notifications which were pending delivery, eg: after a restart.
+    /// The watched resources are not required to exist, as the registry may not
+    /// know of their actual state yet.
+    pub async fn restore(&mut self) {
+        let entries = {
+            let mut store = self.registry.store.lock().await;
+            match store
+                .get_values_prefix(&key_prefix(StorableObjectType::WatchConfig))
+                .await
+            {
+                Ok(entries) => entries,
+                Err(error) => {
+                    tracing::error!("Failed to restore the watches: {}", error);
+                    return;
+                }
+            }
+        };
+        for (key, value) in entries {
+            let recorded = match serde_json::from_value::<WatchCfg>(value) {
+                Ok(recorded) => recorded,
+                Err(error) => {
+                    tracing::error!("Failed to restore the watches of '{}': {}", key, error);
+                    continue;
+                }
+            };
+            let watch_cfg = self.get_or_create_watch_cfg(&recorded.watch_id).await;
+            let mut watch_cfg = watch_cfg.lock().await;
+            for watch in recorded.watchers {
+                let callback = watch.callback.to_string();
+                if let Err(error) = watch_cfg
+                    .add(watch, self.client.clone(), self.max_age, &self.registry)
+                    .await
+                {
+                    tracing::error!(
+                        "Failed to restore the watch of '{}' with callback {}: {}",
+                        recorded.watch_id.id.to_string(),
+                        callback,
+                        error
+                    );
+                }
+            }
+        }
+    }
+
+    /// Whether the notifications may be published on the bus `subject`, which
+    /// must be a plain subject (no wildcards) within the `watches.` namespace,
+    /// so it can't reach the control plane channels nor the reply inboxes
+    /// (`_INBOX.`) and system subjects (`$SYS.`) of the message bus
+    fn valid_subject(subject: &str) -> bool {
+        match subject.strip_prefix(WATCH_SUBJECT_PREFIX) {
+            Some(name) => {
+                !name.is_empty()
+                    && !subject
+                        .chars()
+                        .any(|c| c.is_whitespace() || c == '*' || c == '>')
+            }
+            None => false,
+        }
+    }
+
     /// Delete existing watch with the given parameters
     pub async fn delete_watch(
         &mut self,
@@ -464,7 +913,6 @@ impl StoreWatcher {
             callback: callback.clone(),
             type_: type_.clone(),
         };
-        watch_cfg.del(&watch)?;
-        Ok(())
+        watch_cfg.del(&watch, &self.registry).await
     }
 }
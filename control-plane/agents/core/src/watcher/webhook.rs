@@ -0,0 +1,99 @@
+//! Delivery of the watch notifications to the HTTP(S) callbacks.
+//! A watch may carry a shared secret, in which case every delivery is signed so
+//! that the receivers can reject forged events: the `X-Watch-Signature` header
+//! holds `sha256=` followed by the hex encoded HMAC-SHA256, keyed with the
+//! secret, of the `X-Watch-Timestamp` header value, a `.` and the body.
+use ring::hmac;
+use std::{
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Header with the unix time (in seconds) at which the notification was sent
+pub(crate) const TIMESTAMP_HEADER: &str = "X-Watch-Timestamp";
+/// Header with the signature of the timestamp and body of the notification
+pub(crate) const SIGNATURE_HEADER: &str = "X-Watch-Signature";
+/// Timeout of each delivery attempt
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Sends the notifications of a watch to its callback
+#[derive(Debug, Clone)]
+pub(crate) struct Webhook {
+    client: reqwest::Client,
+    secret: Option<String>,
+}
+
+impl Webhook {
+    /// New webhook which signs the notifications with the `secret`, if any
+    pub(crate) fn new(client: reqwest::Client, secret: Option<String>) -> Self {
+        Self { client, secret }
+    }
+
+    /// Send the json `body` to the `uri`
+    /// The timestamp is refreshed with every attempt so that the retries are
+    /// not mistaken for replays by the receivers
+    pub(crate) async fn send(&self, uri: &str, body: &[u8]) -> reqwest::Result<reqwest::Response> {
+        let mut request = self
+            .client
+            .put(uri)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .timeout(DELIVERY_TIMEOUT);
+        if let Some(secret) = &self.secret {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|since| since.as_secs())
+                .unwrap_or_default();
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp)
+                .header(SIGNATURE_HEADER, signature(secret, timestamp, body));
+        }
+        request.body(body.to_vec()).send().await
+    }
+}
+
+/// HTTP client for the watch callbacks, which also trusts the PEM CA
+/// certificate at `ca`, if provided, to verify the HTTPS callbacks
+pub(crate) fn client(ca: Option<&Path>) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder();
+    if let Some(ca) = ca {
+        let pem = std::fs::read(ca).expect("Failed to read the watch callback CA certificate file");
+        let certificate = reqwest::Certificate::from_pem(&pem)
+            .expect("Failed to parse the watch callback CA certificate");
+        builder = builder.add_root_certificate(certificate);
+    }
+    builder
+        .build()
+        .expect("Failed to build the watch callback HTTP client")
+}
+
+/// Signature of the `body` sent at the `timestamp`, keyed with the `secret`
+pub(crate) fn signature(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let mut context = hmac::Context::with_key(&key);
+    context.update(timestamp.to_string().as_bytes());
+    context.update(b".");
+    context.update(body);
+    format!("sha256={}", hex::encode(context.sign().as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notification_signature() {
+        let body = br#"{"version":"v0"}"#;
+        let signed = signature("secret", 1_600_000_000, body);
+        assert!(signed.starts_with("sha256="));
+
+        // the receiver recomputes the tag over the same timestamp and body
+        let key = hmac::Key::new(hmac::HMAC_SHA256, b"secret");
+        let tag = hex::decode(signed.trim_start_matches("sha256=")).unwrap();
+        hmac::verify(&key, br#"1600000000.{"version":"v0"}"#, &tag).unwrap();
+
+        // a different timestamp, body or secret yields a different signature
+        assert_ne!(signed, signature("secret", 1_600_000_001, body));
+        assert_ne!(signed, signature("secret", 1_600_000_000, b"{}"));
+        assert_ne!(signed, signature("other", 1_600_000_000, body));
+    }
+}
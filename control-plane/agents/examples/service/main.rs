@@ -57,8 +57,30 @@ impl ServiceSubscriber for ServiceHandler<GetSvcName> {
     }
 }
 
+/// Audits the requests handled by the `ServiceHandler`
+#[derive(Clone, Default)]
+struct AuditHandler<T> {
+    data: PhantomData<T>,
+}
+
+#[async_trait]
+impl ServiceSubscriber for AuditHandler<GetSvcName> {
+    async fn handler(&self, args: Arguments<'_>) -> Result<(), SvcError> {
+        let msg: ReceivedMessage<GetSvcName> = args.request.try_into()?;
+        tracing::info!("Audit: '{}' sent {:?}", msg.sender(), msg.inner());
+        Ok(())
+    }
+    fn filter(&self) -> Vec<MessageId> {
+        vec![GetSvcName::default().id()]
+    }
+    fn role(&self) -> SubscriberRole {
+        SubscriberRole::Observer
+    }
+}
+
 #[tokio::main]
 async fn main() {
+    tracing_subscriber::fmt().with_env_filter("info").init();
     let cli_args = CliArgs::from_args();
 
     if cli_args.client {
@@ -81,6 +103,7 @@ async fn server() {
 
     Service::builder(cli_args.url, ChannelVs::Default)
         .with_subscription(ServiceHandler::<GetSvcName>::default())
+        .with_subscription(AuditHandler::<GetSvcName>::default())
         .run()
         .await;
 }
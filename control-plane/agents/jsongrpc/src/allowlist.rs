@@ -0,0 +1,228 @@
+use serde::Deserialize;
+use std::{collections::HashSet, fs::File};
+
+/// Access which a JSON gRPC method requires
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Access {
+    /// the method only reads the state of the node
+    ReadOnly,
+    /// the method changes the state of the node
+    Mutating,
+}
+
+impl std::fmt::Display for Access {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Access::ReadOnly => write!(f, "read-only"),
+            Access::Mutating => write!(f, "mutating"),
+        }
+    }
+}
+
+/// Methods of an access level and the roles which may call them
+#[derive(Deserialize, Debug, Clone, Default)]
+struct AccessRule {
+    #[serde(default)]
+    methods: HashSet<String>,
+    #[serde(default)]
+    roles: HashSet<String>,
+}
+
+/// Allowlist of the JSON gRPC methods which may be called, eg:
+/// ```yaml
+/// readOnly:
+///   methods: [bdev_get_bdevs, nvmf_get_subsystems]
+///   roles: [viewer, operator]
+/// mutating:
+///   methods: [bdev_malloc_create, bdev_malloc_delete]
+///   roles: [admin]
+/// ```
+/// Methods which are not listed may not be called at all. The roles which
+/// may call the mutating methods may also call the read-only methods.
+/// Requests without roles, ie: which were not authenticated by the REST
+/// server, may not call any method, unless the read-only methods are
+/// explicitly opened to them with `unauthenticatedReadOnly: true`, or all the
+/// methods with `unauthenticatedMutating: true`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Allowlist {
+    #[serde(default)]
+    read_only: AccessRule,
+    #[serde(default)]
+    mutating: AccessRule,
+    /// whether the requests without roles may call the read-only methods
+    #[serde(default)]
+    unauthenticated_read_only: bool,
+    /// whether the requests without roles may call the mutating methods, and
+    /// so the read-only methods as well
+    #[serde(default)]
+    unauthenticated_mutating: bool,
+}
+
+impl Default for Allowlist {
+    /// Only the methods which read the state of the node are allowed, to the
+    /// viewers, operators and admins. No mutating method is allowed.
+    fn default() -> Self {
+        let set = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        Self {
+            read_only: AccessRule {
+                methods: set(&[
+                    "rpc_get_methods",
+                    "spdk_get_version",
+                    "bdev_get_bdevs",
+                    "bdev_get_iostat",
+                    "bdev_lvol_get_lvstores",
+                    "bdev_nvme_get_controllers",
+                    "nvmf_get_subsystems",
+                    "nvmf_get_transports",
+                    "framework_get_reactors",
+                    "thread_get_stats",
+                ]),
+                roles: set(&["viewer", "operator", "admin"]),
+            },
+            mutating: AccessRule {
+                methods: HashSet::new(),
+                roles: set(&["admin"]),
+            },
+            unauthenticated_read_only: false,
+            unauthenticated_mutating: false,
+        }
+    }
+}
+
+impl Allowlist {
+    /// Load the allowlist from the YAML (or JSON) file at `path`
+    pub(super) fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_yaml::from_reader(File::open(path)?)?)
+    }
+
+    /// Check that the `method` may be called by a caller with the given
+    /// `roles`, returning the access it requires or the reason of the denial
+    pub(super) fn authorize(
+        &self,
+        method: &str,
+        roles: Option<&[String]>,
+    ) -> Result<Access, String> {
+        let access = if self.read_only.methods.contains(method) {
+            Access::ReadOnly
+        } else if self.mutating.methods.contains(method) {
+            Access::Mutating
+        } else {
+            return Err("the method is not in the allowlist".to_string());
+        };
+        let roles = match roles {
+            Some(roles) => roles,
+            None => {
+                let allowed = match access {
+                    Access::ReadOnly => {
+                        self.unauthenticated_read_only || self.unauthenticated_mutating
+                    }
+                    Access::Mutating => self.unauthenticated_mutating,
+                };
+                return if allowed {
+                    Ok(access)
+                } else {
+                    Err(format!(
+                        "unauthenticated callers may not call {} methods",
+                        access
+                    ))
+                };
+            }
+        };
+        let allowed = roles.iter().any(|role| match access {
+            Access::ReadOnly => {
+                self.read_only.roles.contains(role) || self.mutating.roles.contains(role)
+            }
+            Access::Mutating => self.mutating.roles.contains(role),
+        });
+        if allowed {
+            Ok(access)
+        } else {
+            Err(format!(
+                "none of the roles {:?} may call {} methods",
+                roles, access
+            ))
+        }
+    }
+}
+
+#[test]
+fn allowlist_test() {
+    let roles = |roles: &[&str]| roles.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+
+    let allowlist = Allowlist::default();
+    assert_eq!(
+        allowlist.authorize("bdev_get_bdevs", Some(&roles(&["viewer"]))),
+        Ok(Access::ReadOnly)
+    );
+    assert!(allowlist
+        .authorize("bdev_get_bdevs", Some(&roles(&["other"])))
+        .is_err());
+    assert!(allowlist
+        .authorize("bdev_malloc_create", Some(&roles(&["admin"])))
+        .is_err());
+    assert!(allowlist.authorize("bdev_malloc_create", None).is_err());
+    assert!(allowlist.authorize("bdev_get_bdevs", None).is_err());
+
+    let allowlist: Allowlist = serde_yaml::from_str(
+        r#"
+readOnly:
+  methods: [bdev_get_bdevs]
+  roles: [viewer]
+mutating:
+  methods: [bdev_malloc_create]
+  roles: [admin]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        allowlist.authorize("bdev_malloc_create", Some(&roles(&["admin"]))),
+        Ok(Access::Mutating)
+    );
+    assert_eq!(
+        allowlist.authorize("bdev_get_bdevs", Some(&roles(&["admin"]))),
+        Ok(Access::ReadOnly)
+    );
+    assert!(allowlist
+        .authorize("bdev_malloc_create", Some(&roles(&["viewer"])))
+        .is_err());
+    assert!(allowlist
+        .authorize("bdev_malloc_create", Some(&roles(&[])))
+        .is_err());
+    assert!(allowlist.authorize("bdev_malloc_create", None).is_err());
+    assert!(allowlist.authorize("bdev_get_bdevs", None).is_err());
+
+    let allowlist: Allowlist = serde_yaml::from_str(
+        r#"
+readOnly:
+  methods: [bdev_get_bdevs]
+mutating:
+  methods: [bdev_malloc_create]
+unauthenticatedReadOnly: true
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        allowlist.authorize("bdev_get_bdevs", None),
+        Ok(Access::ReadOnly)
+    );
+    assert!(allowlist.authorize("bdev_malloc_create", None).is_err());
+    assert!(allowlist.authorize("spdk_get_version", None).is_err());
+
+    let allowlist: Allowlist = serde_yaml::from_str(
+        r#"
+mutating:
+  methods: [bdev_malloc_create]
+  roles: [admin]
+unauthenticatedMutating: true
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        allowlist.authorize("bdev_malloc_create", None),
+        Ok(Access::Mutating)
+    );
+    assert!(allowlist
+        .authorize("bdev_malloc_create", Some(&roles(&["viewer"])))
+        .is_err());
+}
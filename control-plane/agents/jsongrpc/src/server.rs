@@ -1,5 +1,7 @@
+pub mod allowlist;
 pub mod service;
 
+use allowlist::Allowlist;
 use async_trait::async_trait;
 use common::{errors::SvcError, *};
 use common_lib::{
@@ -18,6 +20,17 @@ struct CliArgs {
     /// Default: nats://127.0.0.1:4222
     #[structopt(long, short, default_value = "nats://127.0.0.1:4222")]
     nats: String,
+
+    /// The Nats Server connection options (TLS and credentials)
+    #[structopt(flatten)]
+    nats_options: BusOptions,
+
+    /// Path to the allowlist file (YAML) of the JSON gRPC methods which may
+    /// be called, as read-only or mutating methods, and of the roles which
+    /// may call them.
+    /// Default: only a few read-only methods are allowed
+    #[structopt(long)]
+    allowlist: Option<String>,
 }
 
 /// Needed so we can implement the ServiceSubscriber trait for
@@ -37,7 +50,8 @@ macro_rules! impl_service_handler {
             async fn handler(&self, args: Arguments<'_>) -> Result<(), SvcError> {
                 let request: ReceivedMessage<$RequestType> = args.request.try_into()?;
 
-                let reply = JsonGrpcSvc::$ServiceFnName(&request.inner()).await?;
+                let service: &JsonGrpcSvc = args.context.get_state()?;
+                let reply = service.$ServiceFnName(&request.inner()).await?;
                 Ok(request.reply(reply).await?)
             }
             fn filter(&self) -> Vec<MessageId> {
@@ -68,9 +82,15 @@ async fn main() {
 }
 
 async fn server(cli_args: CliArgs) {
+    let allowlist = match &cli_args.allowlist {
+        Some(path) => Allowlist::from_file(path).expect("Should load the allowlist file"),
+        None => Allowlist::default(),
+    };
     Service::builder(cli_args.nats, ChannelVs::JsonGrpc)
+        .with_bus_options(cli_args.nats_options)
         .connect_message_bus()
         .await
+        .with_shared_state(JsonGrpcSvc::new(allowlist))
         .with_subscription(ServiceHandler::<JsonGrpcRequest>::default())
         .with_default_liveness()
         .run()
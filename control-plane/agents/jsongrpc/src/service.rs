@@ -1,8 +1,9 @@
 // clippy warning caused by the instrument macro
 #![allow(clippy::unit_arg)]
 
+use crate::allowlist::Allowlist;
 use ::rpc::mayastor::{JsonRpcReply, JsonRpcRequest};
-use common::errors::{BusGetNode, JsonRpcDeserialise, SvcError};
+use common::errors::{BusGetNode, JsonRpcConnect, JsonRpcDeserialise, SvcError};
 use common_lib::{
     mbus_api::message_bus::v0::{MessageBus, *},
     types::v0::message_bus::JsonGrpcRequest,
@@ -11,22 +12,61 @@ use rpc::mayastor::json_rpc_client::JsonRpcClient;
 use snafu::ResultExt;
 
 #[derive(Clone, Default)]
-pub(super) struct JsonGrpcSvc {}
+pub(super) struct JsonGrpcSvc {
+    /// methods which may be called and the roles which may call them
+    allowlist: Allowlist,
+}
 
 /// JSON gRPC service implementation
 impl JsonGrpcSvc {
-    /// Generic JSON gRPC call issued to Mayastor using the JsonRpcClient.
+    /// New service which only allows calls to the methods of the `allowlist`
+    pub(super) fn new(allowlist: Allowlist) -> Self {
+        Self { allowlist }
+    }
+
+    /// Generic JSON gRPC call issued to Mayastor using the JsonRpcClient, if
+    /// the method is allowed for the caller.
+    /// Every call is audited with the `audit` target.
     pub(super) async fn json_grpc_call(
+        &self,
         request: &JsonGrpcRequest,
     ) -> Result<serde_json::Value, SvcError> {
+        let result = self.call(request).await;
+        let outcome = match &result {
+            Ok(_) => "success".to_string(),
+            Err(error) => error.to_string(),
+        };
+        tracing::info!(
+            target: "audit",
+            caller = %request.caller.as_deref().unwrap_or("anonymous"),
+            node = %request.node,
+            method = %request.method,
+            outcome = %outcome,
+            "JSON gRPC call"
+        );
+        result
+    }
+
+    async fn call(&self, request: &JsonGrpcRequest) -> Result<serde_json::Value, SvcError> {
+        self.allowlist
+            .authorize(request.method.as_str(), request.roles.as_deref())
+            .map_err(|reason| SvcError::JsonRpcNotAllowed {
+                method: request.method.to_string(),
+                reason,
+            })?;
         let node = MessageBus::get_node(&request.node)
             .await
             .context(BusGetNode {
                 node: request.node.clone(),
             })?;
-        let mut client = JsonRpcClient::connect(format!("http://{}", node.grpc_endpoint))
-            .await
-            .unwrap();
+        let endpoint = format!("http://{}", node.grpc_endpoint);
+        let mut client =
+            JsonRpcClient::connect(endpoint.clone())
+                .await
+                .context(JsonRpcConnect {
+                    node: request.node.to_string(),
+                    endpoint,
+                })?;
         let response: JsonRpcReply = client
             .json_rpc_call(JsonRpcRequest {
                 method: request.method.to_string(),
@@ -1,12 +1,17 @@
-#[macro_use]
-extern crate lazy_static;
+pub mod service;
 
 use async_trait::async_trait;
-use common::*;
-use mbus_api::{v0::*, *};
-use std::{collections::HashMap, convert::TryInto, marker::PhantomData};
+use common::{errors::SvcError, *};
+use common_lib::{
+    mbus_api::*,
+    types::v0::message_bus::{
+        ChannelVs, ConfigGetCurrent, ConfigUpdate, GetConfigRevision, GetConfigRevisions,
+        RollbackConfig, SetNodeLabels, SetNodesConfig,
+    },
+};
+use service::*;
+use std::{convert::TryInto, marker::PhantomData};
 use structopt::StructOpt;
-use tokio::sync::Mutex;
 use tracing::info;
 
 #[derive(Debug, StructOpt)]
@@ -16,6 +21,24 @@ struct CliArgs {
     /// Default: nats://127.0.0.1:4222
     #[structopt(long, short, default_value = "nats://127.0.0.1:4222")]
     url: String,
+
+    /// The Nats Server connection options (TLS and credentials)
+    #[structopt(flatten)]
+    nats_options: BusOptions,
+
+    /// The Persistent Store URLs to connect to
+    /// (supports the http/https schema)
+    /// Default: http://localhost:2379
+    #[structopt(long, short, default_value = "http://localhost:2379")]
+    store: String,
+
+    /// The number of the latest revisions which are kept for each config
+    #[structopt(long, default_value = "32")]
+    config_revisions: usize,
+
+    /// How long to wait for a node to acknowledge a pushed config revision
+    #[structopt(long, default_value = "1s")]
+    push_timeout: humantime::Duration,
 }
 
 /// Needed so we can implement the ServiceSubscriber trait for
@@ -25,106 +48,36 @@ struct ServiceHandler<T> {
     data: PhantomData<T>,
 }
 
-#[derive(Default)]
-struct ConfigState {
-    state: Mutex<HashMap<SenderId, HashMap<Config, Vec<u8>>>>,
-}
-
-lazy_static! {
-    static ref CONFIGS: ConfigState = Default::default();
-}
-
-#[async_trait]
-impl ServiceSubscriber for ServiceHandler<ConfigUpdate> {
-    async fn handler(&self, args: Arguments<'_>) -> Result<(), Error> {
-        let data: ConfigUpdate = args.request.inner()?;
-        info!("Received: {:?}", data);
-
-        let msg: ReceivedMessageExt<ConfigUpdate, ()> =
-            args.request.try_into()?;
-        let config = msg.inner();
-
-        let mut state = CONFIGS.state.lock().await;
-
-        match state.get_mut(&msg.sender()) {
-            Some(map) => {
-                map.insert(config.kind, config.data);
-            }
-            None => {
-                let mut config_map = HashMap::new();
-                config_map.insert(config.kind, config.data);
-                state.insert(msg.sender(), config_map);
+macro_rules! impl_service_handler {
+    // RequestType is the message bus request type
+    // ServiceFnName is the name of the service function to route the request
+    // into, along with the sender of the request
+    ($RequestType:ident, $ServiceFnName:ident) => {
+        #[async_trait]
+        impl ServiceSubscriber for ServiceHandler<$RequestType> {
+            async fn handler(&self, args: Arguments<'_>) -> Result<(), SvcError> {
+                let request: ReceivedMessage<$RequestType> = args.request.try_into()?;
+
+                let service: &ConfigSvc = args.context.get_state()?;
+                let reply = service
+                    .$ServiceFnName(request.sender(), &request.inner())
+                    .await?;
+                Ok(request.reply(reply).await?)
             }
-        }
-
-        msg.reply(()).await
-    }
-    fn filter(&self) -> Vec<MessageId> {
-        vec![ConfigUpdate::default().id()]
-    }
-}
-
-#[async_trait]
-impl ServiceSubscriber for ServiceHandler<ConfigGetCurrent> {
-    async fn handler(&self, args: Arguments<'_>) -> Result<(), Error> {
-        let data: ConfigGetCurrent = args.request.inner()?;
-        info!("Received: {:?}", data);
-
-        let msg: ReceivedMessageExt<ConfigGetCurrent, ReplyConfig> =
-            args.request.try_into()?;
-        let request = msg.inner();
-
-        let state = CONFIGS.state.lock().await;
-
-        match state.get(&msg.sender()) {
-            Some(config) => match config.get(&request.kind) {
-                Some(data) => {
-                    msg.reply(ReplyConfig {
-                        config: data.clone(),
-                    })
-                    .await
-                }
-                None => {
-                    msg.reply(Err(ReplyError::WithMessage {
-                        message: "Config is missing".into(),
-                    }))
-                    .await
-                }
-            },
-            None => {
-                msg.reply(Err(ReplyError::WithMessage {
-                    message: "Config is missing".into(),
-                }))
-                .await
+            fn filter(&self) -> Vec<MessageId> {
+                vec![$RequestType::default().id()]
             }
         }
-    }
-    fn filter(&self) -> Vec<MessageId> {
-        vec![ConfigGetCurrent::default().id()]
-    }
+    };
 }
 
-#[async_trait]
-impl ServiceSubscriber for ServiceHandler<Register> {
-    async fn handler(&self, args: Arguments<'_>) -> Result<(), Error> {
-        let _: ReceivedMessageExt<Register, ()> = args.request.try_into()?;
-        Ok(())
-    }
-    fn filter(&self) -> Vec<MessageId> {
-        vec![Register::default().id()]
-    }
-}
-
-#[async_trait]
-impl ServiceSubscriber for ServiceHandler<Deregister> {
-    async fn handler(&self, args: Arguments<'_>) -> Result<(), Error> {
-        let _: ReceivedMessageExt<Deregister, ()> = args.request.try_into()?;
-        Ok(())
-    }
-    fn filter(&self) -> Vec<MessageId> {
-        vec![Deregister::default().id()]
-    }
-}
+impl_service_handler!(ConfigUpdate, config_update);
+impl_service_handler!(ConfigGetCurrent, config_get_current);
+impl_service_handler!(GetConfigRevisions, get_config_revisions);
+impl_service_handler!(GetConfigRevision, get_config_revision);
+impl_service_handler!(RollbackConfig, rollback_config);
+impl_service_handler!(SetNodesConfig, set_nodes_config);
+impl_service_handler!(SetNodeLabels, set_node_labels);
 
 fn init_tracing() {
     if let Ok(filter) = tracing_subscriber::EnvFilter::try_from_default_env() {
@@ -145,12 +98,25 @@ async fn main() {
 }
 
 async fn server(cli_args: CliArgs) {
+    let service = ConfigSvc::new(
+        &cli_args.store,
+        cli_args.config_revisions,
+        cli_args.push_timeout.into(),
+    )
+    .await;
     Service::builder(cli_args.url, ChannelVs::Kiiss)
+        .with_bus_options(cli_args.nats_options)
+        .connect_message_bus()
+        .await
+        .with_shared_state(service)
         .with_subscription(ServiceHandler::<ConfigUpdate>::default())
         .with_subscription(ServiceHandler::<ConfigGetCurrent>::default())
-        .with_channel(ChannelVs::Registry)
-        .with_subscription(ServiceHandler::<Register>::default())
-        .with_subscription(ServiceHandler::<Deregister>::default())
+        .with_subscription(ServiceHandler::<GetConfigRevisions>::default())
+        .with_subscription(ServiceHandler::<GetConfigRevision>::default())
+        .with_subscription(ServiceHandler::<RollbackConfig>::default())
+        .with_subscription(ServiceHandler::<SetNodesConfig>::default())
+        .with_subscription(ServiceHandler::<SetNodeLabels>::default())
+        .with_default_liveness()
         .run()
         .await;
 }
@@ -0,0 +1,577 @@
+use common::errors::SvcError;
+use common_lib::{
+    mbus_api::{
+        v0::{ConfigRevisions, NodeConfigAcks},
+        Message, ResourceKind, SenderId, TimeoutOptions,
+    },
+    store::etcd::Etcd,
+    types::v0::{
+        message_bus::{
+            Config, ConfigChanged, ConfigGetCurrent, ConfigRevision, ConfigUpdate,
+            GetConfigRevision, GetConfigRevisions, NodeConfigAck, ReplyConfig, RollbackConfig,
+            Selector, SetNodeLabels, SetNodesConfig,
+        },
+        store::{
+            config::NodeConfig,
+            definitions::{key_prefix, StorableObjectType, Store},
+            node::{NodeLabels, NodeSpec},
+        },
+    },
+};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+/// Configs of each kind, per sender
+type Configs = HashMap<SenderId, HashMap<Config, NodeConfig>>;
+
+/// Keep It In Sync Service, which keeps the configs sent by the nodes in the
+/// persistent store so that they can reload them after a restart of either.
+/// Every update of a config is kept as a numbered revision, so that a config
+/// can be rolled back to an earlier revision, and is pushed to the node on its
+/// own subject (see `ConfigChanged::channel`).
+#[derive(Clone)]
+pub(super) struct ConfigSvc {
+    store: Arc<Mutex<Etcd>>,
+    /// the configs in the store, loaded at startup and kept up to date
+    configs: Arc<Mutex<Configs>>,
+    /// number of the latest revisions which are kept for each config
+    max_revisions: usize,
+    /// how long to wait for a node to acknowledge a pushed config revision
+    push_timeout: Duration,
+}
+
+impl ConfigSvc {
+    /// Connect to the store at `store_url` and load the configs it holds,
+    /// keeping the latest `max_revisions` revisions of each config and waiting
+    /// up to `push_timeout` for a node to acknowledge a pushed revision
+    pub(super) async fn new(store_url: &str, max_revisions: usize, push_timeout: Duration) -> Self {
+        let mut store = Etcd::new(store_url)
+            .await
+            .expect("Should connect to the persistent store");
+        let configs = Self::load(&mut store)
+            .await
+            .expect("Should load the configs from the persistent store");
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            configs: Arc::new(Mutex::new(configs)),
+            max_revisions,
+            push_timeout,
+        }
+    }
+
+    /// Load all the configs from the `store`
+    async fn load(store: &mut Etcd) -> Result<Configs, SvcError> {
+        let mut configs = Configs::new();
+        let entries = store
+            .get_values_prefix(&key_prefix(StorableObjectType::NodeConfig))
+            .await?;
+        for (key, value) in entries {
+            match serde_json::from_value::<NodeConfig>(value) {
+                Ok(config) => {
+                    configs
+                        .entry(config.sender.clone())
+                        .or_default()
+                        .insert(config.kind.clone(), config);
+                }
+                Err(error) => tracing::error!("Ignoring the invalid config '{}': {}", key, error),
+            }
+        }
+        tracing::info!("Loaded the configs of {} nodes", configs.len());
+        Ok(configs)
+    }
+
+    /// Add a new revision of the config `kind` of the node `node`, which is
+    /// persisted before it's returned
+    async fn add_revision(
+        &self,
+        node: &str,
+        kind: &Config,
+        data: Vec<u8>,
+        rollback_of: Option<u64>,
+    ) -> Result<ConfigRevision, SvcError> {
+        let mut configs = self.configs.lock().await;
+        let mut config = configs
+            .get(node)
+            .and_then(|configs| configs.get(kind))
+            .cloned()
+            .unwrap_or_else(|| NodeConfig::new(node, kind));
+        let revision = config.push(data, rollback_of, self.max_revisions);
+        self.store.lock().await.put_obj(&config).await?;
+        configs
+            .entry(config.sender.clone())
+            .or_default()
+            .insert(config.kind.clone(), config);
+        Ok(revision)
+    }
+
+    /// Push the new `revision` of the config `kind` to the node `node`, which
+    /// acknowledges it by replying
+    async fn notify(&self, node: &str, kind: &Config, revision: &ConfigRevision) -> NodeConfigAck {
+        let changed = ConfigChanged {
+            kind: kind.clone(),
+            revision: revision.revision,
+            data: revision.data.clone(),
+        };
+        let options = TimeoutOptions::new()
+            .with_timeout(self.push_timeout)
+            .with_max_retries(0);
+        let error = match changed
+            .request_on_ext(ConfigChanged::channel(node), options)
+            .await
+        {
+            Ok(()) => None,
+            Err(error) => {
+                tracing::warn!(
+                    "Node {} did not acknowledge revision {} of config {}: {}",
+                    node,
+                    revision.revision,
+                    kind.to_string(),
+                    error
+                );
+                Some(error.to_string())
+            }
+        };
+        NodeConfigAck {
+            node: node.into(),
+            revision: revision.revision,
+            acknowledged: error.is_none(),
+            error,
+        }
+    }
+
+    /// Push the new `revision` of the config `kind` to the node `node` in the
+    /// background, so the request which changed it needn't wait for the node
+    fn notify_background(&self, node: &str, kind: &Config, revision: ConfigRevision) {
+        let service = self.clone();
+        let node = node.to_string();
+        let kind = kind.clone();
+        tokio::spawn(async move { service.notify(&node, &kind, &revision).await });
+    }
+
+    /// Ids of the nodes which have all of the `labels`, as set by
+    /// `SetNodeLabels`, where no such node is an error
+    async fn select_nodes(&self, labels: &[String]) -> Result<Vec<String>, SvcError> {
+        let selector = Selector {
+            labels: labels.to_vec(),
+            ..Default::default()
+        };
+        let entries = self
+            .store
+            .lock()
+            .await
+            .get_values_prefix(&key_prefix(StorableObjectType::NodeSpec))
+            .await?;
+        let nodes = entries
+            .into_iter()
+            .filter(|(_, value)| selector.matches_value(value))
+            .filter_map(
+                |(key, value)| match serde_json::from_value::<NodeSpec>(value) {
+                    Ok(spec) => Some(spec.id().to_string()),
+                    Err(error) => {
+                        tracing::error!("Ignoring the invalid node spec '{}': {}", key, error);
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+        if nodes.is_empty() {
+            return Err(SvcError::NotFound {
+                kind: ResourceKind::Node,
+                id: labels.join(","),
+            });
+        }
+        Ok(nodes)
+    }
+
+    /// Get the config `kind` of the node `node`
+    async fn config(&self, node: &str, kind: &Config) -> Result<NodeConfig, SvcError> {
+        let configs = self.configs.lock().await;
+        match configs.get(node).and_then(|configs| configs.get(kind)) {
+            Some(config) => Ok(config.clone()),
+            None => Err(SvcError::NotFound {
+                kind: ResourceKind::Config,
+                id: format!("{}/{}", node, kind.to_string()),
+            }),
+        }
+    }
+
+    /// Update the config of the `sender` with a new revision
+    pub(super) async fn config_update(
+        &self,
+        sender: SenderId,
+        request: &ConfigUpdate,
+    ) -> Result<(), SvcError> {
+        let revision = self
+            .add_revision(&sender, &request.kind, request.data.clone(), None)
+            .await?;
+        tracing::info!(
+            "Config {} of node {} updated to revision {}",
+            request.kind.to_string(),
+            sender,
+            revision.revision
+        );
+        self.notify_background(&sender, &request.kind, revision);
+        Ok(())
+    }
+
+    /// Get the current config of the `sender`
+    pub(super) async fn config_get_current(
+        &self,
+        sender: SenderId,
+        request: &ConfigGetCurrent,
+    ) -> Result<ReplyConfig, SvcError> {
+        let config = self.config(&sender, &request.kind).await?;
+        match config.current() {
+            Some(revision) => Ok(ReplyConfig {
+                config: revision.data.clone(),
+            }),
+            None => Err(SvcError::NotFound {
+                kind: ResourceKind::Config,
+                id: format!("{}/{}", sender, request.kind.to_string()),
+            }),
+        }
+    }
+
+    /// Get the revisions of a config, oldest first
+    pub(super) async fn get_config_revisions(
+        &self,
+        _sender: SenderId,
+        request: &GetConfigRevisions,
+    ) -> Result<ConfigRevisions, SvcError> {
+        let config = self.config(request.node.as_str(), &request.kind).await?;
+        Ok(ConfigRevisions(config.revisions.into_iter().collect()))
+    }
+
+    /// Get a revision of a config
+    pub(super) async fn get_config_revision(
+        &self,
+        _sender: SenderId,
+        request: &GetConfigRevision,
+    ) -> Result<ConfigRevision, SvcError> {
+        let config = self.config(request.node.as_str(), &request.kind).await?;
+        match config.revision(request.revision) {
+            Some(revision) => Ok(revision.clone()),
+            None => Err(SvcError::NotFound {
+                kind: ResourceKind::Config,
+                id: format!(
+                    "{}/{}/{}",
+                    request.node,
+                    request.kind.to_string(),
+                    request.revision
+                ),
+            }),
+        }
+    }
+
+    /// Roll back a config to an earlier revision, whose data is restored as a
+    /// new revision. Rolling back to the current revision changes nothing.
+    pub(super) async fn rollback_config(
+        &self,
+        sender: SenderId,
+        request: &RollbackConfig,
+    ) -> Result<ConfigRevision, SvcError> {
+        let config = self.config(request.node.as_str(), &request.kind).await?;
+        let target = match config.revision(request.revision) {
+            Some(revision) => revision.clone(),
+            None => {
+                return Err(SvcError::NotFound {
+                    kind: ResourceKind::Config,
+                    id: format!(
+                        "{}/{}/{}",
+                        request.node,
+                        request.kind.to_string(),
+                        request.revision
+                    ),
+                })
+            }
+        };
+        if config.current() == Some(&target) {
+            return Ok(target);
+        }
+        let revision = self
+            .add_revision(
+                request.node.as_str(),
+                &request.kind,
+                target.data,
+                Some(target.revision),
+            )
+            .await?;
+        tracing::info!(
+            "Config {} of node {} rolled back to revision {} as revision {}, by {}",
+            request.kind.to_string(),
+            request.node,
+            target.revision,
+            revision.revision,
+            sender
+        );
+        self.notify_background(request.node.as_str(), &request.kind, revision.clone());
+        Ok(revision)
+    }
+
+    /// Set a config of all the nodes which have all of the labels, as a new
+    /// revision of the config of each node, and push it to the nodes.
+    /// At least one label is required so that all the nodes are not changed
+    /// by mistake, and at least one node must be selected. A node whose
+    /// revision could not be made is reported in the acks with its error.
+    pub(super) async fn set_nodes_config(
+        &self,
+        sender: SenderId,
+        request: &SetNodesConfig,
+    ) -> Result<NodeConfigAcks, SvcError> {
+        if request.labels.is_empty() {
+            return Err(SvcError::InvalidArguments {});
+        }
+        let nodes = self.select_nodes(&request.labels).await?;
+        let mut revisions = vec![];
+        let mut acks = vec![];
+        for node in nodes {
+            match self
+                .add_revision(&node, &request.kind, request.data.clone(), None)
+                .await
+            {
+                Ok(revision) => revisions.push((node, revision)),
+                // the node is reported as not acknowledging a revision which
+                // was never made
+                Err(error) => acks.push(NodeConfigAck {
+                    node: node.into(),
+                    revision: 0,
+                    acknowledged: false,
+                    error: Some(error.to_string()),
+                }),
+            }
+        }
+        acks.extend(
+            futures::future::join_all(
+                revisions
+                    .iter()
+                    .map(|(node, revision)| self.notify(node, &request.kind, revision)),
+            )
+            .await,
+        );
+        tracing::info!(
+            "Config {} of the nodes with labels {:?} set by {}, acknowledged by {}/{} nodes",
+            request.kind.to_string(),
+            request.labels,
+            sender,
+            acks.iter().filter(|ack| ack.acknowledged).count(),
+            acks.len()
+        );
+        Ok(NodeConfigAcks(acks))
+    }
+
+    /// Set the labels of a node, replacing its previous labels.
+    /// Each label must be a `key=value` pair.
+    pub(super) async fn set_node_labels(
+        &self,
+        sender: SenderId,
+        request: &SetNodeLabels,
+    ) -> Result<(), SvcError> {
+        let labels = request
+            .labels
+            .iter()
+            .map(|label| {
+                let mut parts = label.splitn(2, '=');
+                match (parts.next(), parts.next()) {
+                    (Some(key), Some(value)) if !key.is_empty() => {
+                        Ok((key.to_string(), value.to_string()))
+                    }
+                    _ => Err(SvcError::InvalidArguments {}),
+                }
+            })
+            .collect::<Result<NodeLabels, _>>()?;
+        let spec = NodeSpec::new(request.node.clone(), labels);
+        self.store.lock().await.put_obj(&spec).await?;
+        tracing::info!(
+            "Labels of node {} set to {:?} by {}",
+            request.node,
+            request.labels,
+            sender
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common_lib::{
+        mbus_api::{bus, ReceivedRawMessage},
+        types::v0::message_bus::NodeId,
+    };
+    use testlib::ClusterBuilder;
+
+    /// The store of the test cluster
+    const STORE: &str = "0.0.0.0:2379";
+
+    async fn config_svc() -> ConfigSvc {
+        ConfigSvc::new(STORE, 4, Duration::from_millis(100)).await
+    }
+
+    #[actix_rt::test]
+    async fn configs_restart() {
+        let _cluster = ClusterBuilder::builder()
+            .with_rest(false)
+            .with_agents(vec!["core"])
+            .build()
+            .await
+            .unwrap();
+
+        let node = "kiiss-node".to_string();
+        let service = config_svc().await;
+        for data in &["first", "second"] {
+            let update = ConfigUpdate {
+                kind: Config::MayastorConfig,
+                data: data.as_bytes().to_vec(),
+            };
+            service.config_update(node.clone(), &update).await.unwrap();
+        }
+
+        // the configs are loaded from the store when the service restarts
+        drop(service);
+        let service = config_svc().await;
+        let current = service
+            .config_get_current(
+                node.clone(),
+                &ConfigGetCurrent {
+                    kind: Config::MayastorConfig,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(current.config, b"second".to_vec());
+        let revisions = service
+            .get_config_revisions(
+                node.clone(),
+                &GetConfigRevisions {
+                    node: node.as_str().into(),
+                    kind: Config::MayastorConfig,
+                },
+            )
+            .await
+            .unwrap();
+        let revisions = revisions.0.iter().map(|r| r.revision).collect::<Vec<_>>();
+        assert_eq!(revisions, vec![1, 2]);
+
+        // the current config is served from the store, whoever wrote it there
+        let mut store = Etcd::new(STORE).await.unwrap();
+        let mut config = NodeConfig::new(&node, &Config::ChildStatesConfig);
+        config.push(b"stored".to_vec(), None, 4);
+        store.put_obj(&config).await.unwrap();
+        let request = ConfigGetCurrent {
+            kind: Config::ChildStatesConfig,
+        };
+        drop(service);
+        let service = config_svc().await;
+        let current = service.config_get_current(node, &request).await.unwrap();
+        assert_eq!(current.config, b"stored".to_vec());
+    }
+
+    async fn set_labels(service: &ConfigSvc, node: &str, labels: &[&str]) -> Result<(), SvcError> {
+        let request = SetNodeLabels {
+            node: node.into(),
+            labels: labels.iter().map(ToString::to_string).collect(),
+        };
+        service.set_node_labels("test".into(), &request).await
+    }
+
+    #[actix_rt::test]
+    async fn set_nodes_config() {
+        let _cluster = ClusterBuilder::builder()
+            .with_rest(false)
+            .with_agents(vec!["core"])
+            .build()
+            .await
+            .unwrap();
+        let service = config_svc().await;
+
+        set_labels(&service, "node-a", &["zone=eu-1"])
+            .await
+            .unwrap();
+        set_labels(&service, "node-b", &["zone=eu-1", "tier=ssd"])
+            .await
+            .unwrap();
+        set_labels(&service, "node-c", &["zone=us-1"])
+            .await
+            .unwrap();
+        set_labels(&service, "node-d", &["zone"])
+            .await
+            .expect_err("a label is a key=value pair");
+
+        // only node-a acknowledges the revisions which are pushed to it
+        let mut subscription = bus()
+            .subscribe(ConfigChanged::channel("node-a"))
+            .await
+            .unwrap();
+        let (sender, mut pushed) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            while let Some(message) = subscription.next().await {
+                let message = ReceivedRawMessage::from(&message);
+                let changed = message.inner::<ConfigChanged>().unwrap();
+                message.respond::<(), _>(()).await.unwrap();
+                sender.send(changed).await.unwrap();
+            }
+        });
+
+        let request = SetNodesConfig {
+            labels: vec!["zone=eu-1".to_string()],
+            kind: Config::MayastorConfig,
+            data: b"eu".to_vec(),
+        };
+        let mut acks = service
+            .set_nodes_config("test".into(), &request)
+            .await
+            .unwrap()
+            .0;
+        acks.sort_by(|a, b| a.node.as_str().cmp(b.node.as_str()));
+        assert_eq!(acks.len(), 2);
+        assert_eq!(
+            acks[0],
+            NodeConfigAck {
+                node: "node-a".into(),
+                revision: 1,
+                acknowledged: true,
+                error: None,
+            }
+        );
+        assert_eq!(acks[1].node, NodeId::from("node-b"));
+        assert_eq!(acks[1].revision, 1);
+        assert!(!acks[1].acknowledged);
+        assert!(acks[1].error.is_some());
+
+        let changed = pushed.recv().await.unwrap();
+        assert_eq!(changed.kind, Config::MayastorConfig);
+        assert_eq!(changed.revision, 1);
+        assert_eq!(changed.data, b"eu".to_vec());
+
+        // the new revision is the current config of the selected nodes only
+        for (node, current) in &[("node-a", true), ("node-b", true), ("node-c", false)] {
+            let result = service
+                .config_get_current(
+                    node.to_string(),
+                    &ConfigGetCurrent {
+                        kind: Config::MayastorConfig,
+                    },
+                )
+                .await;
+            assert_eq!(result.is_ok(), *current, "{}", node);
+        }
+
+        // a selection without any node is an error, as are no labels at all
+        let request = SetNodesConfig {
+            labels: vec!["zone=eu-1".to_string(), "tier=hdd".to_string()],
+            ..request
+        };
+        service
+            .set_nodes_config("test".into(), &request)
+            .await
+            .expect_err("no node is selected");
+        let request = SetNodesConfig {
+            labels: vec![],
+            ..request
+        };
+        service
+            .set_nodes_config("test".into(), &request)
+            .await
+            .expect_err("the labels are required");
+    }
+}
@@ -15,6 +15,12 @@
 /// expose different versions of the client
 pub mod versions;
 
+/// the REST service, which serves the REST API by sending its requests to the
+/// control plane agents over the message bus
+/// It may also run within another process, eg: to test it along with the
+/// agents over a `LocalMessageBus` (see `service::app`)
+pub mod service;
+
 use actix_http::{
     client::{SendRequestError, TcpConnect, TcpConnectError, TcpConnection},
     encoding::Decoder,
@@ -248,13 +254,14 @@ impl ActixRestClient {
             .await
             .context(InvalidPayload { head: head() })?;
         if status.is_success() {
-            match serde_json::from_slice(&body) {
-                Ok(r) => Ok(r),
-                Err(_) => {
-                    let result = serde_json::from_slice(&body)
-                        .context(InvalidBody { head: head(), body })?;
-                    Ok(vec![result])
-                }
+            if let Ok(r) = serde_json::from_slice(&body) {
+                Ok(r)
+            } else if let Ok(page) = serde_json::from_slice::<Page<R>>(&body) {
+                Ok(page.entries)
+            } else {
+                let result =
+                    serde_json::from_slice(&body).context(InvalidBody { head: head(), body })?;
+                Ok(vec![result])
             }
         } else if body.is_empty() {
             Err(ClientError::Header { head: head() })
@@ -305,6 +312,12 @@ impl ActixRestClient {
     }
 }
 
+/// A page of the objects of a list request
+#[derive(Deserialize)]
+struct Page<R> {
+    entries: Vec<R>,
+}
+
 /// Result of a Rest Client Operation
 /// T is the Object parsed from the Json body
 pub type ClientResult<T> = Result<T, ClientError>;
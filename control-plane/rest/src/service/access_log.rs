@@ -0,0 +1,106 @@
+use super::{caller, metrics::route};
+use actix_service::{Service, Transform};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use serde::Serialize;
+use std::time::{Instant, SystemTime};
+
+/// Entry of the access log, logged as a single line of JSON
+#[derive(Serialize)]
+struct AccessLogEntry {
+    timestamp: String,
+    method: String,
+    path: String,
+    route: String,
+    status: u16,
+    latency_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    peer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_agent: Option<String>,
+    /// subject of the bearer token or of the client certificate, only when the
+    /// request was authenticated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subject: Option<String>,
+}
+
+/// Middleware which logs every request as a structured JSON line with the
+/// `access_log` target
+#[derive(Default)]
+pub(crate) struct AccessLog {}
+
+impl<S, B> Transform<S, ServiceRequest> for AccessLog
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = AccessLogMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AccessLogMiddleware { service }))
+    }
+}
+
+/// Service of the `AccessLog` middleware
+pub(crate) struct AccessLogMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for AccessLogMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let started = Instant::now();
+        let mut entry = AccessLogEntry {
+            timestamp: humantime::format_rfc3339_millis(SystemTime::now()).to_string(),
+            method: request.method().to_string(),
+            path: request.path().to_string(),
+            route: route(request.request()),
+            status: 0,
+            latency_ms: 0.0,
+            peer: request
+                .connection_info()
+                .realip_remote_addr()
+                .map(Into::into),
+            user_agent: request
+                .headers()
+                .get(http::header::USER_AGENT)
+                .and_then(|agent| agent.to_str().ok())
+                .map(Into::into),
+            subject: None,
+        };
+        let response = self.service.call(request);
+
+        Box::pin(async move {
+            let response = response.await;
+            let status = match &response {
+                Ok(response) => response.status(),
+                Err(error) => error.as_response_error().status_code(),
+            };
+            entry.status = status.as_u16();
+            entry.latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+            // the subject is only known once the caller has been authenticated
+            let caller = caller::current();
+            entry.subject = caller.roles.and(caller.subject);
+            match serde_json::to_string(&entry) {
+                Ok(line) => tracing::info!(target: "access_log", "{}", line),
+                Err(error) => tracing::error!("Failed to serialize the access log: {}", error),
+            }
+            response
+        })
+    }
+}
@@ -0,0 +1,398 @@
+use super::{
+    authorization::AuthPolicy, caller, file_watcher::watch_files, jwks::JsonWebKeySet,
+    tls::ClientIdentity,
+};
+use actix_web::HttpRequest;
+use jsonwebtoken::Validation;
+
+use http::HeaderValue;
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use snafu::{ResultExt, Snafu};
+use structopt::StructOpt;
+
+/// Authorization Errors
+#[derive(Debug, Snafu)]
+pub enum AuthError {
+    #[snafu(display("Internal error: {}", details))]
+    InternalError { details: String },
+    #[snafu(display("No Bearer Token was provided in the HTTP Header"))]
+    NoBearerToken {},
+    #[snafu(display("Invalid token, cannot be parsed into a string: {}", source.to_string()))]
+    InvalidTokenStr { source: http::header::ToStrError },
+    #[snafu(display("Unauthorized token({}) for uri({})", token, uri))]
+    Unauthorized { token: String, uri: String },
+    #[snafu(display(
+        "Verification process failed, {}. Please check your json web token.",
+        source
+    ))]
+    Verification { source: jsonwebtoken::errors::Error },
+    #[snafu(display("Invalid Bearer Token: {}", details))]
+    InvalidToken { details: String },
+    #[snafu(display(
+        "Subject '{}' is not allowed to {} uri({}): {}",
+        subject,
+        method,
+        uri,
+        reason
+    ))]
+    Denied {
+        subject: String,
+        method: String,
+        uri: String,
+        reason: String,
+    },
+}
+
+/// Options used to validate the claims of the bearer tokens and to authorize
+/// the requests
+#[derive(Debug, StructOpt)]
+pub(crate) struct AuthOptions {
+    /// Issuer (iss claim) which the bearer tokens must be issued by
+    #[structopt(long)]
+    jwt_issuer: Option<String>,
+
+    /// Audience (aud claim) which the bearer tokens must be issued for
+    #[structopt(long)]
+    jwt_audience: Option<String>,
+
+    /// Path to the authorization policy file (YAML) which maps the roles of
+    /// the bearer tokens to the methods and resources they may access
+    /// Default: viewers may read, operators may also create and delete
+    /// (except with jsongrpc) and admins may do anything
+    #[structopt(long)]
+    auth_policy: Option<String>,
+
+    /// Roles granted to the bearer tokens which have no roles claim, eg:
+    /// `admin` to keep authorizing them as before the authorization policy
+    /// Default: none, such tokens are denied every request
+    #[structopt(long)]
+    jwt_default_roles: Vec<String>,
+}
+
+/// Initialise the authenticator with the JWK Set in the file at 'jwk_path',
+/// which is reloaded whenever the file changes, and which also authenticates
+/// the callers by their verified TLS client certificates, if
+/// `client_certificates` is set.
+/// If jwk_path is 'None' and client certificates are not verified,
+/// authentication is disabled.
+pub(crate) fn init(
+    jwk_path: Option<String>,
+    client_certificates: bool,
+    options: AuthOptions,
+) -> Authenticator {
+    if jwk_path.is_none() && !client_certificates {
+        return Authenticator::default();
+    }
+    let policy = match &options.auth_policy {
+        Some(policy) => match AuthPolicy::from_file(policy) {
+            Ok(policy) => policy,
+            Err(e) => panic!("Failed to load the authorization policy: {}", e),
+        },
+        None => AuthPolicy::default(),
+    };
+    Authenticator {
+        enabled: true,
+        keys: jwk_path.map(|path| init_keys(PathBuf::from(path))),
+        client_certificates,
+        issuer: options.jwt_issuer,
+        audience: options.jwt_audience,
+        default_roles: options.jwt_default_roles,
+        policy,
+    }
+}
+
+/// Load the JWK Set from the file at `path` and reload it when it changes
+fn init_keys(path: PathBuf) -> Arc<RwLock<JsonWebKeySet>> {
+    let keys = match JsonWebKeySet::from_file(&path) {
+        Ok(keys) => keys,
+        Err(e) => panic!("Failed to load the JWK file: {}", e),
+    };
+    let keys = Arc::new(RwLock::new(keys));
+    let reloaded_keys = keys.clone();
+    let reload_path = path.clone();
+    watch_files(vec![path], move || {
+        match JsonWebKeySet::from_file(&reload_path) {
+            Ok(keys) => {
+                tracing::info!("Reloaded the JWK file {}", reload_path.display());
+                *reloaded_keys.write().expect("not poisoned") = keys;
+            }
+            Err(error) => tracing::error!(
+                "Failed to reload the JWK file {}, keeping the current keys: {}",
+                reload_path.display(),
+                error
+            ),
+        }
+    });
+    keys
+}
+
+/// Claims of the bearer token which are used for authorization
+#[derive(serde::Deserialize, Debug)]
+pub(crate) struct Claims {
+    /// subject of the token, ie: the caller
+    #[serde(default)]
+    sub: Option<String>,
+    /// roles granted to the caller
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+/// Authenticates and authorizes the REST requests with their bearer tokens
+/// or their TLS client certificates
+/// The default authenticator has authentication disabled
+#[derive(Clone, Default, Debug)]
+pub struct Authenticator {
+    enabled: bool,
+    /// keys used to verify the tokens, if bearer tokens are accepted
+    keys: Option<Arc<RwLock<JsonWebKeySet>>>,
+    /// whether callers may authenticate with their client certificate
+    client_certificates: bool,
+    issuer: Option<String>,
+    audience: Option<String>,
+    /// roles of the tokens which have no roles claim
+    default_roles: Vec<String>,
+    policy: AuthPolicy,
+}
+
+impl Authenticator {
+    /// Validate a bearer token: its signature, with the key selected by its
+    /// kid, its expiry and, if configured, its issuer and audience, returning
+    /// its claims
+    pub(crate) fn validate(&self, token: &str, uri: &str) -> Result<Claims, AuthError> {
+        split_token(token)?;
+        let keys = match &self.keys {
+            Some(keys) => keys.read().expect("not poisoned"),
+            None => {
+                return Err(AuthError::InvalidToken {
+                    details: "Bearer tokens are not accepted, a client certificate is required"
+                        .to_string(),
+                })
+            }
+        };
+        let header = jsonwebtoken::decode_header(token).context(Verification)?;
+        let key = keys
+            .key(header.kid.as_deref())
+            .map_err(|details| AuthError::InvalidToken { details })?;
+        let decoding_key = key
+            .decoding_key()
+            .map_err(|details| AuthError::InternalError { details })?;
+
+        let mut validation = Validation::new(key.algorithm());
+        validation.iss = self.issuer.clone();
+        if let Some(audience) = &self.audience {
+            validation.set_audience(&[audience]);
+        }
+        match jsonwebtoken::decode::<Claims>(token, &decoding_key, &validation) {
+            Ok(data) if data.claims.roles.is_empty() => Ok(Claims {
+                roles: self.default_roles.clone(),
+                ..data.claims
+            }),
+            Ok(data) => Ok(data.claims),
+            Err(source) => match source.kind() {
+                jsonwebtoken::errors::ErrorKind::InvalidSignature => Err(AuthError::Unauthorized {
+                    token: token.to_string(),
+                    uri: uri.to_string(),
+                }),
+                _ => Err(AuthError::Verification { source }),
+            },
+        }
+    }
+
+    /// Authorize the request with the `method` on the `uri` made by the
+    /// caller with the given `claims`
+    pub(crate) fn authorize(
+        &self,
+        claims: &Claims,
+        method: &str,
+        uri: &str,
+    ) -> Result<(), AuthError> {
+        self.policy
+            .authorize(&claims.roles, method, uri)
+            .map_err(|reason| AuthError::Denied {
+                subject: claims.sub.clone().unwrap_or_default(),
+                method: method.to_string(),
+                uri: uri.to_string(),
+                reason,
+            })
+    }
+
+    /// Claims of a caller authenticated by its client certificate, with the
+    /// roles of its certificate subject
+    fn certificate_claims(&self, identity: &ClientIdentity) -> Claims {
+        Claims {
+            sub: Some(identity.subject().to_string()),
+            roles: self.policy.subject_roles(identity.subject()),
+        }
+    }
+
+    // Returns true if REST calls should be authenticated.
+    fn auth_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Authenticate the HTTP request by checking the authorisation token to ensure
+/// the sender is who they claim to be, and authorize the request according to
+/// the roles of the sender.
+pub fn authenticate(req: &HttpRequest) -> Result<(), AuthError> {
+    let jwk: &Authenticator = match req.app_data() {
+        Some(jwk) => Ok(jwk),
+        None => Err(AuthError::InternalError {
+            details: "Json Web Token not configured in the REST server".to_string(),
+        }),
+    }?;
+
+    // If authentication is disabled there is nothing to do.
+    if !jwk.auth_enabled() {
+        return Ok(());
+    }
+
+    let uri = req.uri().to_string();
+    let identity = req.conn_data::<ClientIdentity>();
+    let claims = match (req.headers().get(http::header::AUTHORIZATION), identity) {
+        (Some(token), _) => jwk.validate(&format_token(token)?, &uri),
+        (None, Some(identity)) if jwk.client_certificates => Ok(jwk.certificate_claims(identity)),
+        (None, _) => Err(AuthError::NoBearerToken {}),
+    }?;
+    // authorize the path as it was routed, ie: percent-decoded, so that an
+    // encoded path cannot be routed to a resource which the policy denies
+    jwk.authorize(&claims, req.method().as_str(), req.match_info().as_str())?;
+    caller::authenticated(claims.sub, claims.roles);
+    Ok(())
+}
+
+// Ensure the token is formatted correctly by removing the "Bearer " prefix if
+// present.
+fn format_token(token: &HeaderValue) -> Result<String, AuthError> {
+    let token = token
+        .to_str()
+        .context(InvalidTokenStr)?
+        .trim_start_matches("Bearer ");
+    Ok(token.trim().into())
+}
+
+// Split the JSON Web Token (JWT) into 2 parts, message and signature.
+// The message comprises the header and payload.
+//
+// JWT format:
+//      <header>.<payload>.<signature>
+//      \______  ________/
+//             \/
+//           message
+fn split_token(token: &str) -> Result<(String, String), AuthError> {
+    let elems = token.split('.').collect::<Vec<&str>>();
+    if elems.len() == 3 {
+        let message = format!("{}.{}", elems[0], elems[1]);
+        let signature = elems[2];
+        Ok((message, signature.into()))
+    } else {
+        Err(AuthError::InvalidToken {
+            details: "Should be formatted as: header.payload.signature".to_string(),
+        })
+    }
+}
+
+#[test]
+fn validate_test() {
+    let token_file = std::env::current_dir()
+        .expect("Failed to get current directory")
+        .join("authentication")
+        .join("token");
+    let mut token = std::fs::read_to_string(token_file).expect("Failed to get bearer token");
+    let jwk_file = std::env::current_dir()
+        .expect("Failed to get current directory")
+        .join("authentication")
+        .join("jwk");
+    let jwk = init(
+        Some(jwk_file.to_str().unwrap().into()),
+        false,
+        AuthOptions::from_iter(vec!["test"]),
+    );
+
+    let claims = jwk.validate(&token, "uri").expect("Validation should pass");
+    assert_eq!(claims.roles, vec!["admin".to_string()]);
+    jwk.authorize(&claims, "DELETE", "/v0/pools/pool-1")
+        .expect("Admins should be authorized");
+
+    // the token is not issued by the expected issuer
+    let other_issuer = init(
+        Some(jwk_file.to_str().unwrap().into()),
+        false,
+        AuthOptions::from_iter(vec!["test", "--jwt-issuer", "other-issuer"]),
+    );
+    other_issuer
+        .validate(&token, "uri")
+        .expect_err("Validation should fail with a different issuer");
+
+    // create invalid token
+    token.push_str("invalid");
+    jwk.validate(&token, "uri")
+        .expect_err("Validation should fail with an invalid token");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+
+    /// Bearer token signed with the key of the test JWK file, with the `roles`
+    fn token(roles: &[&str]) -> String {
+        let key = std::fs::read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/authentication/id_rsa"
+        ))
+        .unwrap();
+        let claims = serde_json::json!({
+            "sub": "tester",
+            "exp": 10000000000u64,
+            "roles": roles,
+        });
+        jsonwebtoken::encode(
+            &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_rsa_pem(&key).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn encoded_path_test() {
+        let authenticator = init(
+            Some(concat!(env!("CARGO_MANIFEST_DIR"), "/authentication/jwk").to_string()),
+            false,
+            AuthOptions::from_iter(&["rest"]),
+        );
+        let app = test::init_service(App::new().app_data(authenticator).route(
+            "/v0/nodes/{node}/jsongrpc/{method}",
+            web::put().to(|req: HttpRequest| async move {
+                match authenticate(&req) {
+                    Ok(_) => HttpResponse::Ok().finish(),
+                    Err(error) => HttpResponse::Forbidden().body(error.to_string()),
+                }
+            }),
+        ))
+        .await;
+        let request = |uri: &str, token: String| {
+            test::TestRequest::put()
+                .uri(uri)
+                .insert_header((http::header::AUTHORIZATION, format!("Bearer {}", token)))
+                .to_request()
+        };
+
+        // operators may not use the jsongrpc passthrough, however it's spelled
+        for uri in &["/v0/nodes/n/jsongrpc/m", "/v0/nodes/n/%6Asongrpc/m"] {
+            let response = test::call_service(&app, request(uri, token(&["operator"]))).await;
+            assert_eq!(response.status(), http::StatusCode::FORBIDDEN, "{}", uri);
+            let response = test::call_service(&app, request(uri, token(&["admin"]))).await;
+            assert_eq!(response.status(), http::StatusCode::OK, "{}", uri);
+        }
+        // tokens without roles are denied unless default roles are configured
+        let response =
+            test::call_service(&app, request("/v0/nodes/n/jsongrpc/m", token(&[]))).await;
+        assert_eq!(response.status(), http::StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,177 @@
+use serde::Deserialize;
+use std::{collections::HashMap, fs::File};
+
+/// Authorization policy which maps the roles of the callers to the requests
+/// they may make, eg:
+/// ```yaml
+/// roles:
+///   viewer:
+///     - methods: [GET]
+///       resources: ["/v0/**"]
+///   operator:
+///     - methods: [GET, PUT, DELETE]
+///       resources: ["/v0/**"]
+///       except: ["/v0/nodes/*/jsongrpc/**"]
+/// subjects:
+///   "CN=automation, O=example": [operator]
+/// ```
+/// A request is allowed if any rule of any of the caller's roles allows it.
+/// The roles of the callers authenticated by their client certificate are
+/// given by the subject of the certificate, with its relative distinguished
+/// names in the order of the certificate, separated by `, `, and with their
+/// values escaped as in RFC 4514, eg: `CN=automation\, inc, O=example`.
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct AuthPolicy {
+    roles: HashMap<String, Vec<PolicyRule>>,
+    #[serde(default)]
+    subjects: HashMap<String, Vec<String>>,
+}
+
+/// Rule which allows the given HTTP methods on the given resources
+#[derive(Deserialize, Debug, Clone)]
+struct PolicyRule {
+    /// HTTP methods, where `*` matches any method
+    methods: Vec<String>,
+    /// resource path patterns, where `*` matches a single path segment and
+    /// `**` matches any number of segments
+    resources: Vec<String>,
+    /// resource path patterns which are excluded from this rule
+    #[serde(default)]
+    except: Vec<String>,
+}
+
+impl Default for AuthPolicy {
+    /// Viewers may only read, operators may also create and delete resources
+    /// except for the raw jsongrpc passthrough, and admins may do anything.
+    /// No client certificate subject is granted any role.
+    fn default() -> Self {
+        let rule = |methods: &[&str], resources: &[&str], except: &[&str]| PolicyRule {
+            methods: methods.iter().map(|m| m.to_string()).collect(),
+            resources: resources.iter().map(|r| r.to_string()).collect(),
+            except: except.iter().map(|e| e.to_string()).collect(),
+        };
+        let mut roles = HashMap::new();
+        roles.insert("viewer".to_string(), vec![rule(&["GET"], &["/v0/**"], &[])]);
+        roles.insert(
+            "operator".to_string(),
+            vec![rule(
+                &["GET", "PUT", "DELETE"],
+                &["/v0/**"],
+                &["/v0/nodes/*/jsongrpc/**"],
+            )],
+        );
+        roles.insert("admin".to_string(), vec![rule(&["*"], &["/**"], &[])]);
+        Self {
+            roles,
+            subjects: HashMap::new(),
+        }
+    }
+}
+
+impl AuthPolicy {
+    /// Load the policy from the YAML (or JSON) file at `path`
+    pub(crate) fn from_file(path: &str) -> anyhow::Result<Self> {
+        let file = File::open(path)?;
+        Ok(serde_yaml::from_reader(file)?)
+    }
+
+    /// Roles of the caller with the client certificate `subject`
+    pub(crate) fn subject_roles(&self, subject: &str) -> Vec<String> {
+        self.subjects.get(subject).cloned().unwrap_or_default()
+    }
+
+    /// Authorize the request with the `method` on the `path` made by a caller
+    /// with the given `roles`, returning the reason of the denial, if denied
+    pub(crate) fn authorize(
+        &self,
+        roles: &[String],
+        method: &str,
+        path: &str,
+    ) -> Result<(), String> {
+        if roles.is_empty() {
+            return Err("the caller has no roles".to_string());
+        }
+        let allowed = roles
+            .iter()
+            .filter_map(|role| self.roles.get(role))
+            .flatten()
+            .any(|rule| rule.allows(method, path));
+        if allowed {
+            Ok(())
+        } else {
+            Err(format!(
+                "none of the roles {:?} may {} this resource",
+                roles, method
+            ))
+        }
+    }
+}
+
+impl PolicyRule {
+    fn allows(&self, method: &str, path: &str) -> bool {
+        let method_matches = self
+            .methods
+            .iter()
+            .any(|m| m == "*" || m.eq_ignore_ascii_case(method));
+        method_matches
+            && self.resources.iter().any(|r| path_matches(r, path))
+            && !self.except.iter().any(|e| path_matches(e, path))
+    }
+}
+
+/// Whether the `path` matches the `pattern`, where `*` matches a single path
+/// segment and `**` matches any number of segments
+fn path_matches(pattern: &str, path: &str) -> bool {
+    fn segments(value: &str) -> Vec<&str> {
+        value.split('/').filter(|s| !s.is_empty()).collect()
+    }
+    fn matches(pattern: &[&str], path: &[&str]) -> bool {
+        match (pattern.first(), path.first()) {
+            (None, None) => true,
+            (Some(&"**"), _) => {
+                matches(&pattern[1 ..], path) || (!path.is_empty() && matches(pattern, &path[1 ..]))
+            }
+            (Some(p), Some(s)) if *p == "*" || p == s => matches(&pattern[1 ..], &path[1 ..]),
+            _ => false,
+        }
+    }
+    matches(&segments(pattern), &segments(path))
+}
+
+#[test]
+fn default_policy_test() {
+    let policy = AuthPolicy::default();
+    let roles = |roles: &[&str]| roles.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+
+    assert!(policy.authorize(&roles(&[]), "GET", "/v0/nodes").is_err());
+    assert!(policy
+        .authorize(&roles(&["other"]), "GET", "/v0/nodes")
+        .is_err());
+
+    assert!(policy
+        .authorize(&roles(&["viewer"]), "GET", "/v0/nodes")
+        .is_ok());
+    // the states are served apart from the generated handlers
+    assert!(policy.authorize(&roles(&[]), "GET", "/v0/states").is_err());
+    assert!(policy
+        .authorize(&roles(&["viewer"]), "GET", "/v0/states")
+        .is_ok());
+    assert!(policy
+        .authorize(&roles(&["viewer"]), "DELETE", "/v0/pools/p1")
+        .is_err());
+
+    assert!(policy
+        .authorize(&roles(&["operator"]), "DELETE", "/v0/pools/p1")
+        .is_ok());
+    assert!(policy
+        .authorize(&roles(&["operator"]), "PUT", "/v0/nodes/n1/jsongrpc/list")
+        .is_err());
+
+    assert!(policy
+        .authorize(
+            &roles(&["viewer", "admin"]),
+            "PUT",
+            "/v0/nodes/n1/jsongrpc/list"
+        )
+        .is_ok());
+}
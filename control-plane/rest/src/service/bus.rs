@@ -0,0 +1,65 @@
+use actix_service::{Service, Transform};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use common_lib::mbus_api::{self, DynBus};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use std::rc::Rc;
+
+/// Middleware which sends the requests of the handlers over the given message
+/// bus rather than the shared one (see `mbus_api::with_bus`)
+pub(crate) struct BusScope {
+    bus: DynBus,
+}
+
+impl BusScope {
+    /// New middleware which scopes every request to the `bus`
+    pub(crate) fn new(bus: DynBus) -> Self {
+        Self { bus }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for BusScope
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = BusScopeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(BusScopeMiddleware {
+            service: Rc::new(service),
+            bus: self.bus.clone(),
+        }))
+    }
+}
+
+/// Service of the `BusScope` middleware
+pub(crate) struct BusScopeMiddleware<S> {
+    service: Rc<S>,
+    bus: DynBus,
+}
+
+impl<S, B> Service<ServiceRequest> for BusScopeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        // as with the `CallerScope`, the call must be made within the scope
+        let service = self.service.clone();
+        Box::pin(mbus_api::with_bus(self.bus.clone(), async move {
+            service.call(request).await
+        }))
+    }
+}
@@ -0,0 +1,90 @@
+use actix_service::{Service, Transform};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use std::{cell::RefCell, rc::Rc};
+
+/// Caller of the request which is being handled, for the handlers which pass
+/// it on to the agents, since they do not have access to the HTTP request
+#[derive(Default, Debug, Clone)]
+pub(crate) struct Caller {
+    /// subject of the verified bearer token or client certificate, if any
+    pub(crate) subject: Option<String>,
+    /// roles of the caller, only if it was authenticated
+    pub(crate) roles: Option<Vec<String>>,
+}
+
+tokio::task_local! {
+    static CALLER: RefCell<Caller>;
+}
+
+/// Caller of the request which is being handled
+pub(crate) fn current() -> Caller {
+    CALLER
+        .try_with(|caller| caller.borrow().clone())
+        .unwrap_or_default()
+}
+
+/// Record the roles of the caller of the request which is being handled, once
+/// it has been authenticated
+pub(crate) fn authenticated(subject: Option<String>, roles: Vec<String>) {
+    let _ = CALLER.try_with(|caller| {
+        *caller.borrow_mut() = Caller {
+            subject,
+            roles: Some(roles),
+        }
+    });
+}
+
+/// Middleware which makes the caller of each request available to its handler
+/// (see `current`)
+#[derive(Default)]
+pub(crate) struct CallerScope {}
+
+impl<S, B> Transform<S, ServiceRequest> for CallerScope
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = CallerScopeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CallerScopeMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+/// Service of the `CallerScope` middleware
+pub(crate) struct CallerScopeMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for CallerScopeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        // the caller is unknown until it's authenticated (see `authenticated`)
+        let caller = Caller::default();
+        // the request is extracted when the service is called, so the call
+        // must be made within the scope as well
+        let service = self.service.clone();
+        Box::pin(CALLER.scope(
+            RefCell::new(caller),
+            async move { service.call(request).await },
+        ))
+    }
+}
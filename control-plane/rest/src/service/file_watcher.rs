@@ -0,0 +1,32 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+/// Period at which the watched files are checked for changes
+const WATCH_PERIOD: Duration = Duration::from_secs(5);
+
+/// Watch the files at `paths` for changes, calling `on_change` from a
+/// background thread whenever any of them is modified or replaced.
+pub(crate) fn watch_files<F>(paths: Vec<PathBuf>, on_change: F)
+where
+    F: Fn() + Send + 'static,
+{
+    let modified = move || {
+        paths
+            .iter()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect::<Vec<Option<SystemTime>>>()
+    };
+    std::thread::spawn(move || {
+        let mut current = modified();
+        loop {
+            std::thread::sleep(WATCH_PERIOD);
+            let latest = modified();
+            if latest != current {
+                current = latest;
+                on_change();
+            }
+        }
+    });
+}
@@ -0,0 +1,143 @@
+use jsonwebtoken::{Algorithm, DecodingKey};
+use serde::Deserialize;
+use std::{fs::File, path::Path};
+
+/// JSON Web Key Set (RFC 7517) used to verify the bearer tokens.
+/// The key which verifies a token is selected by the token's `kid`, which
+/// allows the identity provider to rotate its signing keys.
+#[derive(Debug, Default)]
+pub(crate) struct JsonWebKeySet {
+    keys: Vec<JsonWebKey>,
+}
+
+/// Either a full JWK Set or a single JWK, as a set of one key
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonWebKeyFile {
+    Set { keys: Vec<JsonWebKey> },
+    Key(JsonWebKey),
+}
+
+/// Public JSON Web Key used to verify the token signatures
+#[derive(Deserialize, Debug)]
+pub(crate) struct JsonWebKey {
+    /// key identifier, matched against the `kid` of the token header
+    #[serde(default)]
+    kid: Option<String>,
+    #[serde(alias = "alg")]
+    algorithm: Algorithm,
+    #[serde(flatten)]
+    material: KeyMaterial,
+}
+
+/// Key type specific parameters of the public key
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kty")]
+enum KeyMaterial {
+    #[serde(rename = "RSA")]
+    Rsa {
+        #[serde(alias = "n")]
+        modulus: String,
+        #[serde(alias = "e")]
+        exponent: String,
+    },
+    #[serde(rename = "EC")]
+    Ec {
+        #[serde(alias = "crv")]
+        curve: String,
+        /// base64url encoded x coordinate of the point
+        x: String,
+        /// base64url encoded y coordinate of the point
+        y: String,
+    },
+}
+
+impl JsonWebKeySet {
+    /// Load the JWK Set, or a single JWK, from the file at `path`
+    pub(crate) fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let keys = match serde_json::from_reader(File::open(path)?)? {
+            JsonWebKeyFile::Set { keys } => keys,
+            JsonWebKeyFile::Key(key) => vec![key],
+        };
+        if keys.is_empty() {
+            anyhow::bail!("No keys found in the JWK file");
+        }
+        Ok(Self { keys })
+    }
+
+    /// Key which verifies the tokens with the `kid` key identifier.
+    /// Tokens without a `kid` can only be verified if there's a single key.
+    pub(crate) fn key(&self, kid: Option<&str>) -> Result<&JsonWebKey, String> {
+        match kid {
+            Some(kid) => self
+                .keys
+                .iter()
+                .find(|key| key.kid.as_deref() == Some(kid))
+                .ok_or_else(|| format!("no key found with the kid '{}'", kid)),
+            None if self.keys.len() == 1 => Ok(&self.keys[0]),
+            None => Err("the token has no kid to select a key with".to_string()),
+        }
+    }
+}
+
+impl JsonWebKey {
+    /// Signature algorithm of the key
+    pub(crate) fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Key used to verify the signature of the tokens
+    pub(crate) fn decoding_key(&self) -> Result<DecodingKey<'static>, String> {
+        match &self.material {
+            KeyMaterial::Rsa { modulus, exponent } => {
+                Ok(DecodingKey::from_rsa_components(modulus, exponent).into_static())
+            }
+            KeyMaterial::Ec { curve, x, y } => {
+                let coordinate_len = match curve.as_str() {
+                    "P-256" => 32,
+                    "P-384" => 48,
+                    _ => return Err(format!("unsupported EC curve '{}'", curve)),
+                };
+                let decode = |coordinate: &str| {
+                    base64::decode_config(coordinate, base64::URL_SAFE_NO_PAD)
+                        .ok()
+                        .filter(|bytes| bytes.len() == coordinate_len)
+                        .ok_or_else(|| format!("invalid EC coordinate '{}'", coordinate))
+                };
+                // the public key is the uncompressed point: 0x04 | x | y
+                let mut point = vec![0x04];
+                point.extend(decode(x)?);
+                point.extend(decode(y)?);
+                Ok(DecodingKey::from_ec_der(&point).into_static())
+            }
+        }
+    }
+}
+
+#[test]
+fn key_set_test() {
+    let set: JsonWebKeyFile = serde_json::from_str(
+        r#"{"keys": [
+            {"kty": "RSA", "kid": "rsa-1", "alg": "RS256", "n": "AQAB", "e": "AQAB"},
+            {"kty": "EC", "kid": "ec-1", "alg": "ES256", "crv": "P-256",
+             "x": "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU",
+             "y": "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0"}
+        ]}"#,
+    )
+    .unwrap();
+    let set = match set {
+        JsonWebKeyFile::Set { keys } => JsonWebKeySet { keys },
+        JsonWebKeyFile::Key(_) => panic!("Should be a key set"),
+    };
+
+    let key = set.key(Some("ec-1")).unwrap();
+    assert_eq!(key.algorithm(), Algorithm::ES256);
+    key.decoding_key().unwrap();
+    assert_eq!(
+        set.key(Some("rsa-1")).unwrap().algorithm(),
+        Algorithm::RS256
+    );
+    assert!(set.key(Some("other")).is_err());
+    // with several keys the kid is required
+    assert!(set.key(None).is_err());
+}
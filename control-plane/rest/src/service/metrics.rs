@@ -0,0 +1,113 @@
+use actix_service::{Service, Transform};
+use actix_web::{
+    dev::{ServiceRequest, ServiceResponse},
+    http::StatusCode,
+    HttpResponse,
+};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, Encoder, HistogramVec, IntCounterVec,
+    TextEncoder,
+};
+use std::time::Instant;
+
+lazy_static! {
+    static ref HTTP_REQUESTS: IntCounterVec = register_int_counter_vec!(
+        "http_requests_total",
+        "Number of REST requests, per method, route and status code",
+        &["method", "route", "status"]
+    )
+    .expect("metric can be registered");
+    static ref HTTP_REQUEST_DURATION: HistogramVec = register_histogram_vec!(
+        "http_request_duration_seconds",
+        "Time taken to serve a REST request, per method and route",
+        &["method", "route"]
+    )
+    .expect("metric can be registered");
+}
+
+/// Route of the request as its matched pattern, eg: `/v0/volumes/{volume_id}`,
+/// so that requests to different resources are accounted together
+pub(crate) fn route(request: &actix_web::HttpRequest) -> String {
+    request
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_string())
+}
+
+/// Handler which serves the prometheus metrics in the text format
+pub(crate) async fn handler() -> HttpResponse {
+    let mut buffer = vec![];
+    match TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        Ok(_) => HttpResponse::Ok()
+            .content_type(TextEncoder::new().format_type())
+            .body(buffer),
+        Err(error) => HttpResponse::InternalServerError().body(error.to_string()),
+    }
+}
+
+/// Middleware which records the count, latency and status code of every
+/// request, per route
+#[derive(Default)]
+pub(crate) struct RequestMetrics {}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = RequestMetricsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsMiddleware { service }))
+    }
+}
+
+/// Service of the `RequestMetrics` middleware
+pub(crate) struct RequestMetricsMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let started = Instant::now();
+        let method = request.method().to_string();
+        let route = route(request.request());
+        let response = self.service.call(request);
+
+        Box::pin(async move {
+            let response = response.await;
+            let status = match &response {
+                Ok(response) => response.status(),
+                Err(error) => error.as_response_error().status_code(),
+            };
+            observe(&method, &route, status, started);
+            response
+        })
+    }
+}
+
+fn observe(method: &str, route: &str, status: StatusCode, started: Instant) {
+    HTTP_REQUESTS
+        .with_label_values(&[method, route, status.as_str()])
+        .inc();
+    HTTP_REQUEST_DURATION
+        .with_label_values(&[method, route])
+        .observe(started.elapsed().as_secs_f64());
+}
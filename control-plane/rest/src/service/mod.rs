@@ -0,0 +1,318 @@
+mod access_log;
+mod authentication;
+mod authorization;
+mod bus;
+mod caller;
+mod file_watcher;
+mod jwks;
+mod metrics;
+mod tls;
+mod v0;
+
+pub use authentication::Authenticator;
+
+use actix_service::ServiceFactory;
+use actix_web::{
+    body::Body,
+    dev::{MessageBody, ServiceRequest, ServiceResponse},
+    middleware, web, App, HttpServer,
+};
+
+use rustls::{
+    internal::pemfile::{certs, rsa_private_keys},
+    sign::{self, CertifiedKey},
+    ServerConfig,
+};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+struct CliArgs {
+    /// The bind address for the REST interface (with HTTPS)
+    /// Default: 0.0.0.0:8080
+    #[structopt(long, default_value = "0.0.0.0:8080")]
+    https: String,
+    /// The bind address for the REST interface (with HTTP)
+    #[structopt(long)]
+    http: Option<String>,
+    /// The Nats Server URL or address to connect to
+    /// Default: nats://0.0.0.0:4222
+    #[structopt(long, short, default_value = "nats://0.0.0.0:4222")]
+    nats: String,
+    /// The Nats Server connection options (TLS and credentials)
+    #[structopt(flatten)]
+    nats_options: BusOptions,
+
+    /// Path to the certificate file, which is reloaded when it changes
+    #[structopt(long, short, required_unless = "dummy-certificates")]
+    cert_file: Option<String>,
+    /// Path to the key file, which is reloaded when it changes
+    #[structopt(long, short, required_unless = "dummy-certificates")]
+    key_file: Option<String>,
+
+    /// Use dummy HTTPS certificates (for testing)
+    #[structopt(long, short, required_unless = "cert-file")]
+    dummy_certificates: bool,
+
+    /// The bind address for the prometheus metrics endpoint (HTTP), which is
+    /// served apart from the REST interface since it's not authenticated
+    /// If not provided, the metrics are not served
+    #[structopt(long)]
+    metrics: Option<String>,
+
+    /// Trace rest requests to the Jaeger endpoint agent
+    #[structopt(long, short)]
+    jaeger: Option<String>,
+
+    /// Path to the JSON Web Key (Set) file used for authenticating REST
+    /// requests, which is reloaded when it changes
+    #[structopt(long, required_unless_one = &["no-auth", "client-ca"])]
+    jwk: Option<String>,
+
+    /// Don't authenticate REST requests
+    #[structopt(long, required_unless_one = &["jwk", "client-ca"])]
+    no_auth: bool,
+
+    /// Path to the CA certificate(s) used to verify the TLS client
+    /// certificates: callers which present a certificate signed by this CA are
+    /// authenticated by the subject of their certificate
+    #[structopt(long, conflicts_with = "no-auth")]
+    client_ca: Option<String>,
+
+    /// Require every client to present a certificate signed by the client CA
+    /// (otherwise callers may also authenticate with bearer tokens)
+    /// Not allowed with `--http`, whose callers can't present a certificate
+    #[structopt(long, requires = "client-ca", conflicts_with = "http")]
+    require_client_cert: bool,
+
+    /// Options used to validate the bearer tokens and to authorize requests
+    #[structopt(flatten)]
+    auth_options: authentication::AuthOptions,
+}
+
+/// default timeout options for every bus request
+fn bus_timeout_opts() -> TimeoutOptions {
+    TimeoutOptions::default()
+        .with_max_retries(0)
+        .with_timeout(Duration::from_secs(6))
+}
+
+use actix_web_opentelemetry::RequestTracing;
+use common_lib::{
+    mbus_api,
+    mbus_api::{BusOptions, DynBus, TimeoutOptions},
+};
+use opentelemetry::{
+    global,
+    sdk::{propagation::TraceContextPropagator, trace::Tracer},
+};
+use std::time::Duration;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+fn init_tracing() -> Option<Tracer> {
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    let subscriber = tracing_subscriber::Registry::default()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer());
+
+    if let Some(agent) = CliArgs::from_args().jaeger {
+        // Start a new jaeger trace pipeline, the trace context is propagated
+        // to the control plane agents through the message bus
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = opentelemetry_jaeger::new_pipeline()
+            .with_agent_endpoint(agent.clone())
+            .with_service_name("rest-server")
+            .install_simple()
+            .expect("Jaeger pipeline install error");
+        // the message bus requests are traced as children of the rest request
+        let telemetry = tracing_opentelemetry::layer().with_tracer(tracer.clone());
+        subscriber.with(telemetry).init();
+        tracing::info!("Started jaeger trace pipeline at {}", agent);
+        Some(tracer)
+    } else {
+        subscriber.init();
+        None
+    }
+}
+
+/// Extension trait for actix-web applications.
+trait OpenApiExt<T, B> {
+    /// configures the App with this version's handlers and openapi generation
+    fn configure_api(
+        self,
+        config: &dyn Fn(actix_web::App<T, B>) -> actix_web::App<T, B>,
+    ) -> actix_web::App<T, B>;
+}
+
+impl<T, B> OpenApiExt<T, B> for actix_web::App<T, B>
+where
+    B: MessageBody,
+    T: ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<B>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+{
+    fn configure_api(
+        self,
+        config: &dyn Fn(actix_web::App<T, B>) -> actix_web::App<T, B>,
+    ) -> actix_web::App<T, B> {
+        config(self)
+    }
+}
+
+fn get_certificates() -> anyhow::Result<ServerConfig> {
+    let args = CliArgs::from_args();
+    let verifier = tls::client_verifier(args.client_ca.as_deref(), args.require_client_cert)?;
+    let mut config = ServerConfig::new(verifier);
+    config.cert_resolver = if args.dummy_certificates {
+        Arc::new(tls::CertificateResolver::new(get_dummy_certificates()?)?)
+    } else {
+        // guaranteed to be `Some` by the require_unless attribute
+        let cert_file = PathBuf::from(args.cert_file.expect("cert_file is required"));
+        let key_file = PathBuf::from(args.key_file.expect("key_file is required"));
+        let certificate = load_certificate_files(&cert_file, &key_file)?;
+        let resolver = Arc::new(tls::CertificateResolver::new(certificate)?);
+        // new connections are served with the reloaded certificate while the
+        // established connections are not affected
+        let reloaded = resolver.clone();
+        file_watcher::watch_files(vec![cert_file.clone(), key_file.clone()], move || {
+            match load_certificate_files(&cert_file, &key_file)
+                .and_then(|certificate| reloaded.set(certificate))
+            {
+                Ok(()) => tracing::info!("Reloaded the certificate {}", cert_file.display()),
+                Err(error) => tracing::error!(
+                    "Failed to reload the certificate {}, keeping the current one: {}",
+                    cert_file.display(),
+                    error
+                ),
+            }
+        });
+        resolver
+    };
+    Ok(config)
+}
+
+fn get_dummy_certificates() -> anyhow::Result<CertifiedKey> {
+    let cert_file = &mut BufReader::new(&std::include_bytes!("../../certs/rsa/user.chain")[..]);
+    let key_file = &mut BufReader::new(&std::include_bytes!("../../certs/rsa/user.rsa")[..]);
+
+    load_certificates(cert_file, key_file)
+}
+
+fn load_certificate_files(cert_file: &Path, key_file: &Path) -> anyhow::Result<CertifiedKey> {
+    let cert_file = &mut BufReader::new(File::open(cert_file)?);
+    let key_file = &mut BufReader::new(File::open(key_file)?);
+    load_certificates(cert_file, key_file)
+}
+
+fn load_certificates<R: std::io::Read>(
+    cert_file: &mut BufReader<R>,
+    key_file: &mut BufReader<R>,
+) -> anyhow::Result<CertifiedKey> {
+    let cert_chain = certs(cert_file).map_err(|_| {
+        anyhow::anyhow!("Failed to retrieve certificates from the certificate file")
+    })?;
+    if cert_chain.is_empty() {
+        anyhow::bail!("No certificates found in the certificate file");
+    }
+    let mut keys = rsa_private_keys(key_file).map_err(|_| {
+        anyhow::anyhow!("Failed to retrieve the rsa private keys from the key file")
+    })?;
+    if keys.is_empty() {
+        anyhow::bail!("No keys found in the keys file");
+    }
+    let key = sign::any_supported_type(&keys.remove(0))
+        .map_err(|_| anyhow::anyhow!("Unsupported private key in the key file"))?;
+    Ok(CertifiedKey::new(cert_chain, Arc::new(key)))
+}
+
+fn get_jwk_path() -> Option<String> {
+    match CliArgs::from_args().jwk {
+        Some(path) => Some(path),
+        None => match CliArgs::from_args().no_auth || CliArgs::from_args().client_ca.is_some() {
+            true => None,
+            false => panic!("Cannot authenticate without a JWK file"),
+        },
+    }
+}
+
+/// The REST application, which authenticates its requests with the
+/// `authenticator` and sends them to the control plane agents over the `bus`
+pub fn app(
+    bus: DynBus,
+    authenticator: Authenticator,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<Body>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+    Body,
+> {
+    App::new()
+        .wrap(metrics::RequestMetrics::default())
+        .wrap(access_log::AccessLog::default())
+        .wrap(caller::CallerScope::default())
+        .wrap(bus::BusScope::new(bus))
+        .app_data(authenticator)
+        .configure_api(&v0::configure_api)
+}
+
+/// Run the REST service as configured by its command line arguments, until
+/// it's stopped
+pub async fn run() -> anyhow::Result<()> {
+    // need to keep the jaeger pipeline tracer alive, if enabled
+    let _tracer = init_tracing();
+
+    // shared by all workers, so that the keys are reloaded only once
+    let authenticator = authentication::init(
+        get_jwk_path(),
+        CliArgs::from_args().client_ca.is_some(),
+        CliArgs::from_args().auth_options,
+    );
+
+    mbus_api::message_bus_init_options(
+        CliArgs::from_args().nats,
+        CliArgs::from_args().nats_options,
+        bus_timeout_opts(),
+    )
+    .await;
+    let factory = move || {
+        app(mbus_api::bus(), authenticator.clone())
+            .wrap(middleware::Logger::default())
+            .wrap(RequestTracing::new())
+    };
+    let server = HttpServer::new(factory)
+        .on_connect(tls::on_connect)
+        .bind_rustls(CliArgs::from_args().https, get_certificates()?)?;
+    let server = if let Some(http) = CliArgs::from_args().http {
+        server.bind(http).map_err(anyhow::Error::from)?
+    } else {
+        server
+    }
+    .run();
+
+    match CliArgs::from_args().metrics {
+        Some(address) => {
+            let metrics_server =
+                HttpServer::new(|| App::new().route("/metrics", web::get().to(metrics::handler)))
+                    .workers(1)
+                    .bind(address)?
+                    .run();
+            futures::try_join!(server, metrics_server)?;
+            Ok(())
+        }
+        None => server.await.map_err(|e| e.into()),
+    }
+}
@@ -0,0 +1,385 @@
+use actix_web::dev::Extensions;
+use rustls::{
+    sign::CertifiedKey, AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient,
+    ClientCertVerifier, ClientHello, NoClientAuth, ResolvesServerCert, RootCertStore, Session,
+    SignatureScheme,
+};
+use std::{
+    any::Any,
+    fs::File,
+    io::BufReader,
+    sync::{Arc, RwLock},
+};
+
+/// Resolves the server certificate of every new connection to the current
+/// certificate, which can be replaced at runtime, eg: when it's rotated
+pub(crate) struct CertificateResolver {
+    current: RwLock<CertifiedKey>,
+}
+
+impl CertificateResolver {
+    /// New resolver with the given initial certificate, whose key must match
+    pub(crate) fn new(certificate: CertifiedKey) -> anyhow::Result<Self> {
+        check_key(&certificate)?;
+        Ok(Self {
+            current: RwLock::new(certificate),
+        })
+    }
+
+    /// Replace the current certificate, for the new connections only
+    /// The current certificate is kept if the key of the new one doesn't
+    /// match, eg: when the key file is rotated after the certificate file
+    pub(crate) fn set(&self, certificate: CertifiedKey) -> anyhow::Result<()> {
+        check_key(&certificate)?;
+        *self.current.write().expect("not poisoned") = certificate;
+        Ok(())
+    }
+}
+
+/// Check that the key of the `certificate` matches its leaf certificate, by
+/// verifying a trial signature with the public key of the certificate
+fn check_key(certificate: &CertifiedKey) -> anyhow::Result<()> {
+    let leaf = certificate
+        .end_entity_cert()
+        .map_err(|_| anyhow::anyhow!("No certificate found"))?;
+    let leaf = webpki::EndEntityCert::from(&leaf.0)
+        .map_err(|error| anyhow::anyhow!("Failed to parse the certificate: {:?}", error))?;
+    let schemes = [
+        (
+            SignatureScheme::RSA_PSS_SHA256,
+            &webpki::RSA_PSS_2048_8192_SHA256_LEGACY_KEY,
+        ),
+        (
+            SignatureScheme::RSA_PKCS1_SHA256,
+            &webpki::RSA_PKCS1_2048_8192_SHA256,
+        ),
+        (
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            &webpki::ECDSA_P256_SHA256,
+        ),
+        (
+            SignatureScheme::ECDSA_NISTP384_SHA384,
+            &webpki::ECDSA_P384_SHA384,
+        ),
+        (SignatureScheme::ED25519, &webpki::ED25519),
+    ];
+    let signer = certificate
+        .key
+        .choose_scheme(
+            &schemes
+                .iter()
+                .map(|(scheme, _)| *scheme)
+                .collect::<Vec<_>>(),
+        )
+        .ok_or_else(|| anyhow::anyhow!("Unsupported private key"))?;
+    let (_, algorithm) = schemes
+        .iter()
+        .find(|(scheme, _)| *scheme == signer.get_scheme())
+        .ok_or_else(|| anyhow::anyhow!("Unsupported private key"))?;
+    let message = b"certificate key check";
+    let signature = signer
+        .sign(message)
+        .map_err(|error| anyhow::anyhow!("Failed to sign with the private key: {}", error))?;
+    leaf.verify_signature(algorithm, message, &signature)
+        .map_err(|_| anyhow::anyhow!("The private key does not match the certificate"))
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<CertifiedKey> {
+        Some(self.current.read().expect("not poisoned").clone())
+    }
+}
+
+/// Identity of a caller which presented a client certificate verified by the
+/// client CA, stored in the connection data
+#[derive(Debug, Clone)]
+pub(crate) struct ClientIdentity {
+    subject: String,
+}
+
+impl ClientIdentity {
+    /// Subject of the client certificate, eg: `CN=automation, O=example`
+    pub(crate) fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Identity from the DER encoded client certificate
+    fn from_der(certificate: &[u8]) -> Option<Self> {
+        match der::certificate_subject(certificate) {
+            Some(subject) => Some(Self { subject }),
+            None => {
+                tracing::error!("Failed to parse the subject of the client certificate");
+                None
+            }
+        }
+    }
+}
+
+/// Subject of X.509 certificates, decoded with `simple_asn1`
+mod der {
+    use simple_asn1::{from_der, ASN1Block, BigUint, OID};
+
+    /// Subject of the DER encoded `certificate`, with its relative
+    /// distinguished names in the order of the certificate, separated by `, `,
+    /// eg: `CN=automation, O=example`
+    pub(super) fn certificate_subject(certificate: &[u8]) -> Option<String> {
+        let blocks = from_der(certificate).ok()?;
+        let tbs = match blocks.first()? {
+            ASN1Block::Sequence(_, certificate) => match certificate.first()? {
+                ASN1Block::Sequence(_, tbs) => tbs,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        // the version is optional, the serial number is followed by the
+        // signature algorithm, the issuer, the validity and the subject
+        let serial = tbs
+            .iter()
+            .position(|block| matches!(block, ASN1Block::Integer(..)))?;
+        name(tbs.get(serial + 4)?)
+    }
+
+    /// Distinguished name from its sequence of relative distinguished names,
+    /// whose attribute values are escaped as in RFC 4514, so that a value
+    /// can't pass for other attributes, and whose multiple attributes, if
+    /// any, are separated by `+`
+    pub(super) fn name(name: &ASN1Block) -> Option<String> {
+        let rdns = match name {
+            ASN1Block::Sequence(_, rdns) => rdns,
+            _ => return None,
+        };
+        let mut names = vec![];
+        for rdn in rdns {
+            let attributes = match rdn {
+                ASN1Block::Set(_, attributes) if !attributes.is_empty() => attributes,
+                _ => return None,
+            };
+            let mut values = vec![];
+            for attribute in attributes {
+                match attribute {
+                    ASN1Block::Sequence(_, attribute) => match attribute.as_slice() {
+                        [ASN1Block::ObjectIdentifier(_, oid), value] => values.push(format!(
+                            "{}={}",
+                            attribute_name(oid)?,
+                            escape(string(value)?)
+                        )),
+                        _ => return None,
+                    },
+                    _ => return None,
+                }
+            }
+            names.push(values.join("+"));
+        }
+        Some(names.join(", "))
+    }
+
+    /// Short name of the attribute type `oid`, or its dotted form if it's not
+    /// a well known one
+    fn attribute_name(oid: &OID) -> Option<String> {
+        let names = [
+            (simple_asn1::oid!(2, 5, 4, 3), "CN"),
+            (simple_asn1::oid!(2, 5, 4, 5), "serialNumber"),
+            (simple_asn1::oid!(2, 5, 4, 6), "C"),
+            (simple_asn1::oid!(2, 5, 4, 7), "L"),
+            (simple_asn1::oid!(2, 5, 4, 8), "ST"),
+            (simple_asn1::oid!(2, 5, 4, 10), "O"),
+            (simple_asn1::oid!(2, 5, 4, 11), "OU"),
+            (
+                simple_asn1::oid!(1, 2, 840, 113_549, 1, 9, 1),
+                "emailAddress",
+            ),
+        ];
+        match names.iter().find(|(known, _)| known == oid) {
+            Some((_, name)) => Some(name.to_string()),
+            None => Some(dotted(&oid.as_raw().ok()?)),
+        }
+    }
+
+    /// Dotted form of the DER encoded `oid`, eg: `2.5.4.3`
+    fn dotted(oid: &[u8]) -> String {
+        let mut arcs = vec![];
+        let mut arc = 0u64;
+        for byte in oid {
+            arc = (arc << 7) | (byte & 0x7f) as u64;
+            if byte & 0x80 == 0 {
+                if arcs.is_empty() {
+                    let first = (arc / 40).min(2);
+                    arcs.push(first);
+                    arcs.push(arc - first * 40);
+                } else {
+                    arcs.push(arc);
+                }
+                arc = 0;
+            }
+        }
+        arcs.iter()
+            .map(|arc| arc.to_string())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Value of a directory string
+    fn string(value: &ASN1Block) -> Option<&str> {
+        match value {
+            ASN1Block::UTF8String(_, value)
+            | ASN1Block::PrintableString(_, value)
+            | ASN1Block::TeletexString(_, value)
+            | ASN1Block::IA5String(_, value)
+            | ASN1Block::UniversalString(_, value)
+            | ASN1Block::BMPString(_, value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Attribute `value` escaped as in RFC 4514, also escaping `=`
+    fn escape(value: &str) -> String {
+        let last = value.chars().count().saturating_sub(1);
+        let mut escaped = String::with_capacity(value.len());
+        for (index, c) in value.chars().enumerate() {
+            match c {
+                '"' | '+' | ',' | ';' | '<' | '>' | '\\' | '=' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '#' if index == 0 => escaped.push_str("\\#"),
+                ' ' if index == 0 || index == last => escaped.push_str("\\ "),
+                '\0' => escaped.push_str("\\00"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+}
+
+/// Verifier of the client certificates, which must be signed by one of the
+/// CA certificates in the file at `client_ca`, if any.
+/// Unless `required`, clients which don't present a certificate are accepted
+/// so that they may authenticate with bearer tokens instead.
+pub(crate) fn client_verifier(
+    client_ca: Option<&str>,
+    required: bool,
+) -> anyhow::Result<Arc<dyn ClientCertVerifier>> {
+    let client_ca = match client_ca {
+        Some(client_ca) => client_ca,
+        None => return Ok(NoClientAuth::new()),
+    };
+    let mut roots = RootCertStore::empty();
+    let (added, _) = roots
+        .add_pem_file(&mut BufReader::new(File::open(client_ca)?))
+        .map_err(|_| anyhow::anyhow!("Failed to retrieve certificates from the client CA file"))?;
+    if added == 0 {
+        anyhow::bail!("No certificates found in the client CA file");
+    }
+    Ok(if required {
+        AllowAnyAuthenticatedClient::new(roots)
+    } else {
+        AllowAnyAnonymousOrAuthenticatedClient::new(roots)
+    })
+}
+
+/// Called on every new connection to store the identity of the client, if
+/// it presented a (verified) certificate
+pub(crate) fn on_connect(connection: &dyn Any, data: &mut Extensions) {
+    type TlsStream = actix_tls::accept::rustls::TlsStream<actix_web::rt::net::TcpStream>;
+    if let Some(stream) = connection.downcast_ref::<TlsStream>() {
+        let (_, session) = stream.get_ref();
+        let identity = session.get_peer_certificates().and_then(|chain| {
+            chain
+                .first()
+                .and_then(|leaf| ClientIdentity::from_der(&leaf.0))
+        });
+        if let Some(identity) = identity {
+            data.insert(identity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// DER element with the `tag` and the `content`
+    fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut element = vec![tag, content.len() as u8];
+        element.extend_from_slice(content);
+        element
+    }
+
+    /// Certificate from the `cert` file with the key from the `key` file
+    fn certified_key(cert: &str, key: &str) -> CertifiedKey {
+        let file = |name: &str| {
+            let path = format!("{}/certs/rsa/{}", env!("CARGO_MANIFEST_DIR"), name);
+            BufReader::new(File::open(path).unwrap())
+        };
+        let certificates = rustls::internal::pemfile::certs(&mut file(cert)).unwrap();
+        let keys = rustls::internal::pemfile::rsa_private_keys(&mut file(key)).unwrap();
+        let key = rustls::sign::any_supported_type(&keys[0]).unwrap();
+        CertifiedKey::new(certificates, Arc::new(key))
+    }
+
+    #[test]
+    fn certificate_key_test() {
+        let resolver = CertificateResolver::new(certified_key("user.chain", "user.rsa")).unwrap();
+        assert!(CertificateResolver::new(certified_key("ca.cert", "user.rsa")).is_err());
+
+        // a mismatched certificate is not served
+        let current = resolver.current.read().unwrap().cert.clone();
+        assert!(resolver.set(certified_key("ca.cert", "user.rsa")).is_err());
+        assert_eq!(resolver.current.read().unwrap().cert, current);
+        assert!(resolver.set(certified_key("user.cert", "user.rsa")).is_ok());
+    }
+
+    #[test]
+    fn certificate_subject_test() {
+        let file = concat!(env!("CARGO_MANIFEST_DIR"), "/certs/rsa/user.cert");
+        let certificates =
+            rustls::internal::pemfile::certs(&mut BufReader::new(File::open(file).unwrap()))
+                .unwrap();
+        let identity = ClientIdentity::from_der(&certificates[0].0).unwrap();
+        assert_eq!(identity.subject(), "CN=testserver.com");
+        assert!(ClientIdentity::from_der(&certificates[0].0[.. 100]).is_none());
+
+        let attribute = |oid: &[u8], tag: u8, value: &str| {
+            der(0x30, &[der(0x06, oid), der(tag, value.as_bytes())].concat())
+        };
+        let name = |rdns: &[Vec<Vec<u8>>]| {
+            let rdns = rdns
+                .iter()
+                .map(|attributes| der(0x31, &attributes.concat()))
+                .collect::<Vec<_>>();
+            let name = simple_asn1::from_der(&der(0x30, &rdns.concat())).unwrap();
+            der::name(&name[0]).unwrap()
+        };
+        const CN: &[u8] = &[0x55, 0x04, 0x03];
+        const O: &[u8] = &[0x55, 0x04, 0x0a];
+
+        assert_eq!(
+            name(&[
+                vec![attribute(CN, 0x0c, "automation")],
+                vec![attribute(O, 0x13, "example")],
+                vec![attribute(
+                    &[0x2b, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37, 0x01],
+                    0x16,
+                    "other",
+                )],
+            ]),
+            "CN=automation, O=example, 1.3.6.1.4.1.311.1=other"
+        );
+        // a value can't pass for other attributes
+        assert_eq!(
+            name(&[vec![attribute(CN, 0x0c, "automation, O=example")]]),
+            "CN=automation\\, O\\=example"
+        );
+        assert_eq!(
+            name(&[vec![attribute(CN, 0x0c, " #a+b\\c ")]]),
+            "CN=\\ #a\\+b\\\\c\\ "
+        );
+        assert_eq!(
+            name(&[vec![
+                attribute(CN, 0x0c, "automation"),
+                attribute(O, 0x13, "example")
+            ]]),
+            "CN=automation+O=example"
+        );
+    }
+}
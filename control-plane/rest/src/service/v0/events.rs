@@ -0,0 +1,187 @@
+//! Streaming watch of the resource change events as Server-Sent Events.
+//! Each event is sent with its revision as the event id so that a client can
+//! resume the stream with the `Last-Event-ID` header (or `revision` query) from
+//! the last event it received, as long as the core agent still retains it.
+use super::*;
+use actix_web::{web::Bytes, HttpResponse};
+use common_lib::types::v0::message_bus::{
+    ChannelVs, GetResourceEvents, ResourceEvent, WatchResourceId,
+};
+use mbus_api::{bus, BusSubscription, Message, ReceivedRawMessage};
+use std::{collections::VecDeque, time::Duration};
+
+/// Period of the heartbeats which keep the idle streams alive
+const HEARTBEAT_PERIOD: Duration = Duration::from_secs(15);
+/// Resource kinds which may be watched
+const RESOURCE_KINDS: [&str; 5] = ["nodes", "pools", "replicas", "nexuses", "volumes"];
+
+/// Configure the stream handler, ahead of the generated `get_watch_events`
+/// handler which can only reply with a single json value
+pub(super) fn configure(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.service(
+        actix_web::web::resource("/watches/events")
+            .name("get_watch_events")
+            .guard(actix_web::guard::Get())
+            .route(actix_web::web::get().to(get_watch_events)),
+    );
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+    /// resource kind (eg: `volumes`) or resource (eg: `volumes/{volume_id}`)
+    /// to watch, otherwise all the resources are watched
+    resource: Option<String>,
+    /// revision after which the stream is resumed, overriding the
+    /// `Last-Event-ID` header
+    revision: Option<u64>,
+}
+
+async fn get_watch_events(
+    _token: BearerToken,
+    request: HttpRequest,
+    query: actix_web::web::Query<EventsQuery>,
+) -> Result<HttpResponse, RestError<RestJsonError>> {
+    let query = query.into_inner();
+    if let Some(resource) = &query.resource {
+        let kind = resource.split('/').next().unwrap_or_default();
+        if !RESOURCE_KINDS.contains(&kind) {
+            return Err(invalid_argument(
+                "resource",
+                format!("unknown resource kind '{}'", kind),
+            ));
+        }
+    }
+    let revision = match query.revision {
+        Some(revision) => Some(revision),
+        None => last_event_id(&request)?,
+    };
+
+    // subscribe before getting the retained events so none is missed between
+    // the two, the duplicates are then skipped by their revision
+    let subscription = bus().subscribe(ChannelVs::Events.into()).await?;
+    let backlog = match revision {
+        Some(revision) => GetResourceEvents { revision }
+            .request()
+            .await?
+            .into_inner()
+            .into(),
+        None => VecDeque::new(),
+    };
+    let start = tokio::time::Instant::now() + HEARTBEAT_PERIOD;
+    let stream = EventStream {
+        subscription,
+        backlog,
+        last_revision: revision.unwrap_or_default(),
+        resource: query.resource,
+        heartbeat: tokio::time::interval_at(start, HEARTBEAT_PERIOD),
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((http::header::CACHE_CONTROL, "no-cache"))
+        .streaming(Box::pin(futures::stream::unfold(
+            stream,
+            |mut stream| async move {
+                let chunk = stream.next().await?;
+                Some((Ok::<_, actix_web::Error>(chunk), stream))
+            },
+        ))))
+}
+
+/// Revision of the last event received by the client, if it's resuming
+fn last_event_id(request: &HttpRequest) -> Result<Option<u64>, RestError<RestJsonError>> {
+    match request.headers().get("Last-Event-ID") {
+        None => Ok(None),
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .map(Some)
+            .ok_or_else(|| invalid_argument("Last-Event-ID", "must be a revision".to_string())),
+    }
+}
+
+fn invalid_argument(source: &str, extra: String) -> RestError<RestJsonError> {
+    RestError::from(ReplyError {
+        kind: ReplyErrorKind::InvalidArgument,
+        resource: ResourceKind::Watch,
+        source: source.to_string(),
+        extra,
+    })
+}
+
+/// Stream of the events of the watched resources, starting with the retained
+/// events when resuming
+struct EventStream {
+    subscription: BusSubscription,
+    backlog: VecDeque<ResourceEvent>,
+    last_revision: u64,
+    resource: Option<String>,
+    heartbeat: tokio::time::Interval,
+}
+
+impl EventStream {
+    /// Next chunk of the stream, either an event or a heartbeat, or None once
+    /// the subscription is closed
+    async fn next(&mut self) -> Option<Bytes> {
+        loop {
+            if let Some(event) = self.backlog.pop_front() {
+                if let Some(chunk) = self.chunk(event) {
+                    return Some(chunk);
+                }
+                continue;
+            }
+            tokio::select! {
+                message = self.subscription.next() => {
+                    let message = message?;
+                    match ReceivedRawMessage::from(&message).inner::<ResourceEvent>() {
+                        Ok(event) => {
+                            if let Some(chunk) = self.chunk(event) {
+                                return Some(chunk);
+                            }
+                        }
+                        Err(error) => {
+                            tracing::error!("Failed to decode the resource event: {}", error)
+                        }
+                    }
+                }
+                _ = self.heartbeat.tick() => {
+                    return Some(Bytes::from_static(b": heartbeat\n\n"));
+                }
+            }
+        }
+    }
+
+    /// The event formatted as a Server-Sent Event, unless it was already sent
+    /// or is not of a watched resource
+    fn chunk(&mut self, event: ResourceEvent) -> Option<Bytes> {
+        if event.revision <= self.last_revision {
+            return None;
+        }
+        self.last_revision = event.revision;
+        if !self.watches(&event.resource) {
+            return None;
+        }
+        let data = serde_json::to_string(&event).ok()?;
+        Some(Bytes::from(format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            event.revision,
+            event.event_type.to_string(),
+            data
+        )))
+    }
+
+    /// Whether the resource is watched, either by itself or by its kind
+    fn watches(&self, resource: &WatchResourceId) -> bool {
+        match &self.resource {
+            None => true,
+            Some(watched) => {
+                let resource = resource.to_string();
+                match resource.strip_prefix(watched.as_str()) {
+                    Some(rest) => rest.is_empty() || rest.starts_with('/'),
+                    None => false,
+                }
+            }
+        }
+    }
+}
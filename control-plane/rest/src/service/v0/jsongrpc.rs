@@ -12,7 +12,8 @@ impl apis::JsonGrpc for RestApi {
     // body.
     //
     // # Example
-    // To create a malloc bdev:
+    // To create a malloc bdev, provided the method is in the allowlist of the
+    // jsongrpc agent:
     // ```
     //  curl -X PUT "https://localhost:8080/v0/nodes/mayastor/jsongrpc/bdev_malloc_create" \
     //  -H "accept: application/json" -H "Content-Type: application/json" \
@@ -22,10 +23,13 @@ impl apis::JsonGrpc for RestApi {
         Path((node, method)): Path<(String, String)>,
         Body(body): Body<Value>,
     ) -> Result<Value, RestError<RestJsonError>> {
+        let caller = crate::service::caller::current();
         let result = MessageBus::json_grpc_call(JsonGrpcRequest {
             node: node.into(),
             method: method.into(),
             params: body.to_string().into(),
+            caller: caller.subject,
+            roles: caller.roles,
         })
         .await?;
         Ok(result)
@@ -4,6 +4,7 @@
 
 pub mod block_devices;
 pub mod children;
+pub mod events;
 pub mod jsongrpc;
 pub mod nexuses;
 pub mod nodes;
@@ -23,13 +24,14 @@ use actix_web::{
 use futures::future::Ready;
 use serde::Deserialize;
 
-use crate::authentication::authenticate;
+use super::authentication::authenticate;
+use crate::versions::v0::*;
+use common_lib::types::v0::message_bus::{Filter, Selector};
 pub use common_lib::types::v0::openapi::{
     apis::{Body, Path, Query, RestError},
     models::RestJsonError,
 };
 use mbus_api::{ReplyError, ReplyErrorKind, ResourceKind};
-use rest_client::versions::v0::*;
 
 fn version() -> String {
     "v0".into()
@@ -41,6 +43,8 @@ fn spec_uri() -> String {
 pub(crate) struct RestApi {}
 
 fn configure(cfg: &mut actix_web::web::ServiceConfig) {
+    // the first matching route is used, so the event stream must come first
+    events::configure(cfg);
     apis::configure::<RestApi, BearerToken>(cfg);
     // todo: remove when the /states is added to the spec
     states::configure(cfg);
@@ -56,6 +60,54 @@ fn json_error(err: impl std::fmt::Display, _req: &actix_web::HttpRequest) -> act
     .into()
 }
 
+/// Filter of the list requests which selects the objects by their labels and
+/// state, a page at a time, when any of the selection query parameters is given
+fn selector_filter(
+    resource: ResourceKind,
+    max_entries: Option<i64>,
+    continuation_token: Option<String>,
+    label_selector: Option<String>,
+    state: Option<String>,
+) -> Result<Filter, RestError<RestJsonError>> {
+    let max_entries = match max_entries {
+        Some(max_entries) if max_entries < 1 => {
+            return Err(RestError::from(ReplyError {
+                kind: ReplyErrorKind::InvalidArgument,
+                resource,
+                source: "max_entries".to_string(),
+                extra: format!("max_entries must be at least 1, got {}", max_entries),
+            }))
+        }
+        max_entries => max_entries.map(|max_entries| max_entries as u64),
+    };
+    let selector = Selector {
+        labels: label_selector
+            .as_deref()
+            .map(Selector::parse_labels)
+            .unwrap_or_default(),
+        state,
+        max_entries,
+        continuation_token,
+    };
+    Ok(if selector == Selector::default() {
+        Filter::None
+    } else {
+        Filter::Selector(selector)
+    })
+}
+
+/// Continuation token of the next page of a list request with the `filter`,
+/// if the `page` is full and so there may be more objects after it
+fn next_token<T, F>(filter: &Filter, page: &[T], id: F) -> Option<String>
+where
+    F: Fn(&T) -> String,
+{
+    match filter {
+        Filter::Selector(selector) => selector.next_token(page, id),
+        _ => None,
+    }
+}
+
 pub(super) fn configure_api<T, B>(api: actix_web::App<T, B>) -> actix_web::App<T, B>
 where
     B: MessageBody,
@@ -65,9 +65,25 @@ impl apis::Nexuses for RestApi {
         Ok(nexus.into())
     }
 
-    async fn get_nexuses() -> Result<Vec<models::Nexus>, RestError<RestJsonError>> {
-        let nexuses = MessageBus::get_nexuses(Filter::None).await?;
-        Ok(nexuses.into_iter().map(From::from).collect())
+    async fn get_nexuses(
+        Query((max_entries, continuation_token, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<models::Nexuses, RestError<RestJsonError>> {
+        let filter = selector_filter(
+            ResourceKind::Nexus,
+            max_entries,
+            continuation_token,
+            None,
+            state,
+        )?;
+        let nexuses = MessageBus::get_nexuses(filter.clone()).await?;
+        Ok(models::Nexuses::new_all(
+            next_token(&filter, &nexuses, |nexus| nexus.uuid.to_string()),
+            nexuses,
+        ))
     }
 
     async fn get_node_nexus(
@@ -0,0 +1,68 @@
+use super::*;
+use common_lib::types::v0::message_bus::{Config, GetConfigRevision, GetConfigRevisions};
+use mbus_api::message_bus::v0::{MessageBus, MessageBusTrait};
+use std::str::FromStr;
+
+/// Parse the config `kind` given in the path
+fn config_kind(kind: &str) -> Result<Config, RestError<RestJsonError>> {
+    Config::from_str(kind).map_err(|_| {
+        RestError::from(ReplyError {
+            kind: ReplyErrorKind::InvalidArgument,
+            resource: ResourceKind::Config,
+            source: "kind".to_string(),
+            extra: format!("'{}' is not a valid config kind", kind),
+        })
+    })
+}
+
+#[async_trait::async_trait]
+impl apis::Nodes for RestApi {
+    async fn get_node(Path(id): Path<String>) -> Result<models::Node, RestError<RestJsonError>> {
+        let node = MessageBus::get_node(&id.into()).await?;
+        Ok(node.into())
+    }
+
+    async fn get_node_config_revision(
+        Path((id, kind, revision)): Path<(String, String, i64)>,
+    ) -> Result<models::NodeConfigRevision, RestError<RestJsonError>> {
+        let request = GetConfigRevision {
+            node: id.into(),
+            kind: config_kind(&kind)?,
+            revision: revision.max(0) as u64,
+        };
+        let revision = MessageBus::get_config_revision(request).await?;
+        let mut model = models::NodeConfigRevision::from(&revision);
+        model.data = Some(base64::encode(&revision.data));
+        Ok(model)
+    }
+
+    async fn get_node_config_revisions(
+        Path((id, kind)): Path<(String, String)>,
+    ) -> Result<Vec<models::NodeConfigRevision>, RestError<RestJsonError>> {
+        let request = GetConfigRevisions {
+            node: id.into(),
+            kind: config_kind(&kind)?,
+        };
+        let revisions = MessageBus::get_config_revisions(request).await?;
+        Ok(revisions
+            .into_inner()
+            .iter()
+            .map(models::NodeConfigRevision::from)
+            .collect())
+    }
+
+    async fn get_node_history(
+        Path(id): Path<String>,
+    ) -> Result<Vec<models::NodeStateTransition>, RestError<RestJsonError>> {
+        let history = MessageBus::get_node_history(&id.into()).await?;
+        Ok(history
+            .iter()
+            .map(models::NodeStateTransition::from)
+            .collect())
+    }
+
+    async fn get_nodes() -> Result<Vec<models::Node>, RestError<RestJsonError>> {
+        let nodes = MessageBus::get_nodes().await?;
+        Ok(nodes.iter().map(models::Node::from).collect())
+    }
+}
@@ -68,9 +68,26 @@ impl apis::Pools for RestApi {
         Ok(pool.into())
     }
 
-    async fn get_pools() -> Result<Vec<models::Pool>, RestError<RestJsonError>> {
-        let pools = MessageBus::get_pools(Filter::None).await?;
-        Ok(pools.into_iter().map(From::from).collect())
+    async fn get_pools(
+        Query((max_entries, continuation_token, label_selector, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<models::Pools, RestError<RestJsonError>> {
+        let filter = selector_filter(
+            ResourceKind::Pool,
+            max_entries,
+            continuation_token,
+            label_selector,
+            state,
+        )?;
+        let pools = MessageBus::get_pools(filter.clone()).await?;
+        Ok(models::Pools::new_all(
+            next_token(&filter, &pools, |pool| pool.id.to_string()),
+            pools,
+        ))
     }
 
     async fn put_node_pool(
@@ -210,9 +210,25 @@ impl apis::Replicas for RestApi {
         Ok(replica.into())
     }
 
-    async fn get_replicas() -> Result<Vec<models::Replica>, RestError<RestJsonError>> {
-        let replicas = MessageBus::get_replicas(Filter::None).await?;
-        Ok(replicas.into_iter().map(From::from).collect())
+    async fn get_replicas(
+        Query((max_entries, continuation_token, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<models::Replicas, RestError<RestJsonError>> {
+        let filter = selector_filter(
+            ResourceKind::Replica,
+            max_entries,
+            continuation_token,
+            None,
+            state,
+        )?;
+        let replicas = MessageBus::get_replicas(filter.clone()).await?;
+        Ok(models::Replicas::new_all(
+            next_token(&filter, &replicas, |replica| replica.uuid.to_string()),
+            replicas,
+        ))
     }
 
     async fn put_node_pool_replica(
@@ -0,0 +1,28 @@
+use super::*;
+use common_lib::types::v0::message_bus::GetSpecs;
+use mbus_api::{
+    message_bus::v0::{MessageBus, MessageBusTrait},
+    ResourceKind,
+};
+
+#[async_trait::async_trait]
+impl apis::Specs for RestApi {
+    async fn get_specs(
+        Query((max_entries, continuation_token, label_selector, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<models::Specs, RestError<RestJsonError>> {
+        let filter = selector_filter(
+            ResourceKind::Unknown,
+            max_entries,
+            continuation_token,
+            label_selector,
+            state,
+        )?;
+        let specs = MessageBus::get_specs(GetSpecs { filter }).await?;
+        Ok(specs.into())
+    }
+}
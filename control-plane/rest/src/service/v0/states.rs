@@ -12,7 +12,9 @@ pub(super) fn configure(cfg: &mut actix_web::web::ServiceConfig) {
     );
 }
 
-async fn get_states() -> Result<actix_web::web::Json<States>, RestError<RestJsonError>> {
+async fn get_states(
+    _token: BearerToken,
+) -> Result<actix_web::web::Json<States>, RestError<RestJsonError>> {
     let states = MessageBus::get_states(GetStates {}).await?;
     Ok(actix_web::web::Json(states))
 }
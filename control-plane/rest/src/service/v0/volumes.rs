@@ -88,9 +88,26 @@ impl apis::Volumes for RestApi {
         Ok(volume.into())
     }
 
-    async fn get_volumes() -> Result<Vec<models::Volume>, RestError<RestJsonError>> {
-        let volumes = MessageBus::get_volumes(Filter::None).await?;
-        Ok(volumes.into_iter().map(From::from).collect())
+    async fn get_volumes(
+        Query((max_entries, continuation_token, label_selector, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<models::Volumes, RestError<RestJsonError>> {
+        let filter = selector_filter(
+            ResourceKind::Volume,
+            max_entries,
+            continuation_token,
+            label_selector,
+            state,
+        )?;
+        let volumes = MessageBus::get_volumes(filter.clone()).await?;
+        Ok(models::Volumes::new_all(
+            next_token(&filter, &volumes, |volume| volume.uuid.to_string()),
+            volumes,
+        ))
     }
 
     async fn put_volume(
@@ -0,0 +1,128 @@
+use super::*;
+use common_lib::types::v0::{
+    message_bus::{
+        CreateWatch, DeleteWatch, Filter, GetWatchers, WatchCallback, WatchResourceId, WatchType,
+    },
+    store::definitions::StorableObjectType,
+};
+use mbus_api::Message;
+use std::convert::TryFrom;
+
+#[async_trait::async_trait]
+impl apis::Watches for RestApi {
+    async fn del_watch_volume(
+        Path(volume_id): Path<String>,
+        Query((callback, watch_type)): Query<(url::Url, Option<models::WatchType>)>,
+    ) -> Result<(), RestError<RestJsonError>> {
+        DeleteWatch {
+            id: WatchResourceId::Volume(volume_id.into()),
+            callback: WatchCallback::from(&callback),
+            watch_type: watch_type.map(WatchType::from).unwrap_or_default(),
+        }
+        .request()
+        .await?;
+
+        Ok(())
+    }
+
+    async fn del_watch_volumes(
+        Query((callback, watch_type)): Query<(url::Url, Option<models::WatchType>)>,
+    ) -> Result<(), RestError<RestJsonError>> {
+        DeleteWatch {
+            id: WatchResourceId::Kind(StorableObjectType::Volume),
+            callback: WatchCallback::from(&callback),
+            watch_type: watch_type.map(WatchType::from).unwrap_or_default(),
+        }
+        .request()
+        .await?;
+
+        Ok(())
+    }
+
+    // The events are streamed by `events::get_watch_events`, which is
+    // configured ahead of this generated handler since it cannot stream.
+    async fn get_watch_events(
+        Query((_resource, _revision)): Query<(Option<String>, Option<i64>)>,
+    ) -> Result<String, RestError<RestJsonError>> {
+        Err(RestError::from(ReplyError {
+            kind: ReplyErrorKind::Unimplemented,
+            resource: ResourceKind::Watch,
+            source: "get_watch_events".to_string(),
+            extra: "the events are only served as a stream".to_string(),
+        }))
+    }
+
+    async fn get_watch_volume(
+        Path(volume_id): Path<String>,
+    ) -> Result<Vec<models::RestWatch>, RestError<RestJsonError>> {
+        let watches = GetWatchers {
+            resource: WatchResourceId::Volume(volume_id.into()),
+        }
+        .request()
+        .await?;
+        let watches = watches.0.iter();
+        let watches = watches
+            .filter_map(|w| models::RestWatch::try_from(w).ok())
+            .collect();
+        Ok(watches)
+    }
+
+    async fn get_watch_volumes() -> Result<Vec<models::RestWatch>, RestError<RestJsonError>> {
+        let watches = GetWatchers {
+            resource: WatchResourceId::Kind(StorableObjectType::Volume),
+        }
+        .request()
+        .await?;
+        let watches = watches.0.iter();
+        let watches = watches
+            .filter_map(|w| models::RestWatch::try_from(w).ok())
+            .collect();
+        Ok(watches)
+    }
+
+    async fn put_watch_volume(
+        Path(volume_id): Path<String>,
+        Query((callback, watch_type, secret)): Query<(
+            url::Url,
+            Option<models::WatchType>,
+            Option<String>,
+        )>,
+    ) -> Result<(), RestError<RestJsonError>> {
+        CreateWatch {
+            id: WatchResourceId::Volume(volume_id.into()),
+            callback: WatchCallback::from(&callback),
+            watch_type: watch_type.map(WatchType::from).unwrap_or_default(),
+            secret,
+            filter: Filter::None,
+            delivery: None,
+        }
+        .request()
+        .await?;
+
+        Ok(())
+    }
+
+    async fn put_watch_volumes(
+        Query((callback, watch_type, secret, label_selector, state)): Query<(
+            url::Url,
+            Option<models::WatchType>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<(), RestError<RestJsonError>> {
+        let filter = selector_filter(ResourceKind::Watch, None, None, label_selector, state)?;
+        CreateWatch {
+            id: WatchResourceId::Kind(StorableObjectType::Volume),
+            callback: WatchCallback::from(&callback),
+            watch_type: watch_type.map(WatchType::from).unwrap_or_default(),
+            secret,
+            filter,
+            delivery: None,
+        }
+        .request()
+        .await?;
+
+        Ok(())
+    }
+}
@@ -12,7 +12,7 @@ pub use common_lib::{
             GetBlockDevices, JsonGrpcRequest, Nexus, NexusId, Node, NodeId, Pool, PoolDeviceUri,
             PoolId, Protocol, RemoveNexusChild, Replica, ReplicaId, ReplicaShareProtocol,
             ShareNexus, ShareReplica, Specs, Topology, UnshareNexus, UnshareReplica, Volume,
-            VolumeHealPolicy, VolumeId, Watch, WatchCallback, WatchResourceId,
+            VolumeHealPolicy, VolumeId, Watch, WatchCallback, WatchDelivery, WatchResourceId,
         },
         openapi::{apis, models},
     },
@@ -152,6 +152,9 @@ pub struct CreateVolumeBody {
     pub policy: VolumeHealPolicy,
     #[allow(missing_docs)]
     pub topology: Topology,
+    /// thin provisioning of the replicas
+    #[serde(default)]
+    pub thin: bool,
 }
 impl From<models::CreateVolumeBody> for CreateVolumeBody {
     fn from(src: models::CreateVolumeBody) -> Self {
@@ -160,6 +163,7 @@ impl From<models::CreateVolumeBody> for CreateVolumeBody {
             replicas: src.replicas as u64,
             policy: src.policy.into(),
             topology: src.topology.into(),
+            thin: src.thin.unwrap_or_default(),
         }
     }
 }
@@ -170,6 +174,7 @@ impl From<CreateVolume> for CreateVolumeBody {
             replicas: create.replicas,
             policy: create.policy,
             topology: create.topology,
+            thin: create.thin,
         }
     }
 }
@@ -182,6 +187,7 @@ impl CreateVolumeBody {
             replicas: self.replicas,
             policy: self.policy.clone(),
             topology: self.topology.clone(),
+            thin: self.thin,
         }
     }
 }
@@ -194,19 +200,18 @@ pub struct RestWatch {
     pub resource: String,
     /// callback used to notify the watcher of a change
     pub callback: String,
+    /// delivery status of the notifications of the watch
+    pub delivery: Option<WatchDelivery>,
 }
 
 impl TryFrom<&Watch> for RestWatch {
     type Error = ();
     fn try_from(value: &Watch) -> Result<Self, Self::Error> {
-        match &value.callback {
-            WatchCallback::Uri(uri) => Ok(Self {
-                resource: value.id.to_string(),
-                callback: uri.to_string(),
-            }),
-            /* other types are not implemented yet and should map to an error
-             * _ => Err(()), */
-        }
+        Ok(Self {
+            resource: value.id.to_string(),
+            callback: value.callback.to_string(),
+            delivery: value.delivery.clone(),
+        })
     }
 }
 impl From<models::RestWatch> for RestWatch {
@@ -214,6 +219,7 @@ impl From<models::RestWatch> for RestWatch {
         RestWatch {
             resource: value.resource,
             callback: value.callback,
+            delivery: value.delivery.map(From::from),
         }
     }
 }
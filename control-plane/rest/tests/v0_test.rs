@@ -168,11 +168,17 @@ async fn client_test(mayastor: &NodeId, test: &ComposeTest, auth: &bool) {
         id: mayastor.to_string(),
         grpc_endpoint: "10.1.0.5:10124".to_string(),
         state: models::NodeState::Online,
+        capabilities: Some(vec![]),
+        version: None,
     };
     assert_eq!(nodes.len(), 1);
     assert_eq!(nodes.first().unwrap(), &node);
     info!("Nodes: {:#?}", nodes);
-    let _ = client.pools_api().get_pools().await.unwrap();
+    let _ = client
+        .pools_api()
+        .get_pools(None, None, None, None)
+        .await
+        .unwrap();
     let pool = client
         .pools_api()
         .put_node_pool(
@@ -200,10 +206,20 @@ async fn client_test(mayastor: &NodeId, test: &ComposeTest, auth: &bool) {
 
     assert_eq!(
         Some(&pool),
-        client.pools_api().get_pools().await.unwrap().first()
+        client
+            .pools_api()
+            .get_pools(None, None, None, None)
+            .await
+            .unwrap()
+            .entries
+            .first()
     );
 
-    let _ = client.replicas_api().get_replicas().await.unwrap();
+    let _ = client
+        .replicas_api()
+        .get_replicas(None, None, None)
+        .await
+        .unwrap();
     let replica = client
         .replicas_api()
         .put_node_pool_replica(
@@ -234,7 +250,13 @@ async fn client_test(mayastor: &NodeId, test: &ComposeTest, auth: &bool) {
     );
     assert_eq!(
         Some(&replica),
-        client.replicas_api().get_replicas().await.unwrap().first()
+        client
+            .replicas_api()
+            .get_replicas(None, None, None)
+            .await
+            .unwrap()
+            .entries
+            .first()
     );
     client
         .replicas_api()
@@ -242,10 +264,20 @@ async fn client_test(mayastor: &NodeId, test: &ComposeTest, auth: &bool) {
         .await
         .unwrap();
 
-    let replicas = client.replicas_api().get_replicas().await.unwrap();
+    let replicas = client
+        .replicas_api()
+        .get_replicas(None, None, None)
+        .await
+        .unwrap()
+        .entries;
     assert!(replicas.is_empty());
 
-    let nexuses = client.nexuses_api().get_nexuses().await.unwrap();
+    let nexuses = client
+        .nexuses_api()
+        .get_nexuses(None, None, None)
+        .await
+        .unwrap()
+        .entries;
     assert_eq!(nexuses.len(), 0);
     let nexus = client
         .nexuses_api()
@@ -301,7 +333,12 @@ async fn client_test(mayastor: &NodeId, test: &ComposeTest, auth: &bool) {
         .del_node_nexus(&nexus.node, &nexus.uuid.to_string())
         .await
         .unwrap();
-    let nexuses = client.nexuses_api().get_nexuses().await.unwrap();
+    let nexuses = client
+        .nexuses_api()
+        .get_nexuses(None, None, None)
+        .await
+        .unwrap()
+        .entries;
     assert!(nexuses.is_empty());
 
     let volume = client
@@ -328,7 +365,7 @@ async fn client_test(mayastor: &NodeId, test: &ComposeTest, auth: &bool) {
             .unwrap()
     );
 
-    let _watch_volume = WatchResourceId::Volume(volume.uuid.to_string().into());
+    let watch_volume = WatchResourceId::Volume(volume.uuid.to_string().into());
     let callback = url::Url::parse("http://lala/test").unwrap();
 
     let watchers = client
@@ -340,13 +377,53 @@ async fn client_test(mayastor: &NodeId, test: &ComposeTest, auth: &bool) {
 
     client
         .watches_api()
-        .put_watch_volume(&volume.uuid.to_string(), &callback.to_string())
+        .put_watch_volume(
+            "d7b0e09d-4b45-4cbc-9e43-3d2e0fe25c3b",
+            &callback.to_string(),
+            None,
+            None,
+        )
         .await
-        .expect_err("volume does not exist in the store");
+        .expect_err("volume does not exist");
 
+    // the spec of the volume is in the store so its desired state is watched
     client
         .watches_api()
-        .del_watch_volume(&volume.uuid.to_string(), &callback.to_string())
+        .put_watch_volume(
+            &volume.uuid.to_string(),
+            &callback.to_string(),
+            Some(models::WatchType::Desired),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let watchers = client
+        .watches_api()
+        .get_watch_volume(&volume.uuid.to_string())
+        .await
+        .unwrap();
+    assert_eq!(
+        watchers,
+        vec![models::RestWatch::new(
+            callback.to_string(),
+            watch_volume.to_string()
+        )]
+    );
+
+    client
+        .watches_api()
+        .del_watch_volume(
+            &volume.uuid.to_string(),
+            &callback.to_string(),
+            Some(models::WatchType::Desired),
+        )
+        .await
+        .unwrap();
+
+    client
+        .watches_api()
+        .del_watch_volume(&volume.uuid.to_string(), &callback.to_string(), None)
         .await
         .expect_err("Does not exist");
 
@@ -363,7 +440,12 @@ async fn client_test(mayastor: &NodeId, test: &ComposeTest, auth: &bool) {
         .await
         .unwrap();
 
-    let volumes = client.volumes_api().get_volumes().await.unwrap();
+    let volumes = client
+        .volumes_api()
+        .get_volumes(None, None, None, None)
+        .await
+        .unwrap()
+        .entries;
     assert!(volumes.is_empty());
 
     client
@@ -371,14 +453,26 @@ async fn client_test(mayastor: &NodeId, test: &ComposeTest, auth: &bool) {
         .del_node_pool(&pool.node, &pool.id)
         .await
         .unwrap();
-    let pools = client.pools_api().get_pools().await.unwrap();
+    let pools = client
+        .pools_api()
+        .get_pools(None, None, None, None)
+        .await
+        .unwrap()
+        .entries;
     assert!(pools.is_empty());
 
-    client
+    let result = client
         .json_grpc_api()
         .put_node_jsongrpc(mayastor.as_str(), "rpc_get_methods", serde_json::json!({}))
-        .await
-        .expect("Failed to call JSON gRPC method");
+        .await;
+    match auth {
+        true => {
+            result.expect("Failed to call JSON gRPC method");
+        }
+        false => {
+            result.expect_err("Unauthenticated callers may not call any JSON gRPC method");
+        }
+    }
 
     client
         .block_devices_api()
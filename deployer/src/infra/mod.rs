@@ -104,6 +104,9 @@ macro_rules! impl_ctrlp_agents {
                     if let Some(deadline) = &options.node_deadline {
                         binary = binary.with_args(vec!["-d", &deadline.to_string()]);
                     }
+                    if let Some(grace) = &options.node_grace_period {
+                        binary = binary.with_args(vec!["--node-grace-period", &grace.to_string()]);
+                    }
                     if let Some(timeout) = &options.node_conn_timeout {
                         binary = binary.with_args(vec!["--connect", &timeout.to_string()]);
                     }
@@ -119,6 +122,10 @@ macro_rules! impl_ctrlp_agents {
                     if let Some(period) = &options.reconcile_idle_period {
                         binary = binary.with_args(vec!["--reconcile-idle-period", &period.to_string()]);
                     }
+                    if options.jaeger {
+                        let jaeger = format!("jaeger.{}:6831", options.cluster_name);
+                        binary = binary.with_args(vec!["--jaeger", &jaeger]);
+                    }
                 }
                 Ok(cfg.add_container_bin(&name, binary))
             }
@@ -115,6 +115,11 @@ pub struct StartOptions {
     #[structopt(long)]
     pub node_deadline: Option<humantime::Duration>,
 
+    /// Override the node's grace period (Suspect before Offline) for the
+    /// Core Agent
+    #[structopt(long)]
+    pub node_grace_period: Option<humantime::Duration>,
+
     /// Override the node's request timeout
     #[structopt(long)]
     pub node_req_timeout: Option<humantime::Duration>,
@@ -146,6 +151,10 @@ impl StartOptions {
         self.node_deadline = Some(humantime::Duration::from_str(deadline).unwrap());
         self
     }
+    pub fn with_node_grace_period(mut self, grace: &str) -> Self {
+        self.node_grace_period = Some(humantime::Duration::from_str(grace).unwrap());
+        self
+    }
     pub fn with_store_timeout(mut self, timeout: Duration) -> Self {
         self.store_timeout = Some(timeout.into());
         self
@@ -18,6 +18,7 @@ let
   };
   components = { src }: {
     jsongrpc = agent { inherit src; name = "jsongrpc"; };
+    kiiss = agent { inherit src; name = "kiiss"; };
     core = agent { inherit src; name = "core"; };
     rest = agent { inherit src; name = "rest"; suffix = "api"; };
   };
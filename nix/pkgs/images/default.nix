@@ -28,6 +28,7 @@ let
   agent-images = { build }: {
     core = build-agent-image { inherit build; name = "core"; };
     jsongrpc = build-agent-image { inherit build; name = "jsongrpc"; };
+    kiiss = build-agent-image { inherit build; name = "kiiss"; };
   };
 in
 {
@@ -26,7 +26,12 @@ pub trait Nexuses {
         Path(nexus_id): Path<String>,
     ) -> Result<crate::models::Nexus, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn get_nexuses(
-    ) -> Result<Vec<crate::models::Nexus>, crate::apis::RestError<crate::models::RestJsonError>>;
+        Query((max_entries, continuation_token, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<crate::models::Nexuses, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn get_node_nexus(
         Path((node_id, nexus_id)): Path<(String, String)>,
     ) -> Result<crate::models::Nexus, crate::apis::RestError<crate::models::RestJsonError>>;
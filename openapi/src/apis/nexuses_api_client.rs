@@ -36,7 +36,10 @@ pub trait Nexuses: Clone {
     ) -> Result<crate::models::Nexus, Error<crate::models::RestJsonError>>;
     async fn get_nexuses(
         &self,
-    ) -> Result<Vec<crate::models::Nexus>, Error<crate::models::RestJsonError>>;
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Nexuses, Error<crate::models::RestJsonError>>;
     async fn get_node_nexus(
         &self,
         node_id: &str,
@@ -246,7 +249,10 @@ impl Nexuses for NexusesClient {
     }
     async fn get_nexuses(
         &self,
-    ) -> Result<Vec<crate::models::Nexus>, Error<crate::models::RestJsonError>> {
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Nexuses, Error<crate::models::RestJsonError>> {
         let configuration = &self.configuration;
         let local_var_client = &configuration.client;
 
@@ -254,6 +260,18 @@ impl Nexuses for NexusesClient {
         let mut local_var_req_builder =
             local_var_client.request(awc::http::Method::GET, local_var_uri_str.as_str());
 
+        if let Some(ref local_var_str) = max_entries {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("max_entries", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = continuation_token {
+            local_var_req_builder = local_var_req_builder
+                .query(&[("continuation_token", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = state {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("state", &local_var_str.to_string())])?;
+        }
         if let Some(ref local_var_user_agent) = configuration.user_agent {
             local_var_req_builder = local_var_req_builder
                 .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
@@ -270,7 +288,7 @@ impl Nexuses for NexusesClient {
         let local_var_status = local_var_resp.status();
 
         if local_var_status.is_success() {
-            let local_var_content = local_var_resp.json::<Vec<crate::models::Nexus>>().await?;
+            let local_var_content = local_var_resp.json::<crate::models::Nexuses>().await?;
             Ok(local_var_content)
         } else {
             match local_var_resp.json::<crate::models::RestJsonError>().await {
@@ -74,6 +74,19 @@ pub fn configure<T: crate::apis::Nexuses + 'static, A: FromRequest + 'static>(
     );
 }
 
+#[derive(serde::Deserialize)]
+struct get_nexusesQueryParams {
+    /// maximum number of entries to return
+    #[serde(rename = "max_entries", skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<i64>,
+    /// return the entries after this token, ie: the id of the last entry of the previous page
+    #[serde(rename = "continuation_token", skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    /// state which the entries must be in (case insensitive), eg: online
+    #[serde(rename = "state", skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
 async fn del_nexus<T: crate::apis::Nexuses + 'static, A: FromRequest + 'static>(
     _token: A,
     path: Path<String>,
@@ -112,8 +125,16 @@ async fn get_nexus<T: crate::apis::Nexuses + 'static, A: FromRequest + 'static>(
 
 async fn get_nexuses<T: crate::apis::Nexuses + 'static, A: FromRequest + 'static>(
     _token: A,
-) -> Result<Json<Vec<crate::models::Nexus>>, crate::apis::RestError<crate::models::RestJsonError>> {
-    T::get_nexuses().await.map(Json)
+    query: Query<get_nexusesQueryParams>,
+) -> Result<Json<crate::models::Nexuses>, crate::apis::RestError<crate::models::RestJsonError>> {
+    let query = query.into_inner();
+    T::get_nexuses(crate::apis::Query((
+        query.max_entries,
+        query.continuation_token,
+        query.state,
+    )))
+    .await
+    .map(Json)
 }
 
 async fn get_node_nexus<T: crate::apis::Nexuses + 'static, A: FromRequest + 'static>(
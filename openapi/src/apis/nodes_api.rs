@@ -16,6 +16,24 @@ pub trait Nodes {
     async fn get_node(
         Path(id): Path<String>,
     ) -> Result<crate::models::Node, crate::apis::RestError<crate::models::RestJsonError>>;
+    async fn get_node_config_revision(
+        Path((id, kind, revision)): Path<(String, String, i64)>,
+    ) -> Result<
+        crate::models::NodeConfigRevision,
+        crate::apis::RestError<crate::models::RestJsonError>,
+    >;
+    async fn get_node_config_revisions(
+        Path((id, kind)): Path<(String, String)>,
+    ) -> Result<
+        Vec<crate::models::NodeConfigRevision>,
+        crate::apis::RestError<crate::models::RestJsonError>,
+    >;
+    async fn get_node_history(
+        Path(id): Path<String>,
+    ) -> Result<
+        Vec<crate::models::NodeStateTransition>,
+        crate::apis::RestError<crate::models::RestJsonError>,
+    >;
     async fn get_nodes(
     ) -> Result<Vec<crate::models::Node>, crate::apis::RestError<crate::models::RestJsonError>>;
 }
@@ -24,6 +24,24 @@ pub fn configure<T: crate::apis::Nodes + 'static, A: FromRequest + 'static>(
             .guard(actix_web::guard::Get())
             .route(actix_web::web::get().to(get_node::<T, A>)),
     )
+    .service(
+        actix_web::web::resource("/nodes/{id}/configs/{kind}/revisions/{revision}")
+            .name("get_node_config_revision")
+            .guard(actix_web::guard::Get())
+            .route(actix_web::web::get().to(get_node_config_revision::<T, A>)),
+    )
+    .service(
+        actix_web::web::resource("/nodes/{id}/configs/{kind}/revisions")
+            .name("get_node_config_revisions")
+            .guard(actix_web::guard::Get())
+            .route(actix_web::web::get().to(get_node_config_revisions::<T, A>)),
+    )
+    .service(
+        actix_web::web::resource("/nodes/{id}/history")
+            .name("get_node_history")
+            .guard(actix_web::guard::Get())
+            .route(actix_web::web::get().to(get_node_history::<T, A>)),
+    )
     .service(
         actix_web::web::resource("/nodes")
             .name("get_nodes")
@@ -41,6 +59,42 @@ async fn get_node<T: crate::apis::Nodes + 'static, A: FromRequest + 'static>(
         .map(Json)
 }
 
+async fn get_node_config_revision<T: crate::apis::Nodes + 'static, A: FromRequest + 'static>(
+    _token: A,
+    path: Path<(String, String, i64)>,
+) -> Result<
+    Json<crate::models::NodeConfigRevision>,
+    crate::apis::RestError<crate::models::RestJsonError>,
+> {
+    T::get_node_config_revision(crate::apis::Path(path.into_inner()))
+        .await
+        .map(Json)
+}
+
+async fn get_node_config_revisions<T: crate::apis::Nodes + 'static, A: FromRequest + 'static>(
+    _token: A,
+    path: Path<(String, String)>,
+) -> Result<
+    Json<Vec<crate::models::NodeConfigRevision>>,
+    crate::apis::RestError<crate::models::RestJsonError>,
+> {
+    T::get_node_config_revisions(crate::apis::Path(path.into_inner()))
+        .await
+        .map(Json)
+}
+
+async fn get_node_history<T: crate::apis::Nodes + 'static, A: FromRequest + 'static>(
+    _token: A,
+    path: Path<String>,
+) -> Result<
+    Json<Vec<crate::models::NodeStateTransition>>,
+    crate::apis::RestError<crate::models::RestJsonError>,
+> {
+    T::get_node_history(crate::apis::Path(path.into_inner()))
+        .await
+        .map(Json)
+}
+
 async fn get_nodes<T: crate::apis::Nodes + 'static, A: FromRequest + 'static>(
     _token: A,
 ) -> Result<Json<Vec<crate::models::Node>>, crate::apis::RestError<crate::models::RestJsonError>> {
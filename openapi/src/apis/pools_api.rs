@@ -29,7 +29,13 @@ pub trait Pools {
         Path(pool_id): Path<String>,
     ) -> Result<crate::models::Pool, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn get_pools(
-    ) -> Result<Vec<crate::models::Pool>, crate::apis::RestError<crate::models::RestJsonError>>;
+        Query((max_entries, continuation_token, label_selector, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<crate::models::Pools, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn put_node_pool(
         Path((node_id, pool_id)): Path<(String, String)>,
         Body(create_pool_body): Body<crate::models::CreatePoolBody>,
@@ -40,7 +40,11 @@ pub trait Pools: Clone {
     ) -> Result<crate::models::Pool, Error<crate::models::RestJsonError>>;
     async fn get_pools(
         &self,
-    ) -> Result<Vec<crate::models::Pool>, Error<crate::models::RestJsonError>>;
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        label_selector: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Pools, Error<crate::models::RestJsonError>>;
     async fn put_node_pool(
         &self,
         node_id: &str,
@@ -282,7 +286,11 @@ impl Pools for PoolsClient {
     }
     async fn get_pools(
         &self,
-    ) -> Result<Vec<crate::models::Pool>, Error<crate::models::RestJsonError>> {
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        label_selector: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Pools, Error<crate::models::RestJsonError>> {
         let configuration = &self.configuration;
         let local_var_client = &configuration.client;
 
@@ -290,6 +298,22 @@ impl Pools for PoolsClient {
         let mut local_var_req_builder =
             local_var_client.request(awc::http::Method::GET, local_var_uri_str.as_str());
 
+        if let Some(ref local_var_str) = max_entries {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("max_entries", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = continuation_token {
+            local_var_req_builder = local_var_req_builder
+                .query(&[("continuation_token", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = label_selector {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("label_selector", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = state {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("state", &local_var_str.to_string())])?;
+        }
         if let Some(ref local_var_user_agent) = configuration.user_agent {
             local_var_req_builder = local_var_req_builder
                 .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
@@ -306,7 +330,7 @@ impl Pools for PoolsClient {
         let local_var_status = local_var_resp.status();
 
         if local_var_status.is_success() {
-            let local_var_content = local_var_resp.json::<Vec<crate::models::Pool>>().await?;
+            let local_var_content = local_var_resp.json::<crate::models::Pools>().await?;
             Ok(local_var_content)
         } else {
             match local_var_resp.json::<crate::models::RestJsonError>().await {
@@ -62,6 +62,22 @@ pub fn configure<T: crate::apis::Pools + 'static, A: FromRequest + 'static>(
     );
 }
 
+#[derive(serde::Deserialize)]
+struct get_poolsQueryParams {
+    /// maximum number of entries to return
+    #[serde(rename = "max_entries", skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<i64>,
+    /// return the entries after this token, ie: the id of the last entry of the previous page
+    #[serde(rename = "continuation_token", skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    /// comma separated list of labels which the entries must have, eg: zone=eu-1,tier=ssd
+    #[serde(rename = "label_selector", skip_serializing_if = "Option::is_none")]
+    pub label_selector: Option<String>,
+    /// state which the entries must be in (case insensitive), eg: online
+    #[serde(rename = "state", skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
 async fn del_node_pool<T: crate::apis::Pools + 'static, A: FromRequest + 'static>(
     _token: A,
     path: Path<(String, String)>,
@@ -109,8 +125,17 @@ async fn get_pool<T: crate::apis::Pools + 'static, A: FromRequest + 'static>(
 
 async fn get_pools<T: crate::apis::Pools + 'static, A: FromRequest + 'static>(
     _token: A,
-) -> Result<Json<Vec<crate::models::Pool>>, crate::apis::RestError<crate::models::RestJsonError>> {
-    T::get_pools().await.map(Json)
+    query: Query<get_poolsQueryParams>,
+) -> Result<Json<crate::models::Pools>, crate::apis::RestError<crate::models::RestJsonError>> {
+    let query = query.into_inner();
+    T::get_pools(crate::apis::Query((
+        query.max_entries,
+        query.continuation_token,
+        query.label_selector,
+        query.state,
+    )))
+    .await
+    .map(Json)
 }
 
 async fn put_node_pool<T: crate::apis::Pools + 'static, A: FromRequest + 'static>(
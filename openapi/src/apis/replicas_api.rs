@@ -38,7 +38,12 @@ pub trait Replicas {
         Path(id): Path<String>,
     ) -> Result<crate::models::Replica, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn get_replicas(
-    ) -> Result<Vec<crate::models::Replica>, crate::apis::RestError<crate::models::RestJsonError>>;
+        Query((max_entries, continuation_token, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<crate::models::Replicas, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn put_node_pool_replica(
         Path((node_id, pool_id, replica_id)): Path<(String, String, String)>,
         Body(create_replica_body): Body<crate::models::CreateReplicaBody>,
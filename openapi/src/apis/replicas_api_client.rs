@@ -62,7 +62,10 @@ pub trait Replicas: Clone {
     ) -> Result<crate::models::Replica, Error<crate::models::RestJsonError>>;
     async fn get_replicas(
         &self,
-    ) -> Result<Vec<crate::models::Replica>, Error<crate::models::RestJsonError>>;
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Replicas, Error<crate::models::RestJsonError>>;
     async fn put_node_pool_replica(
         &self,
         node_id: &str,
@@ -477,7 +480,10 @@ impl Replicas for ReplicasClient {
     }
     async fn get_replicas(
         &self,
-    ) -> Result<Vec<crate::models::Replica>, Error<crate::models::RestJsonError>> {
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Replicas, Error<crate::models::RestJsonError>> {
         let configuration = &self.configuration;
         let local_var_client = &configuration.client;
 
@@ -485,6 +491,18 @@ impl Replicas for ReplicasClient {
         let mut local_var_req_builder =
             local_var_client.request(awc::http::Method::GET, local_var_uri_str.as_str());
 
+        if let Some(ref local_var_str) = max_entries {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("max_entries", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = continuation_token {
+            local_var_req_builder = local_var_req_builder
+                .query(&[("continuation_token", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = state {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("state", &local_var_str.to_string())])?;
+        }
         if let Some(ref local_var_user_agent) = configuration.user_agent {
             local_var_req_builder = local_var_req_builder
                 .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
@@ -501,7 +519,7 @@ impl Replicas for ReplicasClient {
         let local_var_status = local_var_resp.status();
 
         if local_var_status.is_success() {
-            let local_var_content = local_var_resp.json::<Vec<crate::models::Replica>>().await?;
+            let local_var_content = local_var_resp.json::<crate::models::Replicas>().await?;
             Ok(local_var_content)
         } else {
             match local_var_resp.json::<crate::models::RestJsonError>().await {
@@ -100,6 +100,19 @@ pub fn configure<T: crate::apis::Replicas + 'static, A: FromRequest + 'static>(
     );
 }
 
+#[derive(serde::Deserialize)]
+struct get_replicasQueryParams {
+    /// maximum number of entries to return
+    #[serde(rename = "max_entries", skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<i64>,
+    /// return the entries after this token, ie: the id of the last entry of the previous page
+    #[serde(rename = "continuation_token", skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    /// state which the entries must be in (case insensitive), eg: online
+    #[serde(rename = "state", skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
 async fn del_node_pool_replica<T: crate::apis::Replicas + 'static, A: FromRequest + 'static>(
     _token: A,
     path: Path<(String, String, String)>,
@@ -179,9 +192,16 @@ async fn get_replica<T: crate::apis::Replicas + 'static, A: FromRequest + 'stati
 
 async fn get_replicas<T: crate::apis::Replicas + 'static, A: FromRequest + 'static>(
     _token: A,
-) -> Result<Json<Vec<crate::models::Replica>>, crate::apis::RestError<crate::models::RestJsonError>>
-{
-    T::get_replicas().await.map(Json)
+    query: Query<get_replicasQueryParams>,
+) -> Result<Json<crate::models::Replicas>, crate::apis::RestError<crate::models::RestJsonError>> {
+    let query = query.into_inner();
+    T::get_replicas(crate::apis::Query((
+        query.max_entries,
+        query.continuation_token,
+        query.state,
+    )))
+    .await
+    .map(Json)
 }
 
 async fn put_node_pool_replica<T: crate::apis::Replicas + 'static, A: FromRequest + 'static>(
@@ -14,5 +14,11 @@ use actix_web::web::Json;
 #[async_trait::async_trait]
 pub trait Specs {
     async fn get_specs(
+        Query((max_entries, continuation_token, label_selector, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        )>,
     ) -> Result<crate::models::Specs, crate::apis::RestError<crate::models::RestJsonError>>;
 }
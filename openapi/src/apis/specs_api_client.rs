@@ -19,12 +19,24 @@ impl SpecsClient {
 #[async_trait::async_trait(?Send)]
 #[dyn_clonable::clonable]
 pub trait Specs: Clone {
-    async fn get_specs(&self) -> Result<crate::models::Specs, Error<crate::models::RestJsonError>>;
+    async fn get_specs(
+        &self,
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        label_selector: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Specs, Error<crate::models::RestJsonError>>;
 }
 
 #[async_trait::async_trait(?Send)]
 impl Specs for SpecsClient {
-    async fn get_specs(&self) -> Result<crate::models::Specs, Error<crate::models::RestJsonError>> {
+    async fn get_specs(
+        &self,
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        label_selector: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Specs, Error<crate::models::RestJsonError>> {
         let configuration = &self.configuration;
         let local_var_client = &configuration.client;
 
@@ -32,6 +44,22 @@ impl Specs for SpecsClient {
         let mut local_var_req_builder =
             local_var_client.request(awc::http::Method::GET, local_var_uri_str.as_str());
 
+        if let Some(ref local_var_str) = max_entries {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("max_entries", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = continuation_token {
+            local_var_req_builder = local_var_req_builder
+                .query(&[("continuation_token", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = label_selector {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("label_selector", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = state {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("state", &local_var_str.to_string())])?;
+        }
         if let Some(ref local_var_user_agent) = configuration.user_agent {
             local_var_req_builder = local_var_req_builder
                 .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
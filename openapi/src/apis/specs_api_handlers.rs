@@ -26,8 +26,33 @@ pub fn configure<T: crate::apis::Specs + 'static, A: FromRequest + 'static>(
     );
 }
 
+#[derive(serde::Deserialize)]
+struct get_specsQueryParams {
+    /// maximum number of entries to return
+    #[serde(rename = "max_entries", skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<i64>,
+    /// return the entries after this token, ie: the continuation_token of the previous page
+    #[serde(rename = "continuation_token", skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    /// comma separated list of labels which the entries must have, eg: zone=eu-1,tier=ssd
+    #[serde(rename = "label_selector", skip_serializing_if = "Option::is_none")]
+    pub label_selector: Option<String>,
+    /// state which the entries must be in (case insensitive), eg: online
+    #[serde(rename = "state", skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
 async fn get_specs<T: crate::apis::Specs + 'static, A: FromRequest + 'static>(
     _token: A,
+    query: Query<get_specsQueryParams>,
 ) -> Result<Json<crate::models::Specs>, crate::apis::RestError<crate::models::RestJsonError>> {
-    T::get_specs().await.map(Json)
+    let query = query.into_inner();
+    T::get_specs(crate::apis::Query((
+        query.max_entries,
+        query.continuation_token,
+        query.label_selector,
+        query.state,
+    )))
+    .await
+    .map(Json)
 }
@@ -29,7 +29,13 @@ pub trait Volumes {
         Path(volume_id): Path<String>,
     ) -> Result<crate::models::Volume, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn get_volumes(
-    ) -> Result<Vec<crate::models::Volume>, crate::apis::RestError<crate::models::RestJsonError>>;
+        Query((max_entries, continuation_token, label_selector, state)): Query<(
+            Option<i64>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        )>,
+    ) -> Result<crate::models::Volumes, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn put_volume(
         Path(volume_id): Path<String>,
         Body(create_volume_body): Body<crate::models::CreateVolumeBody>,
@@ -36,7 +36,11 @@ pub trait Volumes: Clone {
     ) -> Result<crate::models::Volume, Error<crate::models::RestJsonError>>;
     async fn get_volumes(
         &self,
-    ) -> Result<Vec<crate::models::Volume>, Error<crate::models::RestJsonError>>;
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        label_selector: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Volumes, Error<crate::models::RestJsonError>>;
     async fn put_volume(
         &self,
         volume_id: &str,
@@ -277,7 +281,11 @@ impl Volumes for VolumesClient {
     }
     async fn get_volumes(
         &self,
-    ) -> Result<Vec<crate::models::Volume>, Error<crate::models::RestJsonError>> {
+        max_entries: Option<i64>,
+        continuation_token: Option<&str>,
+        label_selector: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<crate::models::Volumes, Error<crate::models::RestJsonError>> {
         let configuration = &self.configuration;
         let local_var_client = &configuration.client;
 
@@ -285,6 +293,22 @@ impl Volumes for VolumesClient {
         let mut local_var_req_builder =
             local_var_client.request(awc::http::Method::GET, local_var_uri_str.as_str());
 
+        if let Some(ref local_var_str) = max_entries {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("max_entries", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = continuation_token {
+            local_var_req_builder = local_var_req_builder
+                .query(&[("continuation_token", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = label_selector {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("label_selector", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = state {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("state", &local_var_str.to_string())])?;
+        }
         if let Some(ref local_var_user_agent) = configuration.user_agent {
             local_var_req_builder = local_var_req_builder
                 .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
@@ -301,7 +325,7 @@ impl Volumes for VolumesClient {
         let local_var_status = local_var_resp.status();
 
         if local_var_status.is_success() {
-            let local_var_content = local_var_resp.json::<Vec<crate::models::Volume>>().await?;
+            let local_var_content = local_var_resp.json::<crate::models::Volumes>().await?;
             Ok(local_var_content)
         } else {
             match local_var_resp.json::<crate::models::RestJsonError>().await {
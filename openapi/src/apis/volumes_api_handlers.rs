@@ -68,6 +68,22 @@ pub fn configure<T: crate::apis::Volumes + 'static, A: FromRequest + 'static>(
     );
 }
 
+#[derive(serde::Deserialize)]
+struct get_volumesQueryParams {
+    /// maximum number of entries to return
+    #[serde(rename = "max_entries", skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<i64>,
+    /// return the entries after this token, ie: the id of the last entry of the previous page
+    #[serde(rename = "continuation_token", skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    /// comma separated list of labels which the entries must have, eg: zone=eu-1,tier=ssd
+    #[serde(rename = "label_selector", skip_serializing_if = "Option::is_none")]
+    pub label_selector: Option<String>,
+    /// state which the entries must be in (case insensitive), eg: online
+    #[serde(rename = "state", skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+}
+
 async fn del_share<T: crate::apis::Volumes + 'static, A: FromRequest + 'static>(
     _token: A,
     path: Path<String>,
@@ -116,9 +132,17 @@ async fn get_volume<T: crate::apis::Volumes + 'static, A: FromRequest + 'static>
 
 async fn get_volumes<T: crate::apis::Volumes + 'static, A: FromRequest + 'static>(
     _token: A,
-) -> Result<Json<Vec<crate::models::Volume>>, crate::apis::RestError<crate::models::RestJsonError>>
-{
-    T::get_volumes().await.map(Json)
+    query: Query<get_volumesQueryParams>,
+) -> Result<Json<crate::models::Volumes>, crate::apis::RestError<crate::models::RestJsonError>> {
+    let query = query.into_inner();
+    T::get_volumes(crate::apis::Query((
+        query.max_entries,
+        query.continuation_token,
+        query.label_selector,
+        query.state,
+    )))
+    .await
+    .map(Json)
 }
 
 async fn put_volume<T: crate::apis::Volumes + 'static, A: FromRequest + 'static>(
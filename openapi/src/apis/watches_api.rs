@@ -15,13 +15,34 @@ use actix_web::web::Json;
 pub trait Watches {
     async fn del_watch_volume(
         Path(volume_id): Path<String>,
-        Query(callback): Query<url::Url>,
+        Query((callback, watch_type)): Query<(url::Url, Option<crate::models::WatchType>)>,
     ) -> Result<(), crate::apis::RestError<crate::models::RestJsonError>>;
+    async fn del_watch_volumes(
+        Query((callback, watch_type)): Query<(url::Url, Option<crate::models::WatchType>)>,
+    ) -> Result<(), crate::apis::RestError<crate::models::RestJsonError>>;
+    async fn get_watch_events(
+        Query((resource, revision)): Query<(Option<String>, Option<i64>)>,
+    ) -> Result<String, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn get_watch_volume(
         Path(volume_id): Path<String>,
     ) -> Result<Vec<crate::models::RestWatch>, crate::apis::RestError<crate::models::RestJsonError>>;
+    async fn get_watch_volumes(
+    ) -> Result<Vec<crate::models::RestWatch>, crate::apis::RestError<crate::models::RestJsonError>>;
     async fn put_watch_volume(
         Path(volume_id): Path<String>,
-        Query(callback): Query<url::Url>,
+        Query((callback, watch_type, secret)): Query<(
+            url::Url,
+            Option<crate::models::WatchType>,
+            Option<String>,
+        )>,
+    ) -> Result<(), crate::apis::RestError<crate::models::RestJsonError>>;
+    async fn put_watch_volumes(
+        Query((callback, watch_type, secret, label_selector, state)): Query<(
+            url::Url,
+            Option<crate::models::WatchType>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        )>,
     ) -> Result<(), crate::apis::RestError<crate::models::RestJsonError>>;
 }
@@ -23,15 +23,39 @@ pub trait Watches: Clone {
         &self,
         volume_id: &str,
         callback: &str,
+        watch_type: Option<crate::models::WatchType>,
     ) -> Result<(), Error<crate::models::RestJsonError>>;
+    async fn del_watch_volumes(
+        &self,
+        callback: &str,
+        watch_type: Option<crate::models::WatchType>,
+    ) -> Result<(), Error<crate::models::RestJsonError>>;
+    async fn get_watch_events(
+        &self,
+        resource: Option<&str>,
+        revision: Option<i64>,
+    ) -> Result<String, Error<crate::models::RestJsonError>>;
     async fn get_watch_volume(
         &self,
         volume_id: &str,
     ) -> Result<Vec<crate::models::RestWatch>, Error<crate::models::RestJsonError>>;
+    async fn get_watch_volumes(
+        &self,
+    ) -> Result<Vec<crate::models::RestWatch>, Error<crate::models::RestJsonError>>;
     async fn put_watch_volume(
         &self,
         volume_id: &str,
         callback: &str,
+        watch_type: Option<crate::models::WatchType>,
+        secret: Option<&str>,
+    ) -> Result<(), Error<crate::models::RestJsonError>>;
+    async fn put_watch_volumes(
+        &self,
+        callback: &str,
+        watch_type: Option<crate::models::WatchType>,
+        secret: Option<&str>,
+        label_selector: Option<&str>,
+        state: Option<&str>,
     ) -> Result<(), Error<crate::models::RestJsonError>>;
 }
 
@@ -41,6 +65,7 @@ impl Watches for WatchesClient {
         &self,
         volume_id: &str,
         callback: &str,
+        watch_type: Option<crate::models::WatchType>,
     ) -> Result<(), Error<crate::models::RestJsonError>> {
         let configuration = &self.configuration;
         let local_var_client = &configuration.client;
@@ -55,6 +80,58 @@ impl Watches for WatchesClient {
 
         local_var_req_builder =
             local_var_req_builder.query(&[("callback", &callback.to_string())])?;
+        if let Some(ref local_var_str) = watch_type {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("watch_type", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_user_agent) = configuration.user_agent {
+            local_var_req_builder = local_var_req_builder
+                .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
+        }
+        if let Some(ref local_var_token) = configuration.bearer_access_token {
+            local_var_req_builder = local_var_req_builder.bearer_auth(local_var_token.to_owned());
+        };
+        let mut local_var_resp = if configuration.trace_requests {
+            local_var_req_builder.trace_request().send().await
+        } else {
+            local_var_req_builder.send().await
+        }?;
+
+        let local_var_status = local_var_resp.status();
+
+        if local_var_status.is_success() {
+            Ok(())
+        } else {
+            match local_var_resp.json::<crate::models::RestJsonError>().await {
+                Ok(error) => Err(Error::ResponseError(ResponseContent {
+                    status: local_var_status,
+                    error,
+                })),
+                Err(_) => Err(Error::ResponseUnexpected(ResponseContentUnexpected {
+                    status: local_var_status,
+                    text: local_var_resp.json().await?,
+                })),
+            }
+        }
+    }
+    async fn del_watch_volumes(
+        &self,
+        callback: &str,
+        watch_type: Option<crate::models::WatchType>,
+    ) -> Result<(), Error<crate::models::RestJsonError>> {
+        let configuration = &self.configuration;
+        let local_var_client = &configuration.client;
+
+        let local_var_uri_str = format!("{}/watches/volumes", configuration.base_path);
+        let mut local_var_req_builder =
+            local_var_client.request(awc::http::Method::DELETE, local_var_uri_str.as_str());
+
+        local_var_req_builder =
+            local_var_req_builder.query(&[("callback", &callback.to_string())])?;
+        if let Some(ref local_var_str) = watch_type {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("watch_type", &local_var_str.to_string())])?;
+        }
         if let Some(ref local_var_user_agent) = configuration.user_agent {
             local_var_req_builder = local_var_req_builder
                 .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
@@ -85,6 +162,57 @@ impl Watches for WatchesClient {
             }
         }
     }
+    async fn get_watch_events(
+        &self,
+        resource: Option<&str>,
+        revision: Option<i64>,
+    ) -> Result<String, Error<crate::models::RestJsonError>> {
+        let configuration = &self.configuration;
+        let local_var_client = &configuration.client;
+
+        let local_var_uri_str = format!("{}/watches/events", configuration.base_path);
+        let mut local_var_req_builder =
+            local_var_client.request(awc::http::Method::GET, local_var_uri_str.as_str());
+
+        if let Some(ref local_var_str) = resource {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("resource", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = revision {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("revision", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_user_agent) = configuration.user_agent {
+            local_var_req_builder = local_var_req_builder
+                .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
+        }
+        if let Some(ref local_var_token) = configuration.bearer_access_token {
+            local_var_req_builder = local_var_req_builder.bearer_auth(local_var_token.to_owned());
+        };
+        let mut local_var_resp = if configuration.trace_requests {
+            local_var_req_builder.trace_request().send().await
+        } else {
+            local_var_req_builder.send().await
+        }?;
+
+        let local_var_status = local_var_resp.status();
+
+        if local_var_status.is_success() {
+            let local_var_content = local_var_resp.json::<String>().await?;
+            Ok(local_var_content)
+        } else {
+            match local_var_resp.json::<crate::models::RestJsonError>().await {
+                Ok(error) => Err(Error::ResponseError(ResponseContent {
+                    status: local_var_status,
+                    error,
+                })),
+                Err(_) => Err(Error::ResponseUnexpected(ResponseContentUnexpected {
+                    status: local_var_status,
+                    text: local_var_resp.json().await?,
+                })),
+            }
+        }
+    }
     async fn get_watch_volume(
         &self,
         volume_id: &str,
@@ -133,10 +261,55 @@ impl Watches for WatchesClient {
             }
         }
     }
+    async fn get_watch_volumes(
+        &self,
+    ) -> Result<Vec<crate::models::RestWatch>, Error<crate::models::RestJsonError>> {
+        let configuration = &self.configuration;
+        let local_var_client = &configuration.client;
+
+        let local_var_uri_str = format!("{}/watches/volumes", configuration.base_path);
+        let mut local_var_req_builder =
+            local_var_client.request(awc::http::Method::GET, local_var_uri_str.as_str());
+
+        if let Some(ref local_var_user_agent) = configuration.user_agent {
+            local_var_req_builder = local_var_req_builder
+                .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
+        }
+        if let Some(ref local_var_token) = configuration.bearer_access_token {
+            local_var_req_builder = local_var_req_builder.bearer_auth(local_var_token.to_owned());
+        };
+        let mut local_var_resp = if configuration.trace_requests {
+            local_var_req_builder.trace_request().send().await
+        } else {
+            local_var_req_builder.send().await
+        }?;
+
+        let local_var_status = local_var_resp.status();
+
+        if local_var_status.is_success() {
+            let local_var_content = local_var_resp
+                .json::<Vec<crate::models::RestWatch>>()
+                .await?;
+            Ok(local_var_content)
+        } else {
+            match local_var_resp.json::<crate::models::RestJsonError>().await {
+                Ok(error) => Err(Error::ResponseError(ResponseContent {
+                    status: local_var_status,
+                    error,
+                })),
+                Err(_) => Err(Error::ResponseUnexpected(ResponseContentUnexpected {
+                    status: local_var_status,
+                    text: local_var_resp.json().await?,
+                })),
+            }
+        }
+    }
     async fn put_watch_volume(
         &self,
         volume_id: &str,
         callback: &str,
+        watch_type: Option<crate::models::WatchType>,
+        secret: Option<&str>,
     ) -> Result<(), Error<crate::models::RestJsonError>> {
         let configuration = &self.configuration;
         let local_var_client = &configuration.client;
@@ -151,6 +324,77 @@ impl Watches for WatchesClient {
 
         local_var_req_builder =
             local_var_req_builder.query(&[("callback", &callback.to_string())])?;
+        if let Some(ref local_var_str) = watch_type {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("watch_type", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = secret {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("secret", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_user_agent) = configuration.user_agent {
+            local_var_req_builder = local_var_req_builder
+                .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
+        }
+        if let Some(ref local_var_token) = configuration.bearer_access_token {
+            local_var_req_builder = local_var_req_builder.bearer_auth(local_var_token.to_owned());
+        };
+        let mut local_var_resp = if configuration.trace_requests {
+            local_var_req_builder.trace_request().send().await
+        } else {
+            local_var_req_builder.send().await
+        }?;
+
+        let local_var_status = local_var_resp.status();
+
+        if local_var_status.is_success() {
+            Ok(())
+        } else {
+            match local_var_resp.json::<crate::models::RestJsonError>().await {
+                Ok(error) => Err(Error::ResponseError(ResponseContent {
+                    status: local_var_status,
+                    error,
+                })),
+                Err(_) => Err(Error::ResponseUnexpected(ResponseContentUnexpected {
+                    status: local_var_status,
+                    text: local_var_resp.json().await?,
+                })),
+            }
+        }
+    }
+    async fn put_watch_volumes(
+        &self,
+        callback: &str,
+        watch_type: Option<crate::models::WatchType>,
+        secret: Option<&str>,
+        label_selector: Option<&str>,
+        state: Option<&str>,
+    ) -> Result<(), Error<crate::models::RestJsonError>> {
+        let configuration = &self.configuration;
+        let local_var_client = &configuration.client;
+
+        let local_var_uri_str = format!("{}/watches/volumes", configuration.base_path);
+        let mut local_var_req_builder =
+            local_var_client.request(awc::http::Method::PUT, local_var_uri_str.as_str());
+
+        local_var_req_builder =
+            local_var_req_builder.query(&[("callback", &callback.to_string())])?;
+        if let Some(ref local_var_str) = watch_type {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("watch_type", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = secret {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("secret", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = label_selector {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("label_selector", &local_var_str.to_string())])?;
+        }
+        if let Some(ref local_var_str) = state {
+            local_var_req_builder =
+                local_var_req_builder.query(&[("state", &local_var_str.to_string())])?;
+        }
         if let Some(ref local_var_user_agent) = configuration.user_agent {
             local_var_req_builder = local_var_req_builder
                 .insert_header((awc::http::header::USER_AGENT, local_var_user_agent.clone()));
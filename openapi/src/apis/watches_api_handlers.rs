@@ -24,31 +24,100 @@ pub fn configure<T: crate::apis::Watches + 'static, A: FromRequest + 'static>(
             .guard(actix_web::guard::Delete())
             .route(actix_web::web::delete().to(del_watch_volume::<T, A>)),
     )
+    .service(
+        actix_web::web::resource("/watches/volumes")
+            .name("del_watch_volumes")
+            .guard(actix_web::guard::Delete())
+            .route(actix_web::web::delete().to(del_watch_volumes::<T, A>)),
+    )
+    .service(
+        actix_web::web::resource("/watches/events")
+            .name("get_watch_events")
+            .guard(actix_web::guard::Get())
+            .route(actix_web::web::get().to(get_watch_events::<T, A>)),
+    )
     .service(
         actix_web::web::resource("/watches/volumes/{volume_id}")
             .name("get_watch_volume")
             .guard(actix_web::guard::Get())
             .route(actix_web::web::get().to(get_watch_volume::<T, A>)),
     )
+    .service(
+        actix_web::web::resource("/watches/volumes")
+            .name("get_watch_volumes")
+            .guard(actix_web::guard::Get())
+            .route(actix_web::web::get().to(get_watch_volumes::<T, A>)),
+    )
     .service(
         actix_web::web::resource("/watches/volumes/{volume_id}")
             .name("put_watch_volume")
             .guard(actix_web::guard::Put())
             .route(actix_web::web::put().to(put_watch_volume::<T, A>)),
+    )
+    .service(
+        actix_web::web::resource("/watches/volumes")
+            .name("put_watch_volumes")
+            .guard(actix_web::guard::Put())
+            .route(actix_web::web::put().to(put_watch_volumes::<T, A>)),
     );
 }
 
 #[derive(serde::Deserialize)]
 struct del_watch_volumeQueryParams {
-    /// URL callback
+    /// URL callback, or nats:<subject> to publish the notifications on a message bus subject
+    #[serde(rename = "callback")]
+    pub callback: url::Url,
+    /// type of state to watch: desired (spec), actual or all (default)
+    #[serde(rename = "watch_type", skip_serializing_if = "Option::is_none")]
+    pub watch_type: Option<crate::models::WatchType>,
+}
+#[derive(serde::Deserialize)]
+struct del_watch_volumesQueryParams {
+    /// URL callback, or nats:<subject> to publish the notifications on a message bus subject
     #[serde(rename = "callback")]
     pub callback: url::Url,
+    /// type of state to watch: desired (spec), actual or all (default)
+    #[serde(rename = "watch_type", skip_serializing_if = "Option::is_none")]
+    pub watch_type: Option<crate::models::WatchType>,
+}
+#[derive(serde::Deserialize)]
+struct get_watch_eventsQueryParams {
+    /// resource kind (eg: volumes) or resource (eg: volumes/{volume_id}) to watch, otherwise all the resources are watched
+    #[serde(rename = "resource", skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// revision of the last received event, after which the stream is resumed, overriding the Last-Event-ID header
+    #[serde(rename = "revision", skip_serializing_if = "Option::is_none")]
+    pub revision: Option<i64>,
 }
 #[derive(serde::Deserialize)]
 struct put_watch_volumeQueryParams {
-    /// URL callback
+    /// URL callback, or nats:<subject> to publish the notifications on a message bus subject
+    #[serde(rename = "callback")]
+    pub callback: url::Url,
+    /// type of state to watch: desired (spec), actual or all (default)
+    #[serde(rename = "watch_type", skip_serializing_if = "Option::is_none")]
+    pub watch_type: Option<crate::models::WatchType>,
+    /// secret shared with the watcher, used to sign the notifications
+    #[serde(rename = "secret", skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+}
+#[derive(serde::Deserialize)]
+struct put_watch_volumesQueryParams {
+    /// URL callback, or nats:<subject> to publish the notifications on a message bus subject
     #[serde(rename = "callback")]
     pub callback: url::Url,
+    /// type of state to watch: desired (spec), actual or all (default)
+    #[serde(rename = "watch_type", skip_serializing_if = "Option::is_none")]
+    pub watch_type: Option<crate::models::WatchType>,
+    /// secret shared with the watcher, used to sign the notifications
+    #[serde(rename = "secret", skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// comma separated list of labels which the notified volumes must have, eg: zone=eu-1,tier=ssd
+    #[serde(rename = "label_selector", skip_serializing_if = "Option::is_none")]
+    pub label_selector: Option<String>,
+    /// state which the notified volumes must be in (case insensitive), eg: online
+    #[serde(rename = "state", skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
 }
 
 async fn del_watch_volume<T: crate::apis::Watches + 'static, A: FromRequest + 'static>(
@@ -56,14 +125,35 @@ async fn del_watch_volume<T: crate::apis::Watches + 'static, A: FromRequest + 's
     path: Path<String>,
     query: Query<del_watch_volumeQueryParams>,
 ) -> Result<Json<()>, crate::apis::RestError<crate::models::RestJsonError>> {
+    let query = query.into_inner();
     T::del_watch_volume(
         crate::apis::Path(path.into_inner()),
-        crate::apis::Query(query.into_inner().callback),
+        crate::apis::Query((query.callback, query.watch_type)),
     )
     .await
     .map(Json)
 }
 
+async fn del_watch_volumes<T: crate::apis::Watches + 'static, A: FromRequest + 'static>(
+    _token: A,
+    query: Query<del_watch_volumesQueryParams>,
+) -> Result<Json<()>, crate::apis::RestError<crate::models::RestJsonError>> {
+    let query = query.into_inner();
+    T::del_watch_volumes(crate::apis::Query((query.callback, query.watch_type)))
+        .await
+        .map(Json)
+}
+
+async fn get_watch_events<T: crate::apis::Watches + 'static, A: FromRequest + 'static>(
+    _token: A,
+    query: Query<get_watch_eventsQueryParams>,
+) -> Result<Json<String>, crate::apis::RestError<crate::models::RestJsonError>> {
+    let query = query.into_inner();
+    T::get_watch_events(crate::apis::Query((query.resource, query.revision)))
+        .await
+        .map(Json)
+}
+
 async fn get_watch_volume<T: crate::apis::Watches + 'static, A: FromRequest + 'static>(
     _token: A,
     path: Path<String>,
@@ -74,15 +164,39 @@ async fn get_watch_volume<T: crate::apis::Watches + 'static, A: FromRequest + 's
         .map(Json)
 }
 
+async fn get_watch_volumes<T: crate::apis::Watches + 'static, A: FromRequest + 'static>(
+    _token: A,
+) -> Result<Json<Vec<crate::models::RestWatch>>, crate::apis::RestError<crate::models::RestJsonError>>
+{
+    T::get_watch_volumes().await.map(Json)
+}
+
 async fn put_watch_volume<T: crate::apis::Watches + 'static, A: FromRequest + 'static>(
     _token: A,
     path: Path<String>,
     query: Query<put_watch_volumeQueryParams>,
 ) -> Result<Json<()>, crate::apis::RestError<crate::models::RestJsonError>> {
+    let query = query.into_inner();
     T::put_watch_volume(
         crate::apis::Path(path.into_inner()),
-        crate::apis::Query(query.into_inner().callback),
+        crate::apis::Query((query.callback, query.watch_type, query.secret)),
     )
     .await
     .map(Json)
 }
+
+async fn put_watch_volumes<T: crate::apis::Watches + 'static, A: FromRequest + 'static>(
+    _token: A,
+    query: Query<put_watch_volumesQueryParams>,
+) -> Result<Json<()>, crate::apis::RestError<crate::models::RestJsonError>> {
+    let query = query.into_inner();
+    T::put_watch_volumes(crate::apis::Query((
+        query.callback,
+        query.watch_type,
+        query.secret,
+        query.label_selector,
+        query.state,
+    )))
+    .await
+    .map(Json)
+}
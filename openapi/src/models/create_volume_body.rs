@@ -28,6 +28,9 @@ pub struct CreateVolumeBody {
     /// size of the volume in bytes
     #[serde(rename = "size")]
     pub size: i64,
+    /// thin provisioning of the replicas
+    #[serde(rename = "thin", skip_serializing_if = "Option::is_none")]
+    pub thin: Option<bool>,
     /// Volume topology used to determine how to place/distribute the data.  Should either be labelled or explicit, not both.  If neither is used then the control plane will select from all available resources.
     #[serde(rename = "topology")]
     pub topology: crate::models::Topology,
@@ -45,6 +48,7 @@ impl CreateVolumeBody {
             policy: policy.into(),
             replicas: replicas.into(),
             size: size.into(),
+            thin: None,
             topology: topology.into(),
         }
     }
@@ -53,12 +57,14 @@ impl CreateVolumeBody {
         policy: impl Into<crate::models::VolumeHealPolicy>,
         replicas: impl Into<i32>,
         size: impl Into<i64>,
+        thin: impl Into<Option<bool>>,
         topology: impl Into<crate::models::Topology>,
     ) -> CreateVolumeBody {
         CreateVolumeBody {
             policy: policy.into(),
             replicas: replicas.into(),
             size: size.into(),
+            thin: thin.into(),
             topology: topology.into(),
         }
     }
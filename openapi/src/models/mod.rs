@@ -30,10 +30,16 @@ pub mod nexus_spec_operation;
 pub use self::nexus_spec_operation::NexusSpecOperation;
 pub mod nexus_state;
 pub use self::nexus_state::NexusState;
+pub mod nexuses;
+pub use self::nexuses::Nexuses;
 pub mod node;
 pub use self::node::Node;
+pub mod node_config_revision;
+pub use self::node_config_revision::NodeConfigRevision;
 pub mod node_state;
 pub use self::node_state::NodeState;
+pub mod node_state_transition;
+pub use self::node_state_transition::NodeStateTransition;
 pub mod node_topology;
 pub use self::node_topology::NodeTopology;
 pub mod pool;
@@ -46,6 +52,8 @@ pub mod pool_state;
 pub use self::pool_state::PoolState;
 pub mod pool_topology;
 pub use self::pool_topology::PoolTopology;
+pub mod pools;
+pub use self::pools::Pools;
 pub mod protocol;
 pub use self::protocol::Protocol;
 pub mod replica;
@@ -60,6 +68,8 @@ pub mod replica_spec_owners;
 pub use self::replica_spec_owners::ReplicaSpecOwners;
 pub mod replica_state;
 pub use self::replica_state::ReplicaState;
+pub mod replicas;
+pub use self::replicas::Replicas;
 pub mod rest_json_error;
 pub use self::rest_json_error::RestJsonError;
 pub mod rest_watch;
@@ -82,5 +92,17 @@ pub mod volume_spec_operation;
 pub use self::volume_spec_operation::VolumeSpecOperation;
 pub mod volume_state;
 pub use self::volume_state::VolumeState;
+pub mod volumes;
+pub use self::volumes::Volumes;
 pub mod watch_callback;
 pub use self::watch_callback::WatchCallback;
+pub mod watch_delivery;
+pub use self::watch_delivery::WatchDelivery;
+pub mod watch_delivery_status;
+pub use self::watch_delivery_status::WatchDeliveryStatus;
+pub mod watch_event_type;
+pub use self::watch_event_type::WatchEventType;
+pub mod watch_notification;
+pub use self::watch_notification::WatchNotification;
+pub mod watch_type;
+pub use self::watch_type::WatchType;
@@ -0,0 +1,48 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::new_without_default,
+    non_camel_case_types,
+    unused_imports
+)]
+/*
+ * Mayastor RESTful API
+ *
+ * The version of the OpenAPI document: v0
+ *
+ * Generated by: https://github.com/openebs/openapi-generator
+ */
+
+use crate::apis::IntoVec;
+
+/// Nexuses : A page of nexuses.
+
+/// A page of nexuses.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Nexuses {
+    /// Token to get the next page with, if there are more entries
+    #[serde(rename = "continuation_token", skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    /// The nexuses in this page
+    #[serde(rename = "entries")]
+    pub entries: Vec<crate::models::Nexus>,
+}
+
+impl Nexuses {
+    /// Nexuses using only the required fields
+    pub fn new(entries: impl IntoVec<crate::models::Nexus>) -> Nexuses {
+        Nexuses {
+            continuation_token: None,
+            entries: entries.into_vec(),
+        }
+    }
+    /// Nexuses using all fields
+    pub fn new_all(
+        continuation_token: impl Into<Option<String>>,
+        entries: impl IntoVec<crate::models::Nexus>,
+    ) -> Nexuses {
+        Nexuses {
+            continuation_token: continuation_token.into(),
+            entries: entries.into_vec(),
+        }
+    }
+}
@@ -19,6 +19,9 @@ use crate::apis::IntoVec;
 /// Node information
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Node {
+    /// capabilities supported by the mayastor instance
+    #[serde(rename = "capabilities", skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<String>>,
     /// grpc_endpoint of the mayastor instance
     #[serde(rename = "grpcEndpoint")]
     pub grpc_endpoint: String,
@@ -27,6 +30,9 @@ pub struct Node {
     pub id: String,
     #[serde(rename = "state")]
     pub state: crate::models::NodeState,
+    /// software version of the mayastor instance
+    #[serde(rename = "version", skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
 }
 
 impl Node {
@@ -37,21 +43,27 @@ impl Node {
         state: impl Into<crate::models::NodeState>,
     ) -> Node {
         Node {
+            capabilities: None,
             grpc_endpoint: grpc_endpoint.into(),
             id: id.into(),
             state: state.into(),
+            version: None,
         }
     }
     /// Node using all fields
     pub fn new_all(
+        capabilities: impl Into<Option<Vec<String>>>,
         grpc_endpoint: impl Into<String>,
         id: impl Into<String>,
         state: impl Into<crate::models::NodeState>,
+        version: impl Into<Option<String>>,
     ) -> Node {
         Node {
+            capabilities: capabilities.into(),
             grpc_endpoint: grpc_endpoint.into(),
             id: id.into(),
             state: state.into(),
+            version: version.into(),
         }
     }
 }
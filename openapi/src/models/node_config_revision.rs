@@ -0,0 +1,70 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::new_without_default,
+    non_camel_case_types,
+    unused_imports
+)]
+/*
+ * Mayastor RESTful API
+ *
+ * The version of the OpenAPI document: v0
+ *
+ * Generated by: https://github.com/openebs/openapi-generator
+ */
+
+use crate::apis::IntoVec;
+
+/// NodeConfigRevision : Numbered revision of a config of a node
+
+/// Numbered revision of a config of a node
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeConfigRevision {
+    /// base64 encoded config data, only returned when a single revision is requested
+    #[serde(rename = "data", skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    /// revision number, incremented with every update of the config
+    #[serde(rename = "revision")]
+    pub revision: i64,
+    /// the earlier revision which this one restored, if it's a rollback
+    #[serde(rename = "rollbackOf", skip_serializing_if = "Option::is_none")]
+    pub rollback_of: Option<i64>,
+    /// size of the config data in bytes
+    #[serde(rename = "size")]
+    pub size: i64,
+    /// when the revision was made (RFC 3339)
+    #[serde(rename = "timestamp")]
+    pub timestamp: String,
+}
+
+impl NodeConfigRevision {
+    /// NodeConfigRevision using only the required fields
+    pub fn new(
+        revision: impl Into<i64>,
+        size: impl Into<i64>,
+        timestamp: impl Into<String>,
+    ) -> NodeConfigRevision {
+        NodeConfigRevision {
+            data: None,
+            revision: revision.into(),
+            rollback_of: None,
+            size: size.into(),
+            timestamp: timestamp.into(),
+        }
+    }
+    /// NodeConfigRevision using all fields
+    pub fn new_all(
+        data: impl Into<Option<String>>,
+        revision: impl Into<i64>,
+        rollback_of: impl Into<Option<i64>>,
+        size: impl Into<i64>,
+        timestamp: impl Into<String>,
+    ) -> NodeConfigRevision {
+        NodeConfigRevision {
+            data: data.into(),
+            revision: revision.into(),
+            rollback_of: rollback_of.into(),
+            size: size.into(),
+            timestamp: timestamp.into(),
+        }
+    }
+}
@@ -25,6 +25,10 @@ pub enum NodeState {
     Online,
     #[serde(rename = "Offline")]
     Offline,
+    #[serde(rename = "Suspect")]
+    Suspect,
+    #[serde(rename = "Flapping")]
+    Flapping,
 }
 
 impl ToString for NodeState {
@@ -33,6 +37,8 @@ impl ToString for NodeState {
             Self::Unknown => String::from("Unknown"),
             Self::Online => String::from("Online"),
             Self::Offline => String::from("Offline"),
+            Self::Suspect => String::from("Suspect"),
+            Self::Flapping => String::from("Flapping"),
         }
     }
 }
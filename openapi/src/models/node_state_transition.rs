@@ -0,0 +1,56 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::new_without_default,
+    non_camel_case_types,
+    unused_imports
+)]
+/*
+ * Mayastor RESTful API
+ *
+ * The version of the OpenAPI document: v0
+ *
+ * Generated by: https://github.com/openebs/openapi-generator
+ */
+
+use crate::apis::IntoVec;
+
+/// NodeStateTransition : Transition of a node from one state to another
+
+/// Transition of a node from one state to another
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NodeStateTransition {
+    #[serde(rename = "from")]
+    pub from: crate::models::NodeState,
+    /// when the node changed state (RFC 3339)
+    #[serde(rename = "timestamp")]
+    pub timestamp: String,
+    #[serde(rename = "to")]
+    pub to: crate::models::NodeState,
+}
+
+impl NodeStateTransition {
+    /// NodeStateTransition using only the required fields
+    pub fn new(
+        from: impl Into<crate::models::NodeState>,
+        timestamp: impl Into<String>,
+        to: impl Into<crate::models::NodeState>,
+    ) -> NodeStateTransition {
+        NodeStateTransition {
+            from: from.into(),
+            timestamp: timestamp.into(),
+            to: to.into(),
+        }
+    }
+    /// NodeStateTransition using all fields
+    pub fn new_all(
+        from: impl Into<crate::models::NodeState>,
+        timestamp: impl Into<String>,
+        to: impl Into<crate::models::NodeState>,
+    ) -> NodeStateTransition {
+        NodeStateTransition {
+            from: from.into(),
+            timestamp: timestamp.into(),
+            to: to.into(),
+        }
+    }
+}
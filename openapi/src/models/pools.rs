@@ -0,0 +1,48 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::new_without_default,
+    non_camel_case_types,
+    unused_imports
+)]
+/*
+ * Mayastor RESTful API
+ *
+ * The version of the OpenAPI document: v0
+ *
+ * Generated by: https://github.com/openebs/openapi-generator
+ */
+
+use crate::apis::IntoVec;
+
+/// Pools : A page of pools.
+
+/// A page of pools.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pools {
+    /// Token to get the next page with, if there are more entries
+    #[serde(rename = "continuation_token", skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+    /// The pools in this page
+    #[serde(rename = "entries")]
+    pub entries: Vec<crate::models::Pool>,
+}
+
+impl Pools {
+    /// Pools using only the required fields
+    pub fn new(entries: impl IntoVec<crate::models::Pool>) -> Pools {
+        Pools {
+            continuation_token: None,
+            entries: entries.into_vec(),
+        }
+    }
+    /// Pools using all fields
+    pub fn new_all(
+        continuation_token: impl Into<Option<String>>,
+        entries: impl IntoVec<crate::models::Pool>,
+    ) -> Pools {
+        Pools {
+            continuation_token: continuation_token.into(),
+            entries: entries.into_vec(),
+        }
+    }
+}
@@ -22,6 +22,9 @@ pub struct RestWatch {
     /// callback used to notify the watcher of a change
     #[serde(rename = "callback")]
     pub callback: String,
+    /// delivery status of the notifications of the watch
+    #[serde(rename = "delivery", skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<crate::models::WatchDelivery>,
     /// id of the resource to watch on
     #[serde(rename = "resource")]
     pub resource: String,
@@ -32,13 +35,19 @@ impl RestWatch {
     pub fn new(callback: impl Into<String>, resource: impl Into<String>) -> RestWatch {
         RestWatch {
             callback: callback.into(),
+            delivery: None,
             resource: resource.into(),
         }
     }
     /// RestWatch using all fields
-    pub fn new_all(callback: impl Into<String>, resource: impl Into<String>) -> RestWatch {
+    pub fn new_all(
+        callback: impl Into<String>,
+        delivery: impl Into<Option<crate::models::WatchDelivery>>,
+        resource: impl Into<String>,
+    ) -> RestWatch {
         RestWatch {
             callback: callback.into(),
+            delivery: delivery.into(),
             resource: resource.into(),
         }
     }
@@ -19,6 +19,9 @@ use crate::apis::IntoVec;
 /// Specs detailing the requested configuration of the objects.
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Specs {
+    /// Token to get the next page of specs with, if there are more specs
+    #[serde(rename = "continuation_token", skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
     /// Nexus Specs
     #[serde(rename = "nexuses")]
     pub nexuses: Vec<crate::models::NexusSpec>,
@@ -42,6 +45,7 @@ impl Specs {
         volumes: impl IntoVec<crate::models::VolumeSpec>,
     ) -> Specs {
         Specs {
+            continuation_token: None,
             nexuses: nexuses.into_vec(),
             pools: pools.into_vec(),
             replicas: replicas.into_vec(),
@@ -50,12 +54,14 @@ impl Specs {
     }
     /// Specs using all fields
     pub fn new_all(
+        continuation_token: impl Into<Option<String>>,
         nexuses: impl IntoVec<crate::models::NexusSpec>,
         pools: impl IntoVec<crate::models::PoolSpec>,
         replicas: impl IntoVec<crate::models::ReplicaSpec>,
         volumes: impl IntoVec<crate::models::VolumeSpec>,
     ) -> Specs {
         Specs {
+            continuation_token: continuation_token.into(),
             nexuses: nexuses.into_vec(),
             pools: pools.into_vec(),
             replicas: replicas.into_vec(),
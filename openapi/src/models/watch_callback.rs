@@ -21,4 +21,6 @@ use crate::apis::IntoVec;
 pub enum WatchCallback {
     #[serde(rename = "uri")]
     uri(String),
+    #[serde(rename = "subject")]
+    subject(String),
 }
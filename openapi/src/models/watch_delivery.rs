@@ -0,0 +1,64 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::new_without_default,
+    non_camel_case_types,
+    unused_imports
+)]
+/*
+ * Mayastor RESTful API
+ *
+ * The version of the OpenAPI document: v0
+ *
+ * Generated by: https://github.com/openebs/openapi-generator
+ */
+
+use crate::apis::IntoVec;
+
+/// WatchDelivery : Delivery status of the notifications of a watch
+
+/// Delivery status of the notifications of a watch
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchDelivery {
+    /// number of notifications which were given up, either because they were older than the maximum age or because the queue was full
+    #[serde(rename = "deadLetters")]
+    pub dead_letters: i64,
+    /// error of the last failed delivery attempt, if the delivery is retrying
+    #[serde(rename = "lastError", skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// number of notifications queued for delivery
+    #[serde(rename = "pending")]
+    pub pending: i64,
+    /// overall status of the delivery
+    #[serde(rename = "status")]
+    pub status: crate::models::WatchDeliveryStatus,
+}
+
+impl WatchDelivery {
+    /// WatchDelivery using only the required fields
+    pub fn new(
+        dead_letters: impl Into<i64>,
+        pending: impl Into<i64>,
+        status: impl Into<crate::models::WatchDeliveryStatus>,
+    ) -> WatchDelivery {
+        WatchDelivery {
+            dead_letters: dead_letters.into(),
+            last_error: None,
+            pending: pending.into(),
+            status: status.into(),
+        }
+    }
+    /// WatchDelivery using all fields
+    pub fn new_all(
+        dead_letters: impl Into<i64>,
+        last_error: impl Into<Option<String>>,
+        pending: impl Into<i64>,
+        status: impl Into<crate::models::WatchDeliveryStatus>,
+    ) -> WatchDelivery {
+        WatchDelivery {
+            dead_letters: dead_letters.into(),
+            last_error: last_error.into(),
+            pending: pending.into(),
+            status: status.into(),
+        }
+    }
+}
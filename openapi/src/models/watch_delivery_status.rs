@@ -0,0 +1,44 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::new_without_default,
+    non_camel_case_types,
+    unused_imports
+)]
+/*
+ * Mayastor RESTful API
+ *
+ * The version of the OpenAPI document: v0
+ *
+ * Generated by: https://github.com/openebs/openapi-generator
+ */
+
+use crate::apis::IntoVec;
+
+/// WatchDeliveryStatus : Status of the delivery of the notifications of a watch: ok, retrying the oldest pending notification, or dead lettered when some notifications were given up
+
+/// Status of the delivery of the notifications of a watch: ok, retrying the oldest pending notification, or dead lettered when some notifications were given up
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum WatchDeliveryStatus {
+    #[serde(rename = "ok")]
+    Ok,
+    #[serde(rename = "retrying")]
+    Retrying,
+    #[serde(rename = "deadLettered")]
+    DeadLettered,
+}
+
+impl ToString for WatchDeliveryStatus {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Ok => String::from("ok"),
+            Self::Retrying => String::from("retrying"),
+            Self::DeadLettered => String::from("deadLettered"),
+        }
+    }
+}
+
+impl Default for WatchDeliveryStatus {
+    fn default() -> Self {
+        Self::Ok
+    }
+}
@@ -0,0 +1,41 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::new_without_default,
+    non_camel_case_types,
+    unused_imports
+)]
+/*
+ * Mayastor RESTful API
+ *
+ * The version of the OpenAPI document: v0
+ *
+ * Generated by: https://github.com/openebs/openapi-generator
+ */
+
+use crate::apis::IntoVec;
+
+/// WatchEventType : Type of change of a watched resource
+
+/// Type of change of a watched resource
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum WatchEventType {
+    #[serde(rename = "put")]
+    Put,
+    #[serde(rename = "delete")]
+    Delete,
+}
+
+impl ToString for WatchEventType {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Put => String::from("put"),
+            Self::Delete => String::from("delete"),
+        }
+    }
+}
+
+impl Default for WatchEventType {
+    fn default() -> Self {
+        Self::Put
+    }
+}
@@ -0,0 +1,77 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::new_without_default,
+    non_camel_case_types,
+    unused_imports
+)]
+/*
+ * Mayastor RESTful API
+ *
+ * The version of the OpenAPI document: v0
+ *
+ * Generated by: https://github.com/openebs/openapi-generator
+ */
+
+use crate::apis::IntoVec;
+
+/// WatchNotification : Notification sent to the callback of a watch when the watched resource changes
+
+/// Notification sent to the callback of a watch when the watched resource changes
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchNotification {
+    /// type of the change
+    #[serde(rename = "eventType")]
+    pub event_type: crate::models::WatchEventType,
+    /// id of the watched resource
+    #[serde(rename = "resource")]
+    pub resource: String,
+    /// revision of the store at which the change occurred, if known
+    #[serde(rename = "revision", skip_serializing_if = "Option::is_none")]
+    pub revision: Option<i64>,
+    /// new value of the resource, unless it was deleted
+    #[serde(rename = "value", skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    /// version of the notification schema, eg: v0
+    #[serde(rename = "version")]
+    pub version: String,
+    /// whether the desired or the actual state of the resource changed
+    #[serde(rename = "watchType")]
+    pub watch_type: crate::models::WatchType,
+}
+
+impl WatchNotification {
+    /// WatchNotification using only the required fields
+    pub fn new(
+        event_type: impl Into<crate::models::WatchEventType>,
+        resource: impl Into<String>,
+        version: impl Into<String>,
+        watch_type: impl Into<crate::models::WatchType>,
+    ) -> WatchNotification {
+        WatchNotification {
+            event_type: event_type.into(),
+            resource: resource.into(),
+            revision: None,
+            value: None,
+            version: version.into(),
+            watch_type: watch_type.into(),
+        }
+    }
+    /// WatchNotification using all fields
+    pub fn new_all(
+        event_type: impl Into<crate::models::WatchEventType>,
+        resource: impl Into<String>,
+        revision: impl Into<Option<i64>>,
+        value: impl Into<Option<serde_json::Value>>,
+        version: impl Into<String>,
+        watch_type: impl Into<crate::models::WatchType>,
+    ) -> WatchNotification {
+        WatchNotification {
+            event_type: event_type.into(),
+            resource: resource.into(),
+            revision: revision.into(),
+            value: value.into(),
+            version: version.into(),
+            watch_type: watch_type.into(),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+#![allow(
+    clippy::too_many_arguments,
+    clippy::new_without_default,
+    non_camel_case_types,
+    unused_imports
+)]
+/*
+ * Mayastor RESTful API
+ *
+ * The version of the OpenAPI document: v0
+ *
+ * Generated by: https://github.com/openebs/openapi-generator
+ */
+
+use crate::apis::IntoVec;
+
+/// WatchType : Type of state of a watched resource: the desired state, the actual state or both
+
+/// Type of state of a watched resource: the desired state, the actual state or both
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum WatchType {
+    #[serde(rename = "desired")]
+    Desired,
+    #[serde(rename = "actual")]
+    Actual,
+    #[serde(rename = "all")]
+    All,
+}
+
+impl ToString for WatchType {
+    fn to_string(&self) -> String {
+        match self {
+            Self::Desired => String::from("desired"),
+            Self::Actual => String::from("actual"),
+            Self::All => String::from("all"),
+        }
+    }
+}
+
+impl Default for WatchType {
+    fn default() -> Self {
+        Self::Desired
+    }
+}
@@ -273,6 +273,12 @@ impl ClusterBuilder {
         self.opts = self.opts.with_node_deadline(deadline);
         self
     }
+    /// Specify the node grace period (Suspect before Offline) for the core
+    /// agent, eg: 2s
+    pub fn with_node_grace_period(mut self, grace: &str) -> Self {
+        self.opts = self.opts.with_node_grace_period(grace);
+        self
+    }
     /// With reconcile periods:
     /// `busy` for when there's work that needs to be retried on the next poll
     /// `idle` when there's no work pending